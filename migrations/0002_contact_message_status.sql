@@ -0,0 +1,7 @@
+ALTER TABLE contact_messages ADD COLUMN status TEXT NOT NULL DEFAULT 'NEW';
+ALTER TABLE contact_messages ADD COLUMN updated_at BIGINT;
+ALTER TABLE contact_messages ADD COLUMN resolved_at BIGINT;
+
+UPDATE contact_messages SET updated_at = created_at;
+
+CREATE INDEX idx_contact_messages_status ON contact_messages(status);
@@ -0,0 +1 @@
+ALTER TABLE contact_messages ADD COLUMN spam_score INTEGER;
@@ -0,0 +1,3 @@
+ALTER TABLE contact_messages ADD COLUMN fingerprint TEXT;
+
+CREATE INDEX idx_contact_messages_fingerprint ON contact_messages(fingerprint, created_at);
@@ -0,0 +1,18 @@
+CREATE TABLE sites (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    -- JSON array of origins allowed to submit; empty allows any
+    allowed_origins TEXT NOT NULL DEFAULT '[]',
+    -- Overrides TURNSTILE_SECRET_KEY for this site's widget
+    turnstile_secret_key TEXT,
+    -- JSON array of accepted category names; empty accepts all
+    categories TEXT NOT NULL DEFAULT '[]',
+    -- Same format as NOTIFICATION_RECIPIENTS; empty uses the global routing
+    notification_recipients TEXT NOT NULL DEFAULT '',
+    created_at BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
+
+ALTER TABLE contact_messages ADD COLUMN site_id TEXT REFERENCES sites(id);
+
+CREATE INDEX idx_contact_messages_site_id ON contact_messages(site_id, created_at);
@@ -0,0 +1,12 @@
+CREATE TABLE forms (
+    id TEXT PRIMARY KEY,
+    -- The site the form belongs to; NULL lets any site use it
+    site_id TEXT REFERENCES sites(id),
+    name TEXT NOT NULL,
+    -- JSON form schema: {"fields": [...], "allow_additional_fields": false}
+    schema TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
+
+ALTER TABLE contact_messages ADD COLUMN form_id TEXT REFERENCES forms(id);
@@ -0,0 +1,23 @@
+CREATE TABLE categories (
+    -- Uppercase letters, digits and underscores, matched case-insensitively on submission
+    slug TEXT PRIMARY KEY,
+    display_name TEXT NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    -- Inactive categories keep their messages but reject new submissions
+    active INTEGER NOT NULL DEFAULT 1,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    -- LOW, NORMAL, HIGH or URGENT, given to messages filed under the category
+    default_priority TEXT NOT NULL DEFAULT 'NORMAL',
+    created_at BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
+
+INSERT INTO categories
+    (slug, display_name, description, active, sort_order, default_priority, created_at, updated_at)
+VALUES
+    ('ERROR', 'Error report', 'Something is broken or not working as expected', 1, 10, 'HIGH', unixepoch(), unixepoch()),
+    ('IDEA', 'Idea', 'A feature request or suggestion', 1, 20, 'NORMAL', unixepoch(), unixepoch()),
+    ('TESTIMONIAL', 'Testimonial', 'Feedback we may share with others', 1, 30, 'LOW', unixepoch(), unixepoch()),
+    ('OTHER', 'Other', 'Anything else', 1, 40, 'NORMAL', unixepoch(), unixepoch());
+
+ALTER TABLE contact_messages ADD COLUMN priority TEXT NOT NULL DEFAULT 'NORMAL';
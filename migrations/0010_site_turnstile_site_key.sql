@@ -0,0 +1,2 @@
+-- Public key of the site's own Turnstile widget; overrides TURNSTILE_SITE_KEY
+ALTER TABLE sites ADD COLUMN turnstile_site_key TEXT;
@@ -0,0 +1,3 @@
+ALTER TABLE contact_messages ADD COLUMN previous_messages INTEGER;
+
+CREATE INDEX idx_contact_messages_email ON contact_messages(lower(email), created_at);
@@ -0,0 +1,6 @@
+CREATE TABLE redeemed_form_tokens (
+    nonce TEXT PRIMARY KEY,
+    expires_at BIGINT NOT NULL
+);
+
+CREATE INDEX idx_redeemed_form_tokens_expires_at ON redeemed_form_tokens(expires_at);
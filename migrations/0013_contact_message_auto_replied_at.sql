@@ -0,0 +1 @@
+ALTER TABLE contact_messages ADD COLUMN auto_replied_at BIGINT;
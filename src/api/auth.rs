@@ -0,0 +1,66 @@
+use std::future::Future;
+
+use worker::{Request, Response, RouteContext, console_error};
+
+use crate::{
+    api::schemas::problem::Problem,
+    application::{
+        authentication_service::{Credentials, Principal, Scope},
+        exceptions::AppError,
+    },
+    setup::app_state::AppState,
+};
+
+/// Wraps a route handler so it only runs for callers holding `scope`.
+///
+/// Callers authenticate with either a bearer API key or, when configured, a
+/// Cloudflare Access JWT. The resulting `Principal` is passed to the handler.
+///
+/// Missing or invalid credentials produce a 401, valid credentials without
+/// the required scope produce a 403.
+pub async fn authenticated<F, Fut>(
+    req: Request,
+    ctx: RouteContext<()>,
+    scope: Scope,
+    handler: F,
+) -> worker::Result<Response>
+where
+    F: FnOnce(Request, RouteContext<()>, AppState, Principal) -> Fut,
+    Fut: Future<Output = worker::Result<Response>>,
+{
+    let app_state = match AppState::from_env(&ctx.env) {
+        Ok(state) => state,
+        Err(e) => {
+            console_error!("Failed to create app state: {:?}", e);
+            return Problem::internal_error().to_response();
+        }
+    };
+
+    let credentials = Credentials {
+        authorization: req.headers().get("Authorization").ok().flatten(),
+        access_jwt: req.headers().get("Cf-Access-Jwt-Assertion").ok().flatten(),
+    };
+
+    let principal = match app_state
+        .authentication_service
+        .authenticate(&credentials)
+        .await
+        .and_then(|principal| principal.require(scope).map(|_| principal))
+    {
+        Ok(principal) => principal,
+        Err(e) => {
+            console_error!("Authentication failed: {:?}", e);
+            return auth_failure_response(e);
+        }
+    };
+
+    handler(req, ctx, app_state, principal).await
+}
+
+fn auth_failure_response(error: AppError) -> worker::Result<Response> {
+    let mut response = Problem::from(&error).to_response()?;
+    if matches!(error, AppError::Unauthorised(_)) {
+        response.headers_mut().set("WWW-Authenticate", "Bearer")?;
+    }
+    Ok(response)
+}
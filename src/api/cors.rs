@@ -1,5 +1,7 @@
 use worker::Response;
 
+use crate::domain::entity::Site;
+
 pub struct CorsHeaders {
     allowed_origins: Vec<String>,
 }
@@ -9,6 +11,14 @@ impl CorsHeaders {
         Self { allowed_origins }
     }
 
+    /// Uses the site's own origins when the request belongs to a site.
+    pub fn for_site(site: Option<&Site>, allowed_origins: Vec<String>) -> Self {
+        match site {
+            Some(site) => Self::new(site.allowed_origins.clone()),
+            None => Self::new(allowed_origins),
+        }
+    }
+
     pub fn is_allowed(&self, origin: &Option<String>) -> bool {
         if self.allowed_origins.is_empty() {
             return true;
@@ -31,10 +41,15 @@ impl CorsHeaders {
                 .set("Access-Control-Allow-Origin", &origin_value)?;
             response
                 .headers_mut()
-                .set("Access-Control-Allow-Methods", "POST, OPTIONS")?;
-            response
-                .headers_mut()
-                .set("Access-Control-Allow-Headers", "Content-Type")?;
+                .set("Access-Control-Allow-Methods", "GET, POST, OPTIONS")?;
+            response.headers_mut().set(
+                "Access-Control-Allow-Headers",
+                "Content-Type, Idempotency-Key",
+            )?;
+            response.headers_mut().set(
+                "Access-Control-Expose-Headers",
+                "Retry-After, Idempotent-Replayed, ETag",
+            )?;
         }
         Ok(response)
     }
@@ -1,4 +1,6 @@
+pub mod auth;
 pub mod cors;
 pub mod router;
 pub mod routes;
 pub mod schemas;
+pub mod site;
@@ -1,20 +1,68 @@
 use worker::*;
 
 use crate::api::routes::{
-    contact_commands::create_contact_message_handler, health_check::health_check_handler,
+    categories::{
+        create_category_handler, delete_category_handler, get_category_handler,
+        list_categories_handler, update_category_handler,
+    },
+    contact_commands::{
+        create_contact_message_handler, preflight_handler, update_contact_message_status_handler,
+    },
+    contact_queries::{get_contact_message_handler, list_contact_messages_handler},
+    form_config::form_config_handler,
+    form_token::form_token_handler,
+    health_check::health_check_handler,
 };
-use crate::{api::cors::CorsHeaders, setup::config::Config};
+use crate::{api::auth::authenticated, application::authentication_service::Scope};
 
 pub fn create_router() -> Router<'static, ()> {
     let router = Router::new();
     router
         .get_async("/api/v1/health-check/", health_check_handler)
+        .get_async("/api/v1/contact-us/", |req, ctx| {
+            authenticated(req, ctx, Scope::Read, list_contact_messages_handler)
+        })
         .post_async("/api/v1/contact-us/", create_contact_message_handler)
-        .options("/api/v1/contact-us/", |req, ctx| {
-            let config = Config::from_env(&ctx.env)?;
-            let cors = CorsHeaders::new(config.allowed_origins);
-            let origin = req.headers().get("Origin").ok().flatten();
-
-            cors.preflight_response(origin)
+        .post_async(
+            "/api/v1/sites/:site_id/contact-us/",
+            create_contact_message_handler,
+        )
+        .get_async("/api/v1/contact-us/form-token/", form_token_handler)
+        .get_async(
+            "/api/v1/sites/:site_id/contact-us/form-token/",
+            form_token_handler,
+        )
+        .get_async("/api/v1/contact-us/config/", form_config_handler)
+        .get_async(
+            "/api/v1/sites/:site_id/contact-us/config/",
+            form_config_handler,
+        )
+        .get_async("/api/v1/contact-us/:id/", |req, ctx| {
+            authenticated(req, ctx, Scope::Read, get_contact_message_handler)
+        })
+        .patch_async("/api/v1/contact-us/:id/status/", |req, ctx| {
+            authenticated(
+                req,
+                ctx,
+                Scope::Write,
+                update_contact_message_status_handler,
+            )
+        })
+        .get_async("/api/v1/categories/", |req, ctx| {
+            authenticated(req, ctx, Scope::Read, list_categories_handler)
+        })
+        .post_async("/api/v1/categories/", |req, ctx| {
+            authenticated(req, ctx, Scope::Write, create_category_handler)
+        })
+        .get_async("/api/v1/categories/:slug/", |req, ctx| {
+            authenticated(req, ctx, Scope::Read, get_category_handler)
+        })
+        .put_async("/api/v1/categories/:slug/", |req, ctx| {
+            authenticated(req, ctx, Scope::Write, update_category_handler)
+        })
+        .delete_async("/api/v1/categories/:slug/", |req, ctx| {
+            authenticated(req, ctx, Scope::Write, delete_category_handler)
         })
+        .options_async("/api/v1/contact-us/", preflight_handler)
+        .options_async("/api/v1/sites/:site_id/contact-us/", preflight_handler)
 }
@@ -0,0 +1,159 @@
+use crate::{
+    api::schemas::{
+        problem::Problem,
+        requests::{CreateCategoryRequest, UpdateCategoryRequest},
+        responses::{ApiResponse, CategoryListResponse, CategoryResponse},
+    },
+    application::{authentication_service::Principal, category_service::CategoryFields},
+    setup::app_state::AppState,
+};
+use worker::{Request, Response, RouteContext, console_error, console_log};
+
+impl From<UpdateCategoryRequest> for CategoryFields {
+    fn from(request: UpdateCategoryRequest) -> Self {
+        Self {
+            display_name: request.display_name,
+            description: request.description,
+            active: request.active,
+            sort_order: request.sort_order,
+            default_priority: request.default_priority,
+        }
+    }
+}
+
+pub async fn list_categories_handler(
+    _req: Request,
+    _ctx: RouteContext<()>,
+    app_state: AppState,
+    _principal: Principal,
+) -> worker::Result<Response> {
+    match app_state.category_service.list().await {
+        Ok(categories) => ApiResponse::success(CategoryListResponse {
+            categories: categories.into_iter().map(Into::into).collect(),
+        })
+        .to_response(),
+        Err(e) => {
+            console_error!("Failed to list categories: {:?}", e);
+            Problem::from(&e).to_response()
+        }
+    }
+}
+
+pub async fn get_category_handler(
+    _req: Request,
+    ctx: RouteContext<()>,
+    app_state: AppState,
+    _principal: Principal,
+) -> worker::Result<Response> {
+    let Some(slug) = ctx.param("slug") else {
+        return missing_slug();
+    };
+
+    match app_state.category_service.get(slug).await {
+        Ok(category) => ApiResponse::success(CategoryResponse::from(category)).to_response(),
+        Err(e) => {
+            console_error!("Failed to get category: {:?}", e);
+            Problem::from(&e).to_response()
+        }
+    }
+}
+
+pub async fn create_category_handler(
+    mut req: Request,
+    _ctx: RouteContext<()>,
+    app_state: AppState,
+    principal: Principal,
+) -> worker::Result<Response> {
+    let payload: CreateCategoryRequest = match req.json().await {
+        Ok(p) => p,
+        Err(e) => {
+            console_error!("Failed to parse request body: {:?}", e);
+            return Problem::invalid_body().to_response();
+        }
+    };
+
+    match app_state
+        .category_service
+        .create_category(&payload.slug, payload.fields.into())
+        .await
+    {
+        Ok(category) => {
+            console_log!(
+                "Category {} created by {}",
+                category.slug,
+                principal.subject
+            );
+            ApiResponse::success(CategoryResponse::from(category)).to_response()
+        }
+        Err(e) => {
+            console_error!("Failed to create category: {:?}", e);
+            Problem::from(&e).to_response()
+        }
+    }
+}
+
+pub async fn update_category_handler(
+    mut req: Request,
+    ctx: RouteContext<()>,
+    app_state: AppState,
+    principal: Principal,
+) -> worker::Result<Response> {
+    let Some(slug) = ctx.param("slug") else {
+        return missing_slug();
+    };
+
+    let payload: UpdateCategoryRequest = match req.json().await {
+        Ok(p) => p,
+        Err(e) => {
+            console_error!("Failed to parse request body: {:?}", e);
+            return Problem::invalid_body().to_response();
+        }
+    };
+
+    match app_state
+        .category_service
+        .update_category(slug, payload.into())
+        .await
+    {
+        Ok(category) => {
+            console_log!(
+                "Category {} updated by {}",
+                category.slug,
+                principal.subject
+            );
+            ApiResponse::success(CategoryResponse::from(category)).to_response()
+        }
+        Err(e) => {
+            console_error!("Failed to update category: {:?}", e);
+            Problem::from(&e).to_response()
+        }
+    }
+}
+
+pub async fn delete_category_handler(
+    _req: Request,
+    ctx: RouteContext<()>,
+    app_state: AppState,
+    principal: Principal,
+) -> worker::Result<Response> {
+    let Some(slug) = ctx.param("slug") else {
+        return missing_slug();
+    };
+
+    match app_state.category_service.delete_category(slug).await {
+        Ok(()) => {
+            console_log!("Category {} deleted by {}", slug, principal.subject);
+            ApiResponse::success(true).to_response()
+        }
+        Err(e) => {
+            console_error!("Failed to delete category: {:?}", e);
+            Problem::from(&e).to_response()
+        }
+    }
+}
+
+fn missing_slug() -> worker::Result<Response> {
+    Problem::new(400, "missing_slug")
+        .with_detail("Missing category slug")
+        .to_response()
+}
@@ -1,12 +1,23 @@
 use crate::{
     api::{
         cors::CorsHeaders,
-        schemas::{requests::CreateContactMessageRequest, responses::ApiResponse},
+        schemas::{
+            problem::Problem,
+            requests::{CreateContactMessageRequest, UpdateContactMessageStatusRequest},
+            responses::{ApiResponse, ContactMessageResponse},
+        },
+        site::resolve_site,
     },
-    application::exceptions::AppError,
+    application::{
+        authentication_service::Principal, contact_message_service::NewContactMessage,
+        rate_limiter::RateLimitDecision, request_validation_service::VerificationRequest,
+    },
+    domain::entity::IdempotencyKey,
     setup::{app_state::AppState, config::Config},
 };
-use worker::{Request, Response, RouteContext, console_error, console_log};
+use worker::{
+    Request, Response, RouteContext, Url, console_error, console_log, js_sys, wasm_bindgen::JsValue,
+};
 
 pub async fn create_contact_message_handler(
     mut req: Request,
@@ -15,75 +26,259 @@ pub async fn create_contact_message_handler(
     let origin: Option<String> = req.headers().get("Origin").ok().flatten();
 
     let config = Config::from_env(&ctx.env).map_err(worker::Error::RustError)?;
-    let cors = CorsHeaders::new(config.allowed_origins);
+
+    let app_state = match AppState::from_env(&ctx.env) {
+        Ok(state) => state,
+        Err(e) => {
+            console_error!("Failed to create app state: {:?}", e);
+            let response = Problem::internal_error().to_response()?;
+            return CorsHeaders::new(config.allowed_origins).add_to_response(response, origin);
+        }
+    };
+
+    let site = match resolve_site(&app_state, &ctx, &origin).await {
+        Ok(site) => site,
+        Err(problem) => {
+            let response = problem.to_response()?;
+            return CorsHeaders::new(config.allowed_origins).add_to_response(response, origin);
+        }
+    };
+    let cors = CorsHeaders::for_site(site.as_ref(), config.allowed_origins);
 
     if !cors.is_allowed(&origin) {
         console_error!("Blocked unauthorised origin: {:?}", origin);
-        return ApiResponse::<()>::failure(403, "Forbidden").to_response();
+        return Problem::origin_not_allowed().to_response();
     }
 
-    let payload: CreateContactMessageRequest = match req.json().await {
-        Ok(p) => p,
+    let is_form = is_form_post(&req);
+    let body = if is_form {
+        read_form(&mut req).await
+    } else {
+        req.json::<serde_json::Value>().await
+    };
+    let body = match body {
+        Ok(body) => body,
         Err(e) => {
             console_error!("Failed to parse request body: {:?}", e);
-            let response = ApiResponse::<()>::failure(400, "Invalid request body").to_response()?;
+            let response = Problem::invalid_body().to_response()?;
             return cors.add_to_response(response, origin);
         }
     };
 
-    let app_state = match AppState::from_env(&ctx.env) {
-        Ok(state) => state,
+    let idempotency_key = match req.headers().get("Idempotency-Key").ok().flatten() {
+        Some(key) => match IdempotencyKey::new(key, &body) {
+            Ok(key) => Some(key),
+            Err(e) => {
+                let response = Problem::from(&e).to_response()?;
+                return cors.add_to_response(response, origin);
+            }
+        },
+        None => None,
+    };
+
+    let payload: CreateContactMessageRequest = match serde_json::from_value(body) {
+        Ok(p) => p,
         Err(e) => {
-            console_error!("Failed to create app state: {:?}", e);
-            let response =
-                ApiResponse::<()>::failure(500, "Internal Server Error").to_response()?;
+            console_error!("Failed to parse request body: {:?}", e);
+            let response = Problem::invalid_body().to_response()?;
             return cors.add_to_response(response, origin);
         }
     };
 
-    let client_ip = req
-        .headers()
-        .get("CF-Connecting-IP")
-        .ok()
-        .flatten()
-        .unwrap_or_else(|| "0.0.0.0".to_string());
+    // Replays are answered before rate limiting and Turnstile, as a retried
+    // request carries the same single-use token as the original.
+    if let Some(key) = &idempotency_key {
+        match app_state.contact_message_service.replay(key).await {
+            Ok(Some(message_id)) => {
+                console_log!("Replaying response for message {}", message_id);
+                let mut response = success_response(is_form, &config.thank_you_url)?;
+                response.headers_mut().set("Idempotent-Replayed", "true")?;
+                return cors.add_to_response(response, origin);
+            }
+            Ok(None) => {}
+            Err(e) => {
+                console_error!("Failed to look up idempotency key: {:?}", e);
+                let response = Problem::from(&e).to_response()?;
+                return cors.add_to_response(response, origin);
+            }
+        }
+    }
 
-    if let Err(e) = app_state
-        .request_validation_service
-        .verify(payload.token, client_ip)
+    let client_ip = req.headers().get("CF-Connecting-IP").ok().flatten();
+
+    if let RateLimitDecision::Limited {
+        retry_after_seconds,
+    } = app_state
+        .rate_limit_service
+        .check_submission(client_ip.as_deref(), &payload.email)
         .await
     {
-        console_error!("Turnstile validation failed: {:?}", e);
-        let response =
-            ApiResponse::<()>::failure(401, "Request validation failed").to_response()?;
+        console_log!("Rate limited submission from {:?}", client_ip);
+        let mut response = Problem::new(429, "rate_limited")
+            .with_detail("Too many requests")
+            .to_response()?;
+        response
+            .headers_mut()
+            .set("Retry-After", &retry_after_seconds.to_string())?;
         return cors.add_to_response(response, origin);
     }
 
-    match app_state
-        .contact_message_service
-        .create_message(
-            payload.category,
-            payload.email,
-            payload.name,
-            payload.message,
-            payload.data,
-        )
+    let verification = VerificationRequest {
+        token: payload.token,
+        honeypot: payload.honeypot,
+        form_token: payload.form_token,
+        remote_ip: client_ip.unwrap_or_else(|| "0.0.0.0".to_string()),
+        site_secret_key: site
+            .as_ref()
+            .and_then(|site| site.turnstile_secret_key.clone()),
+    };
+    if let Err(e) = app_state
+        .request_validation_service
+        .verify(&verification)
         .await
     {
+        console_error!("Request validation failed: {:?}", e);
+        let response = Problem::from(&e).to_response()?;
+        return cors.add_to_response(response, origin);
+    }
+
+    let service = &app_state.contact_message_service;
+    let new_message = NewContactMessage {
+        category: payload.category,
+        email: payload.email,
+        name: payload.name,
+        message: payload.message,
+        data: payload.data,
+        form_id: payload.form_id,
+    };
+    let result = match idempotency_key {
+        Some(key) => service
+            .create_idempotent_message(key, new_message, site.as_ref())
+            .await
+            .map(|_| ()),
+        None => service.create_message(new_message, site.as_ref()).await,
+    };
+
+    match result {
         Ok(_) => {
             console_log!("Contact-us message created successfully.");
-            let response = ApiResponse::success(true).to_response()?;
+            let response = success_response(is_form, &config.thank_you_url)?;
             cors.add_to_response(response, origin)
         }
         Err(e) => {
             console_error!("Failed to create message: {:?}", e);
-            let response = match e {
-                AppError::ValidationError(msg) => {
-                    ApiResponse::<()>::failure(400, msg).to_response()?
-                }
-                _ => ApiResponse::<()>::failure(500, "Failed to save message").to_response()?,
-            };
+            let response = Problem::from(&e).to_response()?;
             cors.add_to_response(response, origin)
         }
     }
 }
+
+/// Answers preflight requests with the origins of the site being posted to,
+/// or the configured origins when no site matches.
+pub async fn preflight_handler(req: Request, ctx: RouteContext<()>) -> worker::Result<Response> {
+    let origin: Option<String> = req.headers().get("Origin").ok().flatten();
+    let config = Config::from_env(&ctx.env).map_err(worker::Error::RustError)?;
+
+    let app_state = match AppState::from_env(&ctx.env) {
+        Ok(state) => state,
+        Err(e) => {
+            console_error!("Failed to create app state: {:?}", e);
+            let response = Problem::internal_error().to_response()?;
+            return CorsHeaders::new(config.allowed_origins).add_to_response(response, origin);
+        }
+    };
+
+    match resolve_site(&app_state, &ctx, &origin).await {
+        Ok(site) => {
+            CorsHeaders::for_site(site.as_ref(), config.allowed_origins).preflight_response(origin)
+        }
+        Err(problem) => {
+            let response = problem.to_response()?;
+            CorsHeaders::new(config.allowed_origins).add_to_response(response, origin)
+        }
+    }
+}
+
+/// HTML forms post urlencoded or multipart bodies. Anything else is read as
+/// JSON, including `text/plain`, which scripts use to avoid a CORS preflight.
+fn is_form_post(req: &Request) -> bool {
+    req.headers()
+        .get("Content-Type")
+        .ok()
+        .flatten()
+        .map(|content_type| content_type.to_ascii_lowercase())
+        .is_some_and(|content_type| {
+            content_type.starts_with("application/x-www-form-urlencoded")
+                || content_type.starts_with("multipart/form-data")
+        })
+}
+
+async fn read_form(req: &mut Request) -> worker::Result<serde_json::Value> {
+    let form = req.form_data().await?;
+    let mut fields = vec![];
+    if let Some(entries) = js_sys::try_iter(&JsValue::from(form))? {
+        for entry in entries {
+            let entry = js_sys::Array::from(&entry?);
+            // File uploads have no string value and are ignored.
+            if let (Some(name), Some(value)) = (entry.get(0).as_string(), entry.get(1).as_string())
+            {
+                fields.push((name, value));
+            }
+        }
+    }
+    Ok(CreateContactMessageRequest::json_from_form(fields))
+}
+
+/// Browsers that posted a plain form are sent to the thank-you page, when one
+/// is configured, instead of being shown the JSON response.
+fn success_response(is_form: bool, thank_you_url: &Option<Url>) -> worker::Result<Response> {
+    match thank_you_url {
+        Some(url) if is_form => {
+            let mut response = Response::empty()?.with_status(303);
+            response.headers_mut().set("Location", url.as_str())?;
+            Ok(response)
+        }
+        _ => ApiResponse::success(true).to_response(),
+    }
+}
+
+pub async fn update_contact_message_status_handler(
+    mut req: Request,
+    ctx: RouteContext<()>,
+    app_state: AppState,
+    principal: Principal,
+) -> worker::Result<Response> {
+    let Some(id) = ctx.param("id") else {
+        return Problem::new(400, "missing_id")
+            .with_detail("Missing message id")
+            .to_response();
+    };
+
+    let payload: UpdateContactMessageStatusRequest = match req.json().await {
+        Ok(p) => p,
+        Err(e) => {
+            console_error!("Failed to parse request body: {:?}", e);
+            return Problem::invalid_body().to_response();
+        }
+    };
+
+    match app_state
+        .contact_message_service
+        .update_status(id, payload.status)
+        .await
+    {
+        Ok(message) => {
+            console_log!(
+                "Contact-us message {} moved to {} by {}",
+                message.id,
+                message.status,
+                principal.subject
+            );
+            ApiResponse::success(ContactMessageResponse::from(message)).to_response()
+        }
+        Err(e) => {
+            console_error!("Failed to update message status: {:?}", e);
+            Problem::from(&e).to_response()
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use crate::{
+    api::schemas::{
+        problem::Problem,
+        requests::ListContactMessagesQuery,
+        responses::{ApiResponse, ContactMessageListResponse, ContactMessageResponse},
+    },
+    application::authentication_service::Principal,
+    setup::app_state::AppState,
+};
+use worker::{Request, Response, RouteContext, console_error};
+
+pub async fn list_contact_messages_handler(
+    req: Request,
+    _ctx: RouteContext<()>,
+    app_state: AppState,
+    _principal: Principal,
+) -> worker::Result<Response> {
+    let query: ListContactMessagesQuery = match req.query() {
+        Ok(q) => q,
+        Err(e) => {
+            console_error!("Failed to parse query parameters: {:?}", e);
+            return Problem::new(400, "invalid_query")
+                .with_detail("Invalid query parameters")
+                .to_response();
+        }
+    };
+
+    let (limit, offset) = (query.limit(), query.offset());
+
+    match app_state
+        .contact_message_service
+        .list_messages(limit, offset)
+        .await
+    {
+        Ok(messages) => ApiResponse::success(ContactMessageListResponse {
+            messages: messages.into_iter().map(Into::into).collect(),
+            limit,
+            offset,
+        })
+        .to_response(),
+        Err(e) => {
+            console_error!("Failed to list messages: {:?}", e);
+            Problem::from(&e).to_response()
+        }
+    }
+}
+
+pub async fn get_contact_message_handler(
+    _req: Request,
+    ctx: RouteContext<()>,
+    app_state: AppState,
+    _principal: Principal,
+) -> worker::Result<Response> {
+    let Some(id) = ctx.param("id") else {
+        return Problem::new(400, "missing_id")
+            .with_detail("Missing message id")
+            .to_response();
+    };
+
+    match app_state.contact_message_service.get_message(id).await {
+        Ok(message) => ApiResponse::success(ContactMessageResponse::from(message)).to_response(),
+        Err(e) => {
+            console_error!("Failed to get message: {:?}", e);
+            Problem::from(&e).to_response()
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use crate::{
+    api::{
+        cors::CorsHeaders,
+        schemas::{
+            problem::Problem,
+            responses::{ApiResponse, FormConfigResponse},
+        },
+        site::resolve_site,
+    },
+    setup::{
+        app_state::AppState,
+        config::{CaptchaProvider, Config},
+    },
+};
+use worker::{Request, Response, RouteContext, console_error};
+
+/// How long clients and caches may reuse the config before revalidating.
+const MAX_AGE_SECONDS: u32 = 300;
+
+/// Describes the accepted categories, field limits and Turnstile site key,
+/// so clients can render and pre-validate forms without copying them.
+/// Answers `304 Not Modified` when `If-None-Match` carries the current ETag.
+pub async fn form_config_handler(req: Request, ctx: RouteContext<()>) -> worker::Result<Response> {
+    let origin: Option<String> = req.headers().get("Origin").ok().flatten();
+
+    let config = Config::from_env(&ctx.env).map_err(worker::Error::RustError)?;
+
+    let app_state = match AppState::from_env(&ctx.env) {
+        Ok(state) => state,
+        Err(e) => {
+            console_error!("Failed to create app state: {:?}", e);
+            let response = Problem::internal_error().to_response()?;
+            return CorsHeaders::new(config.allowed_origins).add_to_response(response, origin);
+        }
+    };
+
+    let site = match resolve_site(&app_state, &ctx, &origin).await {
+        Ok(site) => site,
+        Err(problem) => {
+            let response = problem.to_response()?;
+            return CorsHeaders::new(config.allowed_origins).add_to_response(response, origin);
+        }
+    };
+    let cors = CorsHeaders::for_site(site.as_ref(), config.allowed_origins);
+
+    if !cors.is_allowed(&origin) {
+        console_error!("Blocked unauthorised origin: {:?}", origin);
+        return Problem::origin_not_allowed().to_response();
+    }
+
+    let mut categories = match app_state.category_service.list_active().await {
+        Ok(categories) => categories,
+        Err(e) => {
+            console_error!("Failed to list categories: {:?}", e);
+            let response = Problem::from(&e).to_response()?;
+            return cors.add_to_response(response, origin);
+        }
+    };
+    if let Some(site) = &site {
+        categories.retain(|category| site.accepts_category(&category.slug));
+    }
+
+    let turnstile_site_key = match config.captcha_provider {
+        CaptchaProvider::Turnstile => site
+            .and_then(|site| site.turnstile_site_key)
+            .or(config.turnstile_site_key),
+        _ => None,
+    };
+    let body = FormConfigResponse::new(categories, turnstile_site_key);
+    let etag = body
+        .etag()
+        .map_err(|e| worker::Error::RustError(e.to_string()))?;
+
+    let if_none_match = req.headers().get("If-None-Match").ok().flatten();
+    let mut response = if FormConfigResponse::is_not_modified(if_none_match.as_deref(), &etag) {
+        Response::empty()?.with_status(304)
+    } else {
+        ApiResponse::success(body).to_response()?
+    };
+    response.headers_mut().set("ETag", &etag)?;
+    response.headers_mut().set(
+        "Cache-Control",
+        &format!("public, max-age={MAX_AGE_SECONDS}"),
+    )?;
+    // The CORS headers differ by origin.
+    response.headers_mut().set("Vary", "Origin")?;
+    cors.add_to_response(response, origin)
+}
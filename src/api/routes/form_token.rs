@@ -0,0 +1,59 @@
+use crate::{
+    api::{
+        cors::CorsHeaders,
+        schemas::{
+            problem::Problem,
+            responses::{ApiResponse, FormTokenResponse},
+        },
+        site::resolve_site,
+    },
+    setup::{app_state::AppState, config::Config},
+};
+use chrono::Utc;
+use worker::{Request, Response, RouteContext, console_error};
+
+/// Issues a signed render timestamp for forms using the honeypot check.
+/// Fetch it when the form is displayed and send it back as `form_token`.
+pub async fn form_token_handler(req: Request, ctx: RouteContext<()>) -> worker::Result<Response> {
+    let origin: Option<String> = req.headers().get("Origin").ok().flatten();
+
+    let config = Config::from_env(&ctx.env).map_err(worker::Error::RustError)?;
+
+    let app_state = match AppState::from_env(&ctx.env) {
+        Ok(state) => state,
+        Err(e) => {
+            console_error!("Failed to create app state: {:?}", e);
+            let response = Problem::internal_error().to_response()?;
+            return CorsHeaders::new(config.allowed_origins).add_to_response(response, origin);
+        }
+    };
+
+    let site = match resolve_site(&app_state, &ctx, &origin).await {
+        Ok(site) => site,
+        Err(problem) => {
+            let response = problem.to_response()?;
+            return CorsHeaders::new(config.allowed_origins).add_to_response(response, origin);
+        }
+    };
+    let cors = CorsHeaders::for_site(site.as_ref(), config.allowed_origins);
+
+    if !cors.is_allowed(&origin) {
+        console_error!("Blocked unauthorised origin: {:?}", origin);
+        return Problem::origin_not_allowed().to_response();
+    }
+
+    let Some(signer) = app_state.form_token_signer else {
+        let response = Problem::new(404, "not_found").to_response()?;
+        return cors.add_to_response(response, origin);
+    };
+    let now = Utc::now().timestamp();
+
+    let mut response = ApiResponse::success(FormTokenResponse {
+        token: signer.issue(now),
+        valid_from: now + signer.min_age_seconds,
+        expires_at: now + signer.max_age_seconds,
+    })
+    .to_response()?;
+    response.headers_mut().set("Cache-Control", "no-store")?;
+    cors.add_to_response(response, origin)
+}
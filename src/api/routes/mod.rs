@@ -1,2 +1,6 @@
+pub mod categories;
 pub mod contact_commands;
+pub mod contact_queries;
+pub mod form_config;
+pub mod form_token;
 pub mod health_check;
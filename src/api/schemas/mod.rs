@@ -1,2 +1,3 @@
+pub mod problem;
 pub mod requests;
 pub mod responses;
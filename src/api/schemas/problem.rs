@@ -0,0 +1,233 @@
+use serde::{Deserialize, Serialize};
+use worker::Response;
+
+use crate::{
+    application::{exceptions::AppError, request_validation_service::VerificationError},
+    domain::exceptions::{FieldViolation, ValidationError, ValidationErrors},
+};
+
+/// An RFC 7807 error body, served as `application/problem+json`.
+///
+/// `code` is stable and meant for clients to branch on; `type` is the same
+/// code as a relative URI reference, and `title` and `detail` are for people.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Problem {
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    pub code: String,
+    /// One entry per invalid request field.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct FieldError {
+    pub field: String,
+    pub code: String,
+    pub detail: String,
+}
+
+impl Problem {
+    pub fn new(status: u16, code: &str) -> Self {
+        Self {
+            problem_type: format!("/problems/{code}"),
+            title: Self::title(status).to_string(),
+            status,
+            detail: None,
+            code: code.to_string(),
+            errors: vec![],
+        }
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    pub fn invalid_body() -> Self {
+        Self::new(400, "invalid_body").with_detail("Invalid request body")
+    }
+
+    pub fn origin_not_allowed() -> Self {
+        Self::new(403, "origin_not_allowed")
+    }
+
+    pub fn internal_error() -> Self {
+        Self::new(500, "internal_error")
+    }
+
+    pub fn to_response(self) -> worker::Result<Response> {
+        let status = self.status;
+        let mut response = Response::from_json(&self)?;
+        response
+            .headers_mut()
+            .set("Content-Type", "application/problem+json")?;
+        Ok(response.with_status(status))
+    }
+
+    fn title(status: u16) -> &'static str {
+        match status {
+            400 => "Bad Request",
+            401 => "Unauthorized",
+            403 => "Forbidden",
+            404 => "Not Found",
+            409 => "Conflict",
+            422 => "Unprocessable Content",
+            429 => "Too Many Requests",
+            503 => "Service Unavailable",
+            _ => "Internal Server Error",
+        }
+    }
+}
+
+impl From<&FieldViolation> for FieldError {
+    fn from(violation: &FieldViolation) -> Self {
+        let (code, detail) = match &violation.error {
+            ValidationError::InvalidEmail(msg) => ("invalid_email", msg),
+            ValidationError::InvalidName(msg) => ("invalid_name", msg),
+            ValidationError::InvalidMessage(msg) => ("invalid_message", msg),
+            ValidationError::InvalidData(msg) => ("invalid_data", msg),
+            ValidationError::InvalidCategory(msg) => ("invalid_category", msg),
+            ValidationError::InvalidStatus(msg) => ("invalid_status", msg),
+            ValidationError::InvalidIdempotencyKey(msg) => ("invalid_idempotency_key", msg),
+            ValidationError::InvalidForm(msg) => ("invalid_form", msg),
+            ValidationError::InvalidPriority(msg) => ("invalid_priority", msg),
+        };
+        Self {
+            field: violation.path.clone(),
+            code: code.to_string(),
+            detail: detail.clone(),
+        }
+    }
+}
+
+impl From<&ValidationErrors> for Problem {
+    fn from(errors: &ValidationErrors) -> Self {
+        let detail = match errors.violations().len() {
+            1 => errors.to_string(),
+            n => format!("{n} fields are invalid"),
+        };
+        let mut problem = Problem::new(400, "validation_failed").with_detail(detail);
+        problem.errors = errors.violations().iter().map(FieldError::from).collect();
+        problem
+    }
+}
+
+impl From<&ValidationError> for Problem {
+    fn from(error: &ValidationError) -> Self {
+        Problem::from(&ValidationErrors::from(error.clone()))
+    }
+}
+
+impl From<&AppError> for Problem {
+    /// Messages from authentication and storage failures stay in the logs.
+    fn from(error: &AppError) -> Self {
+        match error {
+            AppError::ValidationError(e) => Problem::from(e),
+            AppError::NotFound(msg) => Problem::new(404, "not_found").with_detail(msg),
+            AppError::Conflict(msg) => Problem::new(409, "conflict").with_detail(msg),
+            AppError::UnprocessableEntity(msg) => {
+                Problem::new(422, "unprocessable_entity").with_detail(msg)
+            }
+            AppError::Unauthorised(_) => Problem::new(401, "unauthorised"),
+            AppError::Forbidden(_) => Problem::new(403, "forbidden"),
+            AppError::DatabaseError(_) | AppError::InternalError => Problem::internal_error(),
+        }
+    }
+}
+
+impl From<&VerificationError> for Problem {
+    fn from(error: &VerificationError) -> Self {
+        let (status, detail) = match error {
+            VerificationError::TokenInvalid(_) => (401, "Request validation failed"),
+            VerificationError::TokenExpired(_) => {
+                (409, "Verification token has expired or was already used")
+            }
+            VerificationError::ProviderMisconfigured(_) => {
+                (500, "Request validation is misconfigured")
+            }
+            VerificationError::ProviderUnreachable(_) => (503, "Request validation unavailable"),
+            VerificationError::Internal(_) => (500, "Request validation could not be completed"),
+        };
+        Problem::new(status, error.code()).with_detail(detail)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validation_error_lists_the_field() {
+        let problem = Problem::from(&AppError::from(ValidationError::InvalidCategory(
+            "Category 'x' is invalid".to_string(),
+        )));
+
+        assert_eq!(problem.status, 400);
+        assert_eq!(problem.code, "validation_failed");
+        assert_eq!(problem.problem_type, "/problems/validation_failed");
+        assert_eq!(
+            problem.errors,
+            vec![FieldError {
+                field: "category".to_string(),
+                code: "invalid_category".to_string(),
+                detail: "Category 'x' is invalid".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_validation_errors_are_all_listed() {
+        let mut errors = ValidationErrors::default();
+        errors.push(
+            "email",
+            ValidationError::InvalidEmail("Invalid email format".to_string()),
+        );
+        errors.push(
+            "data.order_id",
+            ValidationError::InvalidData("Too long".to_string()),
+        );
+
+        let problem = Problem::from(&AppError::from(errors));
+
+        assert_eq!(problem.detail.as_deref(), Some("2 fields are invalid"));
+        let fields: Vec<&str> = problem.errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["email", "data.order_id"]);
+    }
+
+    #[test]
+    fn test_internal_errors_hide_their_message() {
+        let problem = Problem::from(&AppError::DatabaseError("no such table".to_string()));
+
+        assert_eq!(problem.status, 500);
+        assert_eq!(problem.title, "Internal Server Error");
+        assert_eq!(problem.detail, None);
+    }
+
+    #[test]
+    fn test_verification_errors_keep_their_code() {
+        let problem = Problem::from(&VerificationError::TokenExpired("old".to_string()));
+
+        assert_eq!(problem.status, 409);
+        assert_eq!(problem.code, "token_expired");
+    }
+
+    #[test]
+    fn test_serializes_type_and_omits_empty_fields() {
+        let json = serde_json::to_value(Problem::new(404, "not_found")).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "type": "/problems/not_found",
+                "title": "Not Found",
+                "status": 404,
+                "code": "not_found",
+            })
+        );
+    }
+}
@@ -1,13 +1,185 @@
-use std::collections::HashMap;
-
 use serde::{Deserialize, Serialize};
 
+use crate::domain::form_schema::FormData;
+
 #[derive(PartialEq, Debug, Deserialize, Serialize)]
 pub struct CreateContactMessageRequest {
-    pub token: String,
+    pub token: Option<String>,
+    pub form_token: Option<String>,
+    pub honeypot: Option<String>,
     pub category: String,
     pub email: String,
     pub name: String,
     pub message: String,
-    pub data: Option<HashMap<String, String>>,
+    pub data: Option<FormData>,
+    pub form_id: Option<String>,
+}
+
+impl CreateContactMessageRequest {
+    /// Field names challenge widgets give their hidden response input.
+    const TOKEN_FIELDS: [&'static str; 4] = [
+        "token",
+        "cf-turnstile-response",
+        "h-captcha-response",
+        "g-recaptcha-response",
+    ];
+
+    /// Builds the JSON body a form post stands for, so it is validated and
+    /// hashed for idempotency like a JSON submission. `data[key]` fields
+    /// become entries of `data`, widget response fields become `token`, and
+    /// unknown fields are dropped.
+    pub fn json_from_form(fields: impl IntoIterator<Item = (String, String)>) -> serde_json::Value {
+        let mut body = serde_json::Map::new();
+        let mut data = serde_json::Map::new();
+
+        for (name, value) in fields {
+            if let Some(key) = name
+                .strip_prefix("data[")
+                .and_then(|rest| rest.strip_suffix(']'))
+            {
+                data.insert(key.to_string(), value.into());
+            } else if Self::TOKEN_FIELDS.contains(&name.as_str()) {
+                if !value.is_empty() || !body.contains_key("token") {
+                    body.insert("token".to_string(), value.into());
+                }
+            } else if matches!(
+                name.as_str(),
+                "category" | "email" | "name" | "message" | "form_id" | "form_token" | "honeypot"
+            ) {
+                body.insert(name, value.into());
+            }
+        }
+
+        if !data.is_empty() {
+            body.insert("data".to_string(), data.into());
+        }
+        body.into()
+    }
+}
+
+#[derive(PartialEq, Debug, Deserialize, Serialize)]
+pub struct UpdateContactMessageStatusRequest {
+    pub status: String,
+}
+
+/// The editable fields of a category; omitted fields take their defaults.
+#[derive(PartialEq, Debug, Deserialize, Serialize)]
+pub struct UpdateCategoryRequest {
+    pub display_name: String,
+    pub description: Option<String>,
+    pub active: Option<bool>,
+    pub sort_order: Option<i64>,
+    pub default_priority: Option<String>,
+}
+
+#[derive(PartialEq, Debug, Deserialize, Serialize)]
+pub struct CreateCategoryRequest {
+    pub slug: String,
+    #[serde(flatten)]
+    pub fields: UpdateCategoryRequest,
+}
+
+#[derive(PartialEq, Debug, Deserialize, Serialize, Default)]
+pub struct ListContactMessagesQuery {
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+impl ListContactMessagesQuery {
+    pub const DEFAULT_LIMIT: u32 = 20;
+    pub const MAX_LIMIT: u32 = 100;
+
+    pub fn limit(&self) -> u32 {
+        self.limit
+            .unwrap_or(Self::DEFAULT_LIMIT)
+            .clamp(1, Self::MAX_LIMIT)
+    }
+
+    pub fn offset(&self) -> u32 {
+        self.offset.unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_form_fields_map_to_request() {
+        let body = CreateContactMessageRequest::json_from_form(fields(&[
+            ("category", "IDEA"),
+            ("email", "test@example.com"),
+            ("name", "Test User"),
+            ("message", "Hello"),
+            ("data[order_id]", "42"),
+            ("data[source]", "footer"),
+            ("cf-turnstile-response", "token-1"),
+            ("submit", "Send"),
+        ]));
+
+        let request: CreateContactMessageRequest = serde_json::from_value(body).unwrap();
+
+        assert_eq!(request.category, "IDEA");
+        assert_eq!(request.token.as_deref(), Some("token-1"));
+        assert_eq!(
+            request.data.map(serde_json::Value::Object),
+            Some(serde_json::json!({ "order_id": "42", "source": "footer" }))
+        );
+    }
+
+    #[test]
+    fn test_empty_widget_field_does_not_replace_token() {
+        let body = CreateContactMessageRequest::json_from_form(fields(&[
+            ("g-recaptcha-response", "token-1"),
+            ("cf-turnstile-response", ""),
+        ]));
+
+        assert_eq!(body["token"], "token-1");
+        assert!(body.get("data").is_none());
+    }
+
+    #[test]
+    fn test_create_category_request_defaults_optional_fields() {
+        let request: CreateCategoryRequest = serde_json::from_value(serde_json::json!({
+            "slug": "billing",
+            "display_name": "Billing",
+        }))
+        .unwrap();
+
+        assert_eq!(request.slug, "billing");
+        assert_eq!(request.fields.display_name, "Billing");
+        assert_eq!(request.fields.active, None);
+        assert_eq!(request.fields.default_priority, None);
+    }
+
+    #[test]
+    fn test_list_query_defaults() {
+        let query = ListContactMessagesQuery::default();
+
+        assert_eq!(query.limit(), ListContactMessagesQuery::DEFAULT_LIMIT);
+        assert_eq!(query.offset(), 0);
+    }
+
+    #[test]
+    fn test_list_query_limit_is_clamped() {
+        let too_large = ListContactMessagesQuery {
+            limit: Some(1000),
+            offset: Some(40),
+        };
+        let zero = ListContactMessagesQuery {
+            limit: Some(0),
+            offset: None,
+        };
+
+        assert_eq!(too_large.limit(), ListContactMessagesQuery::MAX_LIMIT);
+        assert_eq!(too_large.offset(), 40);
+        assert_eq!(zero.limit(), 1);
+    }
 }
@@ -1,6 +1,12 @@
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use worker::Response;
 
+use crate::domain::{
+    entity::{Category, ContactMessage},
+    form_schema::{FormData, FormSchema},
+};
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ApiResponse<T>
 where
@@ -23,14 +29,6 @@ where
         }
     }
 
-    pub fn failure(status: u16, message: impl Into<String>) -> Self {
-        Self {
-            status,
-            message: message.into(),
-            data: None,
-        }
-    }
-
     pub fn to_response(self) -> worker::Result<Response> {
         let status = self.status;
         let mut response = Response::from_json(&self)?;
@@ -40,3 +38,222 @@ where
         Ok(response.with_status(status))
     }
 }
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FormTokenResponse {
+    pub token: String,
+    /// Earliest time the form may be submitted with this token.
+    pub valid_from: i64,
+    pub expires_at: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ContactMessageResponse {
+    pub id: String,
+    pub category: String,
+    pub email: String,
+    pub name: String,
+    pub message: String,
+    pub data: Option<FormData>,
+    pub status: String,
+    pub priority: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub resolved_at: Option<i64>,
+    pub spam_score: Option<u32>,
+    pub previous_messages: Option<u32>,
+    pub auto_replied_at: Option<i64>,
+    pub site_id: Option<String>,
+}
+
+impl From<ContactMessage> for ContactMessageResponse {
+    fn from(contact: ContactMessage) -> Self {
+        Self {
+            id: contact.id,
+            category: contact.category.to_string(),
+            email: contact.email,
+            name: contact.name,
+            message: contact.message,
+            data: contact.data,
+            status: contact.status.to_string(),
+            priority: contact.priority.to_string(),
+            created_at: contact.created_at,
+            updated_at: contact.updated_at,
+            resolved_at: contact.resolved_at,
+            spam_score: contact.spam_score,
+            previous_messages: contact.previous_messages,
+            auto_replied_at: contact.auto_replied_at,
+            site_id: contact.site_id,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ContactMessageListResponse {
+    pub messages: Vec<ContactMessageResponse>,
+    pub limit: u32,
+    pub offset: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CategoryResponse {
+    pub slug: String,
+    pub display_name: String,
+    pub description: String,
+    pub active: bool,
+    pub sort_order: i64,
+    pub default_priority: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl From<Category> for CategoryResponse {
+    fn from(category: Category) -> Self {
+        Self {
+            slug: category.slug.to_string(),
+            display_name: category.display_name,
+            description: category.description,
+            active: category.active,
+            sort_order: category.sort_order,
+            default_priority: category.default_priority.to_string(),
+            created_at: category.created_at,
+            updated_at: category.updated_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CategoryListResponse {
+    pub categories: Vec<CategoryResponse>,
+}
+
+/// What a submission may contain, for clients to render and pre-validate
+/// their forms with.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct FormConfigResponse {
+    pub categories: Vec<CategoryOptionResponse>,
+    pub fields: FieldConstraintsResponse,
+    /// `None` unless submissions are checked with Turnstile.
+    pub turnstile_site_key: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct CategoryOptionResponse {
+    pub slug: String,
+    pub display_name: String,
+    pub description: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct FieldConstraintsResponse {
+    pub email: TextConstraintsResponse,
+    pub name: TextConstraintsResponse,
+    pub message: TextConstraintsResponse,
+    /// Limits of `data` when the submission names no form.
+    pub data: DataConstraintsResponse,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct TextConstraintsResponse {
+    pub required: bool,
+    pub max_length: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct DataConstraintsResponse {
+    pub max_fields: usize,
+    pub max_key_length: usize,
+    pub max_value_length: usize,
+}
+
+impl FormConfigResponse {
+    pub fn new(categories: Vec<Category>, turnstile_site_key: Option<String>) -> Self {
+        let text = |max_length| TextConstraintsResponse {
+            required: true,
+            max_length,
+        };
+
+        Self {
+            categories: categories
+                .into_iter()
+                .map(|category| CategoryOptionResponse {
+                    slug: category.slug.to_string(),
+                    display_name: category.display_name,
+                    description: category.description,
+                })
+                .collect(),
+            fields: FieldConstraintsResponse {
+                email: text(ContactMessage::MAX_EMAIL_LENGTH),
+                name: text(ContactMessage::MAX_NAME_LENGTH),
+                message: text(ContactMessage::MAX_MESSAGE_LENGTH),
+                data: DataConstraintsResponse {
+                    max_fields: FormSchema::MAX_FIELDS,
+                    max_key_length: FormSchema::MAX_KEY_LENGTH,
+                    max_value_length: FormSchema::DEFAULT_MAX_LENGTH,
+                },
+            },
+            turnstile_site_key,
+        }
+    }
+
+    /// A strong validator that changes whenever the serialized config does.
+    pub fn etag(&self) -> serde_json::Result<String> {
+        let body = serde_json::to_vec(self)?;
+        Ok(format!("\"{}\"", hex::encode(&Sha256::digest(&body)[..16])))
+    }
+
+    /// Whether an `If-None-Match` header lists `etag`, ignoring weak prefixes.
+    pub fn is_not_modified(if_none_match: Option<&str>, etag: &str) -> bool {
+        if_none_match.is_some_and(|header| {
+            header.split(',').map(str::trim).any(|candidate| {
+                candidate == "*" || candidate.strip_prefix("W/").unwrap_or(candidate) == etag
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::test_support::test_categories;
+
+    #[test]
+    fn test_form_config_lists_categories_and_limits() {
+        let config = FormConfigResponse::new(test_categories(), Some("site-key".to_string()));
+
+        let json = serde_json::to_value(&config).unwrap();
+        assert_eq!(json["categories"][0]["slug"], "ERROR");
+        assert_eq!(json["categories"][0]["display_name"], "Error report");
+        assert_eq!(json["fields"]["name"]["max_length"], 100);
+        assert_eq!(json["fields"]["message"]["max_length"], 5000);
+        assert_eq!(json["fields"]["data"]["max_fields"], 20);
+        assert_eq!(json["turnstile_site_key"], "site-key");
+    }
+
+    #[test]
+    fn test_etag_changes_with_config() {
+        let mut categories = test_categories();
+        let before = FormConfigResponse::new(categories.clone(), None)
+            .etag()
+            .unwrap();
+        categories.pop();
+        let after = FormConfigResponse::new(categories, None).etag().unwrap();
+
+        assert_ne!(before, after);
+        assert!(before.starts_with('"') && before.ends_with('"'));
+    }
+
+    #[test]
+    fn test_if_none_match() {
+        let etag = "\"abc\"";
+
+        assert!(FormConfigResponse::is_not_modified(Some("\"abc\""), etag));
+        assert!(FormConfigResponse::is_not_modified(
+            Some("\"xyz\", W/\"abc\""),
+            etag
+        ));
+        assert!(FormConfigResponse::is_not_modified(Some("*"), etag));
+        assert!(!FormConfigResponse::is_not_modified(Some("\"xyz\""), etag));
+        assert!(!FormConfigResponse::is_not_modified(None, etag));
+    }
+}
@@ -0,0 +1,30 @@
+use worker::{RouteContext, console_error, console_log};
+
+use crate::{
+    api::schemas::problem::Problem, application::exceptions::AppError, domain::entity::Site,
+    setup::app_state::AppState,
+};
+
+/// The site named by the `site_id` route parameter, or else the one that
+/// lists the request's origin.
+pub async fn resolve_site(
+    app_state: &AppState,
+    ctx: &RouteContext<()>,
+    origin: &Option<String>,
+) -> Result<Option<Site>, Problem> {
+    match app_state
+        .site_service
+        .resolve(ctx.param("site_id").map(String::as_str), origin.as_deref())
+        .await
+    {
+        Ok(site) => Ok(site),
+        Err(AppError::NotFound(msg)) => {
+            console_log!("Rejected request for unknown site: {}", msg);
+            Err(Problem::new(404, "site_not_found").with_detail(msg))
+        }
+        Err(e) => {
+            console_error!("Failed to resolve site: {:?}", e);
+            Err(Problem::from(&e))
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use crate::application::exceptions::AppError;
+use async_trait::async_trait;
+use strum_macros::{Display, EnumString};
+
+#[derive(Debug, Clone, Copy, PartialEq, EnumString, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum Scope {
+    Read,
+    Write,
+}
+
+/// Raw credentials extracted from an incoming request.
+#[derive(Debug, Default, Clone)]
+pub struct Credentials {
+    pub authorization: Option<String>,
+    pub access_jwt: Option<String>,
+}
+
+/// The authenticated caller of an admin route.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Principal {
+    pub subject: String,
+    pub email: Option<String>,
+    pub scopes: Vec<Scope>,
+}
+
+impl Principal {
+    pub fn require(&self, scope: Scope) -> Result<(), AppError> {
+        if self.scopes.contains(&scope) {
+            Ok(())
+        } else {
+            Err(AppError::Forbidden(format!(
+                "'{}' is missing the '{scope}' scope",
+                self.subject
+            )))
+        }
+    }
+}
+
+#[async_trait(?Send)]
+pub trait AuthenticationServiceTrait: Send + Sync {
+    async fn authenticate(&self, credentials: &Credentials) -> Result<Principal, AppError>;
+}
+
+/// Tries each authentication service in turn, accepting the first success.
+pub struct AuthenticationChain {
+    services: Vec<Arc<dyn AuthenticationServiceTrait>>,
+}
+
+impl AuthenticationChain {
+    pub fn create(
+        services: Vec<Arc<dyn AuthenticationServiceTrait>>,
+    ) -> Arc<dyn AuthenticationServiceTrait> {
+        Arc::new(Self { services })
+    }
+}
+
+#[async_trait(?Send)]
+impl AuthenticationServiceTrait for AuthenticationChain {
+    async fn authenticate(&self, credentials: &Credentials) -> Result<Principal, AppError> {
+        let mut last_error = AppError::Unauthorised("No credentials provided".to_string());
+
+        for service in &self.services {
+            match service.authenticate(credentials).await {
+                Ok(principal) => return Ok(principal),
+                Err(e) => last_error = e,
+            }
+        }
+
+        Err(last_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct StaticAuthenticationService(Result<Principal, String>);
+
+    #[async_trait(?Send)]
+    impl AuthenticationServiceTrait for StaticAuthenticationService {
+        async fn authenticate(&self, _credentials: &Credentials) -> Result<Principal, AppError> {
+            self.0.clone().map_err(AppError::Unauthorised)
+        }
+    }
+
+    fn principal(subject: &str) -> Principal {
+        Principal {
+            subject: subject.to_string(),
+            email: None,
+            scopes: vec![Scope::Read],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_chain_returns_first_success() {
+        let chain = AuthenticationChain::create(vec![
+            Arc::new(StaticAuthenticationService(Err("no token".to_string()))),
+            Arc::new(StaticAuthenticationService(Ok(principal("second")))),
+            Arc::new(StaticAuthenticationService(Ok(principal("third")))),
+        ]);
+
+        let result = chain.authenticate(&Credentials::default()).await.unwrap();
+        assert_eq!(result.subject, "second");
+    }
+
+    #[tokio::test]
+    async fn test_chain_fails_when_all_fail() {
+        let empty = AuthenticationChain::create(vec![]);
+        assert!(matches!(
+            empty.authenticate(&Credentials::default()).await,
+            Err(AppError::Unauthorised(_))
+        ));
+
+        let chain = AuthenticationChain::create(vec![Arc::new(StaticAuthenticationService(Err(
+            "bad key".to_string(),
+        )))]);
+        match chain.authenticate(&Credentials::default()).await {
+            Err(AppError::Unauthorised(msg)) => assert_eq!(msg, "bad key"),
+            _ => panic!("Expected Unauthorised"),
+        }
+    }
+}
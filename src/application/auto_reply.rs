@@ -0,0 +1,327 @@
+use std::{collections::HashMap, sync::Arc};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use regex::{Captures, Regex};
+use serde::Deserialize;
+
+use crate::{
+    application::{
+        email_provider::{EmailProviderTrait, OutgoingEmail},
+        event_consumer::PostProcessingStepTrait,
+        exceptions::AppError,
+    },
+    domain::{
+        entity::ContactMessage, enums::ContactMessageStatus,
+        repository::ContactMessageRepository as ContactMessageRepositoryInterface,
+    },
+    log_error, log_info,
+};
+
+/// The subject and body of an acknowledgement email. `{{name}}`,
+/// `{{category}}` and `{{reference}}` are replaced with the submitter's
+/// name, the message's category and its id; everything else is sent as written.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct AutoReplyTemplate {
+    pub subject: String,
+    pub body: String,
+}
+
+impl AutoReplyTemplate {
+    pub fn render(&self, message: &ContactMessage) -> OutgoingEmail {
+        OutgoingEmail {
+            to: vec![message.email.clone()],
+            reply_to: None,
+            subject: Self::substitute(&self.subject, message),
+            text: Self::substitute(&self.body, message),
+        }
+    }
+
+    /// Replaces every placeholder in one pass, so placeholders inside the
+    /// submitter's own name are left alone.
+    fn substitute(template: &str, message: &ContactMessage) -> String {
+        let placeholder = Regex::new(r"\{\{\s*(name|category|reference)\s*\}\}").unwrap();
+        placeholder
+            .replace_all(template, |captures: &Captures| match &captures[1] {
+                "name" => message.name.trim().to_string(),
+                "category" => message.category.to_string(),
+                _ => message.id.clone(),
+            })
+            .into_owned()
+    }
+}
+
+/// Acknowledgement templates by category, parsed from
+/// `{"ERROR": {"subject": "...", "body": "..."}, "*": {...}}`, where `*` is
+/// the fallback for categories without their own entry. Categories with
+/// neither get no acknowledgement.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AutoReplyTemplates {
+    by_category: HashMap<String, AutoReplyTemplate>,
+    default: Option<AutoReplyTemplate>,
+}
+
+impl AutoReplyTemplates {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        if value.trim().is_empty() {
+            return Ok(Self::default());
+        }
+
+        let entries: HashMap<String, AutoReplyTemplate> = serde_json::from_str(value)
+            .map_err(|e| format!("Invalid auto-reply templates: {e}"))?;
+
+        let mut templates = Self::default();
+        for (category, template) in entries {
+            if template.subject.trim().is_empty() || template.body.trim().is_empty() {
+                return Err(format!(
+                    "Auto-reply template for '{category}' needs a subject and a body"
+                ));
+            }
+            match category.trim() {
+                "*" => templates.default = Some(template),
+                category => {
+                    templates
+                        .by_category
+                        .insert(category.to_uppercase(), template);
+                }
+            }
+        }
+
+        Ok(templates)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_category.is_empty() && self.default.is_none()
+    }
+
+    pub fn template_for(&self, category: &str) -> Option<&AutoReplyTemplate> {
+        self.by_category
+            .get(&category.to_uppercase())
+            .or(self.default.as_ref())
+    }
+}
+
+/// Emails the submitter an acknowledgement, unless the message has been
+/// marked as spam.
+///
+/// The reply is recorded in `auto_replied_at` before it is sent, so a retried
+/// or redelivered event never sends a second one. A failed send is therefore
+/// logged rather than returned.
+pub struct AutoReplyStep {
+    repo: Arc<dyn ContactMessageRepositoryInterface>,
+    email_provider: Arc<dyn EmailProviderTrait>,
+    templates: AutoReplyTemplates,
+}
+
+impl AutoReplyStep {
+    pub fn create(
+        repo: Arc<dyn ContactMessageRepositoryInterface>,
+        email_provider: Arc<dyn EmailProviderTrait>,
+        templates: AutoReplyTemplates,
+    ) -> Arc<dyn PostProcessingStepTrait> {
+        Arc::new(Self {
+            repo,
+            email_provider,
+            templates,
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl PostProcessingStepTrait for AutoReplyStep {
+    fn name(&self) -> &'static str {
+        "auto_reply"
+    }
+
+    async fn run(&self, message: &mut ContactMessage) -> Result<(), AppError> {
+        if message.status == ContactMessageStatus::SPAM || message.auto_replied_at.is_some() {
+            return Ok(());
+        }
+        let Some(template) = self.templates.template_for(message.category.as_str()) else {
+            return Ok(());
+        };
+
+        let now = Utc::now().timestamp();
+        match self.repo.mark_auto_replied(&message.id, now).await {
+            Ok(true) => message.auto_replied_at = Some(now),
+            Ok(false) => {
+                log_info!("Auto-reply for message {} was already sent", message.id);
+                return Ok(());
+            }
+            Err(e) => {
+                log_error!(
+                    "Failed to record auto-reply for message {}: {}",
+                    message.id,
+                    e
+                );
+                return Ok(());
+            }
+        }
+
+        match self.email_provider.send(&template.render(message)).await {
+            Ok(()) => log_info!("Sent auto-reply for message {}", message.id),
+            Err(e) => log_error!(
+                "Failed to send auto-reply for message {}: {}",
+                message.id,
+                e
+            ),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::test_support::{MockContactMessageRepository, MockEmailProvider};
+    use crate::domain::{
+        entity::OutboxEvent, enums::ContactMessageCategory, form_schema::FormSchema,
+        repository::ContactMessageRepository,
+    };
+
+    fn message(category: ContactMessageCategory) -> ContactMessage {
+        ContactMessage::create(
+            category,
+            "user@example.com".to_string(),
+            "Jane {{reference}}".to_string(),
+            "Something is broken".to_string(),
+            None,
+            &FormSchema::unstructured(),
+        )
+        .unwrap()
+    }
+
+    async fn stored_message(
+        repo: &MockContactMessageRepository,
+        category: ContactMessageCategory,
+    ) -> ContactMessage {
+        let message = message(category);
+        repo.save(&message, &OutboxEvent::message_created(&message), None)
+            .await
+            .unwrap();
+        message
+    }
+
+    fn templates() -> AutoReplyTemplates {
+        AutoReplyTemplates::parse(
+            r#"{
+                "error": {
+                    "subject": "We received your {{category}} report",
+                    "body": "Hi {{ name }},\nyour reference is {{reference}}. {{unknown}}"
+                },
+                "*": { "subject": "Thanks, {{name}}", "body": "Reference {{reference}}" }
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_render_replaces_placeholders() {
+        let message = message(ContactMessageCategory::ERROR);
+
+        let email = templates().template_for("ERROR").unwrap().render(&message);
+
+        assert_eq!(email.to, vec!["user@example.com"]);
+        assert_eq!(email.subject, "We received your ERROR report");
+        assert_eq!(
+            email.text,
+            format!(
+                "Hi Jane {{{{reference}}}},\nyour reference is {}. {{{{unknown}}}}",
+                message.id
+            )
+        );
+    }
+
+    #[test]
+    fn test_templates_fall_back_to_default() {
+        let templates = templates();
+
+        assert_eq!(
+            templates.template_for("idea").unwrap().subject,
+            "Thanks, {{name}}"
+        );
+        assert!(
+            AutoReplyTemplates::parse(r#"{"ERROR": {"subject": "Hi", "body": "Thanks"}}"#)
+                .unwrap()
+                .template_for("IDEA")
+                .is_none()
+        );
+        assert!(AutoReplyTemplates::parse("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_rejects_empty_templates() {
+        assert!(AutoReplyTemplates::parse(r#"{"ERROR": {"subject": "", "body": "Hi"}}"#).is_err());
+        assert!(AutoReplyTemplates::parse("not json").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_step_sends_auto_reply() {
+        let repo = Arc::new(MockContactMessageRepository::new());
+        let provider = Arc::new(MockEmailProvider::default());
+        let mut message = stored_message(&repo, ContactMessageCategory::IDEA).await;
+
+        let result = AutoReplyStep::create(repo.clone(), provider.clone(), templates())
+            .run(&mut message)
+            .await;
+
+        assert!(result.is_ok());
+        assert!(message.auto_replied_at.is_some());
+        let sent = provider.sent.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].subject, "Thanks, Jane {{reference}}");
+    }
+
+    #[tokio::test]
+    async fn test_step_replies_only_once() {
+        let repo = Arc::new(MockContactMessageRepository::new());
+        let provider = Arc::new(MockEmailProvider::default());
+        let message = stored_message(&repo, ContactMessageCategory::IDEA).await;
+        let step = AutoReplyStep::create(repo.clone(), provider.clone(), templates());
+
+        // Two deliveries of the same event, each loading the message before either replied.
+        step.run(&mut message.clone()).await.unwrap();
+        step.run(&mut message.clone()).await.unwrap();
+
+        assert_eq!(provider.sent.lock().unwrap().len(), 1);
+        assert!(
+            repo.find_by_id(&message.id)
+                .await
+                .unwrap()
+                .auto_replied_at
+                .is_some()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_step_skips_spam() {
+        let repo = Arc::new(MockContactMessageRepository::new());
+        let provider = Arc::new(MockEmailProvider::default());
+        let mut message = stored_message(&repo, ContactMessageCategory::ERROR).await;
+        message.record_spam_score(90, true, 0);
+
+        let result = AutoReplyStep::create(repo.clone(), provider.clone(), templates())
+            .run(&mut message)
+            .await;
+
+        assert!(result.is_ok());
+        assert!(provider.sent.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_step_does_not_retry_failed_sends() {
+        let repo = Arc::new(MockContactMessageRepository::new());
+        let provider = Arc::new(MockEmailProvider {
+            should_fail: true,
+            ..Default::default()
+        });
+        let mut message = stored_message(&repo, ContactMessageCategory::ERROR).await;
+
+        let result = AutoReplyStep::create(repo.clone(), provider.clone(), templates())
+            .run(&mut message)
+            .await;
+
+        assert!(result.is_ok());
+        assert_eq!(provider.sent.lock().unwrap().len(), 1);
+    }
+}
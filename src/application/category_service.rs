@@ -0,0 +1,370 @@
+use std::{
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+use chrono::Utc;
+
+use crate::{
+    application::exceptions::AppError,
+    domain::{
+        entity::Category,
+        enums::{ContactMessageCategory, ContactMessagePriority},
+        exceptions::{ValidationError, ValidationErrors},
+        repository::CategoryRepository as CategoryRepositoryInterface,
+    },
+};
+
+/// The editable fields of a category, before they are validated. Omitted
+/// fields take their defaults: no description, active, sort order 0 and
+/// `NORMAL` priority.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CategoryFields {
+    pub display_name: String,
+    pub description: Option<String>,
+    pub active: Option<bool>,
+    pub sort_order: Option<i64>,
+    pub default_priority: Option<String>,
+}
+
+/// Manages the categories stored in D1.
+///
+/// The service lives for one request, so categories are read from D1 at most
+/// once per request and changes made through the admin API apply from the
+/// next request on.
+pub struct CategoryService {
+    repo: Arc<dyn CategoryRepositoryInterface>,
+    cache: Mutex<Option<Vec<Category>>>,
+}
+
+impl CategoryService {
+    pub fn create(repo: Arc<dyn CategoryRepositoryInterface>) -> Self {
+        Self {
+            repo,
+            cache: Mutex::new(None),
+        }
+    }
+
+    /// Every category, active or not, in display order.
+    pub async fn list(&self) -> Result<Vec<Category>, AppError> {
+        if let Some(categories) = self.cache.lock().unwrap().as_ref() {
+            return Ok(categories.clone());
+        }
+
+        let categories = self
+            .repo
+            .list()
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        *self.cache.lock().unwrap() = Some(categories.clone());
+        Ok(categories)
+    }
+
+    /// The active categories, in display order.
+    pub async fn list_active(&self) -> Result<Vec<Category>, AppError> {
+        let mut categories = self.list().await?;
+        categories.retain(|category| category.active);
+        Ok(categories)
+    }
+
+    /// The category `slug` names in any case, or `NotFound`.
+    pub async fn get(&self, slug: &str) -> Result<Category, AppError> {
+        let not_found = || AppError::NotFound(format!("Category {slug} not found"));
+        let slug = ContactMessageCategory::from_str(slug).map_err(|_| not_found())?;
+
+        self.list()
+            .await?
+            .into_iter()
+            .find(|category| category.slug == slug)
+            .ok_or_else(not_found)
+    }
+
+    /// The active category `slug` names in any case, if there is one.
+    pub async fn find_active(&self, slug: &str) -> Result<Option<Category>, AppError> {
+        match self.get(slug).await {
+            Ok(category) => Ok(Some(category).filter(|category| category.active)),
+            Err(AppError::NotFound(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub async fn create_category(
+        &self,
+        slug: &str,
+        fields: CategoryFields,
+    ) -> Result<Category, AppError> {
+        let now = Utc::now().timestamp();
+        let category = Self::build(ContactMessageCategory::from_str(slug), fields, now, now)?;
+
+        let inserted = self
+            .repo
+            .insert(&category)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        self.invalidate();
+        if !inserted {
+            return Err(AppError::Conflict(format!(
+                "Category {} already exists",
+                category.slug
+            )));
+        }
+
+        Ok(category)
+    }
+
+    /// Replaces the editable fields of the category `slug` names.
+    pub async fn update_category(
+        &self,
+        slug: &str,
+        fields: CategoryFields,
+    ) -> Result<Category, AppError> {
+        let existing = self.get(slug).await?;
+        let category = Self::build(
+            Ok(existing.slug),
+            fields,
+            existing.created_at,
+            Utc::now().timestamp(),
+        )?;
+
+        let updated = self
+            .repo
+            .update(&category)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        self.invalidate();
+        if !updated {
+            return Err(AppError::NotFound(format!(
+                "Category {} not found",
+                category.slug
+            )));
+        }
+
+        Ok(category)
+    }
+
+    /// Deletes the category `slug` names. Categories that messages are filed
+    /// under are kept, with `Conflict`; deactivate them instead.
+    pub async fn delete_category(&self, slug: &str) -> Result<(), AppError> {
+        let category = self.get(slug).await?;
+
+        let deleted = self
+            .repo
+            .delete(&category.slug)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        self.invalidate();
+        if !deleted {
+            return Err(AppError::Conflict(format!(
+                "Category {} has messages and cannot be deleted; deactivate it instead",
+                category.slug
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Validates every field, including the slug when it failed to parse.
+    fn build(
+        slug: Result<ContactMessageCategory, String>,
+        fields: CategoryFields,
+        created_at: i64,
+        updated_at: i64,
+    ) -> Result<Category, ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        let slug = slug.map_err(|msg| errors.push("slug", ValidationError::InvalidCategory(msg)));
+
+        let default_priority = match fields.default_priority {
+            Some(priority) => ContactMessagePriority::from_str(priority.trim())
+                .map_err(|_| {
+                    errors.push(
+                        "default_priority",
+                        ValidationError::InvalidPriority(format!(
+                            "Priority '{priority}' is invalid"
+                        )),
+                    )
+                })
+                .unwrap_or_default(),
+            None => ContactMessagePriority::default(),
+        };
+
+        let display_name = fields.display_name.trim().to_string();
+        let description = fields.description.unwrap_or_default().trim().to_string();
+        if let Err(field_errors) = Category::validate(&display_name, &description) {
+            errors.extend(field_errors);
+        }
+
+        match slug {
+            Ok(slug) if errors.is_empty() => Ok(Category {
+                slug,
+                display_name,
+                description,
+                active: fields.active.unwrap_or(true),
+                sort_order: fields.sort_order.unwrap_or(0),
+                default_priority,
+                created_at,
+                updated_at,
+            }),
+            _ => Err(errors),
+        }
+    }
+
+    fn invalidate(&self) {
+        *self.cache.lock().unwrap() = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::test_support::{MockCategoryRepository, test_categories};
+
+    fn service() -> (CategoryService, Arc<MockCategoryRepository>) {
+        let repo = Arc::new(MockCategoryRepository::with_categories(test_categories()));
+        (CategoryService::create(repo.clone()), repo)
+    }
+
+    fn fields(display_name: &str) -> CategoryFields {
+        CategoryFields {
+            display_name: display_name.to_string(),
+            description: None,
+            active: None,
+            sort_order: None,
+            default_priority: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_categories_are_loaded_once_per_service() {
+        let (service, repo) = service();
+
+        service.list().await.unwrap();
+        service.find_active("error").await.unwrap();
+        service.get("IDEA").await.unwrap();
+
+        assert_eq!(*repo.list_calls.lock().unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_find_active_is_case_insensitive() {
+        let (service, _) = service();
+
+        let category = service.find_active(" Testimonial ").await.unwrap();
+
+        assert_eq!(category.unwrap().slug, ContactMessageCategory::TESTIMONIAL);
+        assert_eq!(service.find_active("UNKNOWN").await.unwrap(), None);
+        assert_eq!(service.find_active("not a slug").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_find_active_skips_inactive_categories() {
+        let (service, _) = service();
+        service
+            .update_category(
+                "idea",
+                CategoryFields {
+                    active: Some(false),
+                    ..fields("Idea")
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(service.find_active("IDEA").await.unwrap(), None);
+        assert_eq!(service.list_active().await.unwrap().len(), 3);
+        assert_eq!(service.list().await.unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_create_category() {
+        let (service, repo) = service();
+
+        let category = service
+            .create_category(
+                "billing",
+                CategoryFields {
+                    sort_order: Some(50),
+                    default_priority: Some("high".to_string()),
+                    ..fields(" Billing ")
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(category.slug.as_str(), "BILLING");
+        assert_eq!(category.display_name, "Billing");
+        assert_eq!(category.default_priority, ContactMessagePriority::HIGH);
+        assert!(category.active);
+        assert_eq!(repo.categories.lock().unwrap().len(), 5);
+        assert_eq!(service.list().await.unwrap().last(), Some(&category));
+    }
+
+    #[tokio::test]
+    async fn test_create_category_reports_every_invalid_field() {
+        let (service, _) = service();
+
+        let result = service
+            .create_category(
+                "no spaces",
+                CategoryFields {
+                    default_priority: Some("critical".to_string()),
+                    ..fields("")
+                },
+            )
+            .await;
+
+        let Err(AppError::ValidationError(errors)) = result else {
+            panic!("Expected ValidationError");
+        };
+        let paths: Vec<&str> = errors
+            .violations()
+            .iter()
+            .map(|v| v.path.as_str())
+            .collect();
+        assert_eq!(paths, vec!["slug", "default_priority", "display_name"]);
+    }
+
+    #[tokio::test]
+    async fn test_create_existing_category_conflicts() {
+        let (service, _) = service();
+
+        let result = service.create_category("Error", fields("Errors")).await;
+
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    async fn test_update_unknown_category_is_not_found() {
+        let (service, _) = service();
+
+        let result = service.update_category("BILLING", fields("Billing")).await;
+
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_delete_category() {
+        let (service, repo) = service();
+
+        service.delete_category("other").await.unwrap();
+
+        assert!(matches!(
+            service.get("OTHER").await,
+            Err(AppError::NotFound(_))
+        ));
+        assert_eq!(repo.categories.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_delete_category_in_use_conflicts() {
+        let (service, repo) = service();
+        repo.in_use
+            .lock()
+            .unwrap()
+            .push(ContactMessageCategory::ERROR);
+
+        let result = service.delete_category("ERROR").await;
+
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+        assert_eq!(repo.categories.lock().unwrap().len(), 4);
+    }
+}
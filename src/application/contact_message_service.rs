@@ -1,103 +1,379 @@
-use std::{collections::HashMap, str::FromStr, sync::Arc};
+use std::{str::FromStr, sync::Arc};
+
+use chrono::Utc;
 
 use crate::{
-    application::exceptions::AppError,
+    application::{
+        category_service::CategoryService, exceptions::AppError, outbox_processor::OutboxProcessor,
+    },
     domain::{
-        entity::ContactMessage, enums::ContactMessageCategory,
-        repository::ContactMessageRepository as ContactMessageRepositoryInterface,
+        entity::{ContactMessage, IdempotencyKey, IdempotencyRecord, OutboxEvent, Site},
+        enums::ContactMessageStatus,
+        exceptions::{RepositoryError, ValidationError, ValidationErrors},
+        form_schema::{FormData, FormSchema},
+        repository::{
+            ContactMessageRepository as ContactMessageRepositoryInterface,
+            FormRepository as FormRepositoryInterface,
+            IdempotencyRepository as IdempotencyRepositoryInterface,
+        },
     },
+    log_error, log_info,
 };
 
+/// What happens to a submission whose content fingerprint matches a message
+/// created within the duplicate window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DuplicatePolicy {
+    /// Fail with `AppError::Conflict`.
+    Reject,
+    /// Report success without storing the message again.
+    Collapse,
+}
+
+impl FromStr for DuplicatePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "reject" => Ok(DuplicatePolicy::Reject),
+            "collapse" => Ok(DuplicatePolicy::Collapse),
+            _ => Err(format!(
+                "Unknown duplicate policy '{s}', expected 'reject' or 'collapse'"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DuplicateDetection {
+    pub window_seconds: i64,
+    pub policy: DuplicatePolicy,
+}
+
+/// The fields of a submission, before they are validated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewContactMessage {
+    pub category: String,
+    pub email: String,
+    pub name: String,
+    pub message: String,
+    pub data: Option<FormData>,
+    /// The form whose schema `data` must follow; free-form text when `None`.
+    pub form_id: Option<String>,
+}
+
 pub struct ContactMessageService {
     pub repo: Arc<dyn ContactMessageRepositoryInterface + Send + Sync>,
+    pub outbox_processor: Arc<OutboxProcessor>,
+    pub idempotency_repo: Arc<dyn IdempotencyRepositoryInterface>,
+    pub form_repo: Arc<dyn FormRepositoryInterface>,
+    pub category_service: Arc<CategoryService>,
+    pub duplicate_detection: Option<DuplicateDetection>,
 }
 
 impl ContactMessageService {
-    pub fn create(contact_repo: Arc<dyn ContactMessageRepositoryInterface>) -> Self {
-        Self { repo: contact_repo }
+    pub fn create(
+        contact_repo: Arc<dyn ContactMessageRepositoryInterface>,
+        outbox_processor: Arc<OutboxProcessor>,
+        idempotency_repo: Arc<dyn IdempotencyRepositoryInterface>,
+        form_repo: Arc<dyn FormRepositoryInterface>,
+        category_service: Arc<CategoryService>,
+        duplicate_detection: Option<DuplicateDetection>,
+    ) -> Self {
+        Self {
+            repo: contact_repo,
+            outbox_processor,
+            idempotency_repo,
+            form_repo,
+            category_service,
+            duplicate_detection,
+        }
     }
 
     pub async fn create_message(
         &self,
-        category: String,
-        email: String,
-        name: String,
-        message: String,
-        data: Option<HashMap<String, String>>,
+        new_message: NewContactMessage,
+        site: Option<&Site>,
     ) -> Result<(), AppError> {
-        let parsed_category = ContactMessageCategory::from_str(&category);
-        if parsed_category.is_err() {
-            return Err(AppError::ValidationError(format!(
-                "Category '{category}' is invalid"
-            )));
-        }
-        let category = parsed_category.unwrap();
-        let contact_message = ContactMessage::create(category, email, name, message, data)
-            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+        self.save_new_message(new_message, site, None).await?;
+        Ok(())
+    }
 
-        self.repo
-            .save(&contact_message)
+    /// Returns the id of the message previously created with `key`, or
+    /// `UnprocessableEntity` if the key was used for a different request.
+    pub async fn replay(&self, key: &IdempotencyKey) -> Result<Option<String>, AppError> {
+        let record = self
+            .idempotency_repo
+            .find(&key.key, Utc::now().timestamp())
             .await
             .map_err(|e| AppError::DatabaseError(e.to_string()))?;
-        Ok(())
-    }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::domain::exceptions::RepositoryError;
-    use async_trait::async_trait;
-    use std::collections::HashMap;
-    use std::sync::Mutex;
-
-    #[derive(Default)]
-    struct MockContactMessageRepository {
-        contact_messages: Arc<Mutex<Vec<ContactMessage>>>,
-        should_save_fail: Arc<Mutex<bool>>,
-    }
-
-    impl MockContactMessageRepository {
-        fn new() -> Self {
-            Self {
-                contact_messages: Arc::new(Mutex::new(Vec::new())),
-                should_save_fail: Arc::new(Mutex::new(false)),
-            }
+        match record {
+            Some(record) if record.matches(key) => Ok(Some(record.message_id)),
+            Some(_) => Err(AppError::UnprocessableEntity(
+                "Idempotency-Key was already used with a different request body".to_string(),
+            )),
+            None => Ok(None),
         }
+    }
 
-        fn set_save_should_fail(&self, should_fail: bool) {
-            *self.should_save_fail.lock().unwrap() = should_fail;
+    /// Creates the message and claims `key` in the same write. Returns the id
+    /// of the stored message, which belongs to an earlier request if a
+    /// concurrent retry with the same key won the race.
+    pub async fn create_idempotent_message(
+        &self,
+        key: IdempotencyKey,
+        new_message: NewContactMessage,
+        site: Option<&Site>,
+    ) -> Result<String, AppError> {
+        match self.save_new_message(new_message, site, Some(&key)).await {
+            Ok(contact_message) => Ok(contact_message.id),
+            Err(AppError::DatabaseError(e)) => match self.replay(&key).await? {
+                Some(message_id) => Ok(message_id),
+                None => Err(AppError::DatabaseError(e)),
+            },
+            Err(e) => Err(e),
         }
+    }
 
-        fn get_all_contact_messages(&self) -> Vec<ContactMessage> {
-            self.contact_messages.lock().unwrap().clone()
-        }
+    pub async fn purge_expired_idempotency_keys(&self, now: i64) -> Result<u64, AppError> {
+        self.idempotency_repo
+            .delete_expired(now)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
     }
 
-    #[async_trait(?Send)]
-    impl ContactMessageRepositoryInterface for MockContactMessageRepository {
-        async fn save(&self, contact: &ContactMessage) -> Result<bool, RepositoryError> {
-            if *self.should_save_fail.lock().unwrap() {
-                return Err(RepositoryError::DatabaseError(
-                    "Mock database error on save".into(),
+    async fn save_new_message(
+        &self,
+        new_message: NewContactMessage,
+        site: Option<&Site>,
+        idempotency_key: Option<&IdempotencyKey>,
+    ) -> Result<ContactMessage, AppError> {
+        let NewContactMessage {
+            category,
+            email,
+            name,
+            message,
+            data,
+            form_id,
+        } = new_message;
+        let mut errors = ValidationErrors::default();
+
+        let category = match (self.category_service.find_active(&category).await?, site) {
+            (Some(c), Some(site)) if !site.accepts_category(&c.slug) => Err(format!(
+                "Category '{category}' is not accepted by site '{}'",
+                site.id
+            )),
+            (Some(c), _) => Ok(c),
+            (None, _) => Err(format!("Category '{category}' is invalid")),
+        }
+        .map_err(|msg| errors.push("category", ValidationError::InvalidCategory(msg)))
+        .ok();
+
+        let form = match &form_id {
+            Some(form_id) => match self.form_repo.find_by_id(form_id).await {
+                Ok(Some(form)) if form.belongs_to(site) => Some(form),
+                Ok(_) => {
+                    errors.push(
+                        "form_id",
+                        ValidationError::InvalidForm(format!("Form '{form_id}' does not exist")),
+                    );
+                    None
+                }
+                Err(e) => return Err(AppError::DatabaseError(e.to_string())),
+            },
+            None => None,
+        };
+        let unstructured = FormSchema::unstructured();
+        let schema = form.as_ref().map_or(&unstructured, |form| &form.schema);
+
+        let Some(category) = category.filter(|_| errors.is_empty()) else {
+            if let Err(field_errors) =
+                ContactMessage::validate(&email, &name, &message, data.as_ref(), schema)
+            {
+                errors.extend(field_errors);
+            }
+            return Err(errors.into());
+        };
+        let mut contact_message =
+            ContactMessage::create(category.slug, email, name, message, data, schema)?;
+        contact_message.priority = category.default_priority;
+        contact_message.site_id = site.map(|site| site.id.clone());
+        contact_message.form_id = form.map(|form| form.id);
+
+        if let Some(existing) = self.find_duplicate(&contact_message).await? {
+            log_info!(
+                "Submission duplicates message {} (fingerprint {})",
+                existing.id,
+                existing.fingerprint
+            );
+            if let Some(DuplicatePolicy::Reject) = self.duplicate_detection.map(|d| d.policy) {
+                return Err(AppError::Conflict(
+                    "An identical message was submitted recently".to_string(),
                 ));
             }
+            // A retry with the same key must replay the message it collapsed into.
+            if let Some(key) = idempotency_key {
+                let record = IdempotencyRecord {
+                    message_id: existing.id.clone(),
+                    ..IdempotencyRecord::new(key, &contact_message)
+                };
+                self.idempotency_repo
+                    .insert(&record)
+                    .await
+                    .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            }
+            return Ok(existing);
+        }
 
-            let mut contact_messages = self.contact_messages.lock().unwrap();
+        let mut event = OutboxEvent::message_created(&contact_message);
+        let idempotency_record =
+            idempotency_key.map(|key| IdempotencyRecord::new(key, &contact_message));
+        self.repo
+            .save(&contact_message, &event, idempotency_record.as_ref())
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
 
-            if let Some(existing_index) = contact_messages.iter().position(|c| c.id == contact.id) {
-                contact_messages[existing_index] = contact.clone();
-            } else {
-                contact_messages.push(contact.clone());
-            }
+        // The message and its event are already stored, so a failed enqueue is
+        // left for the scheduled drain to retry rather than returned to the submitter.
+        if let Err(e) = self
+            .outbox_processor
+            .dispatch(&mut event, Utc::now().timestamp())
+            .await
+        {
+            log_error!(
+                "Failed to dispatch outbox event for message {}: {}",
+                contact_message.id,
+                e
+            );
+        }
+        Ok(contact_message)
+    }
 
-            Ok(true)
+    async fn find_duplicate(
+        &self,
+        contact_message: &ContactMessage,
+    ) -> Result<Option<ContactMessage>, AppError> {
+        let Some(detection) = self.duplicate_detection else {
+            return Ok(None);
+        };
+
+        self.repo
+            .find_recent_by_fingerprint(
+                &contact_message.fingerprint,
+                contact_message.site_id.as_deref(),
+                contact_message.created_at - detection.window_seconds,
+            )
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
+    pub async fn get_message(&self, id: &str) -> Result<ContactMessage, AppError> {
+        self.repo.find_by_id(id).await.map_err(|e| match e {
+            RepositoryError::NotFound(msg) => AppError::NotFound(msg),
+            _ => AppError::DatabaseError(e.to_string()),
+        })
+    }
+
+    pub async fn list_messages(
+        &self,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<ContactMessage>, AppError> {
+        self.repo
+            .list(limit, offset)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
+    pub async fn update_status(
+        &self,
+        id: &str,
+        status: String,
+    ) -> Result<ContactMessage, AppError> {
+        let status = ContactMessageStatus::from_str(&status).map_err(|_| {
+            AppError::from(ValidationError::InvalidStatus(format!(
+                "Status '{status}' is invalid"
+            )))
+        })?;
+
+        let mut contact_message = self.get_message(id).await?;
+        let previous_status = contact_message.status;
+
+        contact_message
+            .update_status(status, Utc::now().timestamp())
+            .map_err(|e| AppError::Conflict(e.to_string()))?;
+
+        let updated = self
+            .repo
+            .update_status(&contact_message, previous_status)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        if !updated {
+            return Err(AppError::Conflict(
+                "Message status was changed by another request".to_string(),
+            ));
         }
+
+        Ok(contact_message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::test_support::{
+        MockCategoryRepository, MockContactMessageRepository, MockEventPublisher,
+        MockFormRepository, test_categories, test_site,
+    };
+    use crate::domain::entity::Form;
+    use crate::domain::enums::{ContactMessageCategory, ContactMessagePriority};
+    use crate::domain::{enums::OutboxStatus, events::DomainEvent};
+    use serde_json::json;
+
+    /// An order form open to every site, and one only the `shop` site may use.
+    fn forms() -> Vec<Form> {
+        let schema: FormSchema = serde_json::from_value(json!({
+            "fields": [
+                { "name": "order_id", "type": "text", "required": true },
+                { "name": "quantity", "type": "number", "min": 1 },
+            ]
+        }))
+        .unwrap();
+        let form = |id: &str, site_id: Option<&str>| Form {
+            id: id.to_string(),
+            site_id: site_id.map(str::to_string),
+            name: id.to_string(),
+            schema: schema.clone(),
+            created_at: 0,
+            updated_at: 0,
+        };
+        vec![form("order", None), form("shop-order", Some("shop"))]
+    }
+
+    fn create_service_with(
+        mock_repo: &Arc<MockContactMessageRepository>,
+        publisher: &Arc<MockEventPublisher>,
+    ) -> ContactMessageService {
+        let outbox_processor = Arc::new(OutboxProcessor::create(
+            mock_repo.clone(),
+            publisher.clone(),
+        ));
+        ContactMessageService::create(
+            mock_repo.clone(),
+            outbox_processor,
+            mock_repo.clone(),
+            Arc::new(MockFormRepository::with_forms(forms())),
+            Arc::new(CategoryService::create(Arc::new(
+                MockCategoryRepository::with_categories(test_categories()),
+            ))),
+            None,
+        )
     }
 
     fn create_service() -> (ContactMessageService, Arc<MockContactMessageRepository>) {
         let mock_repo = Arc::new(MockContactMessageRepository::new());
-        let service = ContactMessageService::create(mock_repo.clone());
+        let service = create_service_with(&mock_repo, &Arc::new(MockEventPublisher::default()));
         (service, mock_repo)
     }
 
@@ -107,10 +383,14 @@ mod tests {
 
         let result = service
             .create_message(
-                "ERROR".to_string(),
-                "test@example.com".to_string(),
-                "John Doe".to_string(),
-                "Test message".to_string(),
+                NewContactMessage {
+                    category: "ERROR".to_string(),
+                    email: "test@example.com".to_string(),
+                    name: "John Doe".to_string(),
+                    message: "Test message".to_string(),
+                    data: None,
+                    form_id: None,
+                },
                 None,
             )
             .await;
@@ -132,17 +412,21 @@ mod tests {
     async fn test_create_message_with_data() {
         let (service, mock_repo) = create_service();
 
-        let mut data = HashMap::new();
-        data.insert("rating".to_string(), "5".to_string());
-        data.insert("testimonial".to_string(), "I love quest-lock".to_string());
+        let mut data = FormData::new();
+        data.insert("rating".to_string(), "5".into());
+        data.insert("testimonial".to_string(), "I love quest-lock".into());
 
         let result = service
             .create_message(
-                "IDEA".to_string(),
-                "user@example.com".to_string(),
-                "Jane Smith".to_string(),
-                "Feature request".to_string(),
-                Some(data.clone()),
+                NewContactMessage {
+                    category: "IDEA".to_string(),
+                    email: "user@example.com".to_string(),
+                    name: "Jane Smith".to_string(),
+                    message: "Feature request".to_string(),
+                    data: Some(data.clone()),
+                    form_id: None,
+                },
+                None,
             )
             .await;
 
@@ -165,10 +449,14 @@ mod tests {
 
         let result = service
             .create_message(
-                "INVALID_CATEGORY".to_string(),
-                "test@example.com".to_string(),
-                "John Doe".to_string(),
-                "Test message".to_string(),
+                NewContactMessage {
+                    category: "INVALID_CATEGORY".to_string(),
+                    email: "test@example.com".to_string(),
+                    name: "John Doe".to_string(),
+                    message: "Test message".to_string(),
+                    data: None,
+                    form_id: None,
+                },
                 None,
             )
             .await;
@@ -180,6 +468,153 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_create_message_invalid_category_reports_other_fields() {
+        let (service, _) = create_service();
+
+        let result = service
+            .create_message(
+                NewContactMessage {
+                    category: "INVALID_CATEGORY".to_string(),
+                    email: "not-an-email".to_string(),
+                    name: "John Doe".to_string(),
+                    message: "Test message".to_string(),
+                    data: None,
+                    form_id: None,
+                },
+                None,
+            )
+            .await;
+
+        let Err(AppError::ValidationError(errors)) = result else {
+            panic!("Expected ValidationError");
+        };
+        let paths: Vec<&str> = errors
+            .violations()
+            .iter()
+            .map(|v| v.path.as_str())
+            .collect();
+        assert_eq!(paths, vec!["category", "email"]);
+    }
+
+    #[tokio::test]
+    async fn test_create_message_records_site() {
+        let (service, mock_repo) = create_service();
+        let site = test_site("shop", vec![]);
+
+        service
+            .create_message(
+                NewContactMessage {
+                    category: "ERROR".to_string(),
+                    email: "test@example.com".to_string(),
+                    name: "John Doe".to_string(),
+                    message: "Test message".to_string(),
+                    data: None,
+                    form_id: None,
+                },
+                Some(&site),
+            )
+            .await
+            .unwrap();
+
+        let saved = mock_repo.get_all_contact_messages();
+        assert_eq!(saved[0].site_id.as_deref(), Some("shop"));
+    }
+
+    #[tokio::test]
+    async fn test_create_message_rejects_category_site_does_not_accept() {
+        let (service, mock_repo) = create_service();
+        let site = test_site("shop", vec![ContactMessageCategory::ERROR]);
+
+        let result = service
+            .create_message(
+                NewContactMessage {
+                    category: "IDEA".to_string(),
+                    email: "test@example.com".to_string(),
+                    name: "John Doe".to_string(),
+                    message: "Test message".to_string(),
+                    data: None,
+                    form_id: None,
+                },
+                Some(&site),
+            )
+            .await;
+
+        let Err(AppError::ValidationError(errors)) = result else {
+            panic!("Expected ValidationError");
+        };
+        assert_eq!(errors.violations()[0].path, "category");
+        assert!(mock_repo.get_all_contact_messages().is_empty());
+    }
+
+    fn order(form_id: &str, data: serde_json::Value) -> NewContactMessage {
+        NewContactMessage {
+            category: "OTHER".to_string(),
+            email: "buyer@example.com".to_string(),
+            name: "Buyer".to_string(),
+            message: "Where is my order?".to_string(),
+            data: data.as_object().cloned(),
+            form_id: Some(form_id.to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_message_types_data_by_form_schema() {
+        let (service, mock_repo) = create_service();
+
+        service
+            .create_message(
+                order("order", json!({ "order_id": "A-1", "quantity": "2" })),
+                None,
+            )
+            .await
+            .unwrap();
+
+        let saved = &mock_repo.get_all_contact_messages()[0];
+        assert_eq!(saved.form_id.as_deref(), Some("order"));
+        assert_eq!(
+            saved.data.clone().map(serde_json::Value::Object),
+            Some(json!({ "order_id": "A-1", "quantity": 2 }))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_message_reports_form_schema_violations() {
+        let (service, mock_repo) = create_service();
+
+        let result = service
+            .create_message(order("order", json!({ "quantity": "0" })), None)
+            .await;
+
+        let Err(AppError::ValidationError(errors)) = result else {
+            panic!("Expected ValidationError");
+        };
+        let paths: Vec<&str> = errors
+            .violations()
+            .iter()
+            .map(|v| v.path.as_str())
+            .collect();
+        assert_eq!(paths, vec!["data.order_id", "data.quantity"]);
+        assert!(mock_repo.get_all_contact_messages().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_create_message_rejects_unknown_or_foreign_form() {
+        let (service, _) = create_service();
+        let blog = test_site("blog", vec![]);
+
+        for (form_id, site) in [("missing", None), ("shop-order", Some(&blog))] {
+            let result = service
+                .create_message(order(form_id, json!({ "order_id": "A-1" })), site)
+                .await;
+
+            let Err(AppError::ValidationError(errors)) = result else {
+                panic!("Expected ValidationError for {form_id}");
+            };
+            assert_eq!(errors.violations()[0].path, "form_id");
+        }
+    }
+
     #[tokio::test]
     async fn test_create_message_database_error() {
         let (service, mock_repo) = create_service();
@@ -188,10 +623,14 @@ mod tests {
 
         let result = service
             .create_message(
-                "OTHER".to_string(),
-                "test@example.com".to_string(),
-                "John Doe".to_string(),
-                "Test message".to_string(),
+                NewContactMessage {
+                    category: "OTHER".to_string(),
+                    email: "test@example.com".to_string(),
+                    name: "John Doe".to_string(),
+                    message: "Test message".to_string(),
+                    data: None,
+                    form_id: None,
+                },
                 None,
             )
             .await;
@@ -210,15 +649,19 @@ mod tests {
     async fn test_all_valid_categories() {
         let (service, mock_repo) = create_service();
 
-        let categories = vec!["ERROR", "IDEA", "TESTIMONIAL", "OTHER"];
+        let categories = ["ERROR", "IDEA", "TESTIMONIAL", "OTHER"];
 
         for (i, category) in categories.iter().enumerate() {
             let result = service
                 .create_message(
-                    category.to_string(),
-                    format!("test{}@example.com", i),
-                    format!("User {}", i),
-                    format!("This is test message number {}", i),
+                    NewContactMessage {
+                        category: category.to_string(),
+                        email: format!("test{}@example.com", i),
+                        name: format!("User {}", i),
+                        message: format!("This is test message number {}", i),
+                        data: None,
+                        form_id: None,
+                    },
                     None,
                 )
                 .await;
@@ -253,10 +696,14 @@ mod tests {
 
         let result = service
             .create_message(
-                "error".to_string(),
-                "test@example.com".to_string(),
-                "John Doe".to_string(),
-                "Test message".to_string(),
+                NewContactMessage {
+                    category: "error".to_string(),
+                    email: "test@example.com".to_string(),
+                    name: "John Doe".to_string(),
+                    message: "Test message".to_string(),
+                    data: None,
+                    form_id: None,
+                },
                 None,
             )
             .await;
@@ -269,5 +716,559 @@ mod tests {
             saved_contact_messages[0].category,
             ContactMessageCategory::ERROR
         );
+        assert_eq!(
+            saved_contact_messages[0].priority,
+            ContactMessagePriority::HIGH
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_message_rejects_inactive_category() {
+        let (mut service, mock_repo) = create_service();
+        let mut categories = test_categories();
+        categories[1].active = false;
+        service.category_service = Arc::new(CategoryService::create(Arc::new(
+            MockCategoryRepository::with_categories(categories),
+        )));
+
+        let result = service
+            .create_message(
+                NewContactMessage {
+                    category: "IDEA".to_string(),
+                    email: "test@example.com".to_string(),
+                    name: "John Doe".to_string(),
+                    message: "Test message".to_string(),
+                    data: None,
+                    form_id: None,
+                },
+                None,
+            )
+            .await;
+
+        let Err(AppError::ValidationError(errors)) = result else {
+            panic!("Expected ValidationError");
+        };
+        assert_eq!(errors.violations()[0].path, "category");
+        assert!(mock_repo.get_all_contact_messages().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_message_success() {
+        let (service, mock_repo) = create_service();
+
+        service
+            .create_message(
+                NewContactMessage {
+                    category: "IDEA".to_string(),
+                    email: "test@example.com".to_string(),
+                    name: "John Doe".to_string(),
+                    message: "Test message".to_string(),
+                    data: None,
+                    form_id: None,
+                },
+                None,
+            )
+            .await
+            .unwrap();
+
+        let saved = mock_repo.get_all_contact_messages()[0].clone();
+        let result = service.get_message(&saved.id).await.unwrap();
+
+        assert_eq!(result.id, saved.id);
+        assert_eq!(result.created_at, saved.created_at);
+    }
+
+    #[tokio::test]
+    async fn test_get_message_not_found() {
+        let (service, _mock_repo) = create_service();
+
+        let result = service.get_message("missing-id").await;
+
+        match result.unwrap_err() {
+            AppError::NotFound(_) => {}
+            _ => panic!("Expected NotFound"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_messages_paginates_newest_first() {
+        let (service, _mock_repo) = create_service();
+
+        for i in 0..3 {
+            service
+                .create_message(
+                    NewContactMessage {
+                        category: "OTHER".to_string(),
+                        email: format!("test{}@example.com", i),
+                        name: format!("User {}", i),
+                        message: format!("This is test message number {}", i),
+                        data: None,
+                        form_id: None,
+                    },
+                    None,
+                )
+                .await
+                .unwrap();
+        }
+
+        let first_page = service.list_messages(2, 0).await.unwrap();
+        assert_eq!(first_page.len(), 2);
+        assert_eq!(first_page[0].email, "test2@example.com");
+        assert_eq!(first_page[1].email, "test1@example.com");
+
+        let second_page = service.list_messages(2, 2).await.unwrap();
+        assert_eq!(second_page.len(), 1);
+        assert_eq!(second_page[0].email, "test0@example.com");
+    }
+
+    async fn create_test_message(
+        service: &ContactMessageService,
+        mock_repo: &MockContactMessageRepository,
+    ) -> ContactMessage {
+        service
+            .create_message(
+                NewContactMessage {
+                    category: "ERROR".to_string(),
+                    email: "test@example.com".to_string(),
+                    name: "John Doe".to_string(),
+                    message: "Test message".to_string(),
+                    data: None,
+                    form_id: None,
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        mock_repo.get_all_contact_messages()[0].clone()
+    }
+
+    #[tokio::test]
+    async fn test_update_status_success() {
+        let (service, mock_repo) = create_service();
+        let contact = create_test_message(&service, &mock_repo).await;
+
+        let result = service
+            .update_status(&contact.id, "resolved".to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(result.status, ContactMessageStatus::RESOLVED);
+        assert!(result.resolved_at.is_some());
+
+        let saved = &mock_repo.get_all_contact_messages()[0];
+        assert_eq!(saved.status, ContactMessageStatus::RESOLVED);
+        assert_eq!(saved.resolved_at, result.resolved_at);
+    }
+
+    #[tokio::test]
+    async fn test_update_status_illegal_transition() {
+        let (service, mock_repo) = create_service();
+        let contact = create_test_message(&service, &mock_repo).await;
+
+        service
+            .update_status(&contact.id, "RESOLVED".to_string())
+            .await
+            .unwrap();
+        let result = service.update_status(&contact.id, "SPAM".to_string()).await;
+
+        match result.unwrap_err() {
+            AppError::Conflict(_) => {}
+            _ => panic!("Expected Conflict"),
+        }
+        assert_eq!(
+            mock_repo.get_all_contact_messages()[0].status,
+            ContactMessageStatus::RESOLVED
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_status_invalid_status() {
+        let (service, mock_repo) = create_service();
+        let contact = create_test_message(&service, &mock_repo).await;
+
+        let result = service
+            .update_status(&contact.id, "CLOSED".to_string())
+            .await;
+
+        match result.unwrap_err() {
+            AppError::ValidationError(_) => {}
+            _ => panic!("Expected ValidationError"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_status_not_found() {
+        let (service, _mock_repo) = create_service();
+
+        let result = service
+            .update_status("missing-id", "TRIAGED".to_string())
+            .await;
+
+        match result.unwrap_err() {
+            AppError::NotFound(_) => {}
+            _ => panic!("Expected NotFound"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_message_enqueues_message_created() {
+        let mock_repo = Arc::new(MockContactMessageRepository::new());
+        let publisher = Arc::new(MockEventPublisher::default());
+        let service = create_service_with(&mock_repo, &publisher);
+
+        service
+            .create_message(
+                NewContactMessage {
+                    category: "IDEA".to_string(),
+                    email: "test@example.com".to_string(),
+                    name: "John Doe".to_string(),
+                    message: "Test message".to_string(),
+                    data: None,
+                    form_id: None,
+                },
+                None,
+            )
+            .await
+            .unwrap();
+
+        let saved_id = mock_repo.get_all_contact_messages()[0].id.clone();
+        let published = publisher.published.lock().unwrap();
+        assert_eq!(published.len(), 1);
+        let DomainEvent::MessageCreated(event) = &published[0];
+        assert_eq!(event.message_id, saved_id);
+
+        let events = mock_repo.get_all_outbox_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].id, event.event_id);
+        assert_eq!(events[0].status, OutboxStatus::DELIVERED);
+    }
+
+    #[tokio::test]
+    async fn test_create_message_succeeds_when_enqueue_fails() {
+        let mock_repo = Arc::new(MockContactMessageRepository::new());
+        let publisher = Arc::new(MockEventPublisher {
+            should_fail: true,
+            ..Default::default()
+        });
+        let service = create_service_with(&mock_repo, &publisher);
+
+        let result = service
+            .create_message(
+                NewContactMessage {
+                    category: "ERROR".to_string(),
+                    email: "test@example.com".to_string(),
+                    name: "John Doe".to_string(),
+                    message: "Test message".to_string(),
+                    data: None,
+                    form_id: None,
+                },
+                None,
+            )
+            .await;
+
+        assert!(result.is_ok());
+        assert_eq!(mock_repo.get_all_contact_messages().len(), 1);
+
+        let events = mock_repo.get_all_outbox_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].status, OutboxStatus::PENDING);
+        assert_eq!(events[0].attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn test_create_message_does_not_enqueue_when_save_fails() {
+        let mock_repo = Arc::new(MockContactMessageRepository::new());
+        mock_repo.set_save_should_fail(true);
+        let publisher = Arc::new(MockEventPublisher::default());
+        let service = create_service_with(&mock_repo, &publisher);
+
+        let result = service
+            .create_message(
+                NewContactMessage {
+                    category: "ERROR".to_string(),
+                    email: "test@example.com".to_string(),
+                    name: "John Doe".to_string(),
+                    message: "Test message".to_string(),
+                    data: None,
+                    form_id: None,
+                },
+                None,
+            )
+            .await;
+
+        assert!(result.is_err());
+        assert!(publisher.published.lock().unwrap().is_empty());
+        assert!(mock_repo.get_all_outbox_events().is_empty());
+    }
+
+    fn idempotency_key(body: serde_json::Value) -> IdempotencyKey {
+        IdempotencyKey::new("key-1".to_string(), &body).unwrap()
+    }
+
+    async fn create_idempotent(
+        service: &ContactMessageService,
+        key: IdempotencyKey,
+    ) -> Result<String, AppError> {
+        service
+            .create_idempotent_message(
+                key,
+                NewContactMessage {
+                    category: "ERROR".to_string(),
+                    email: "test@example.com".to_string(),
+                    name: "John Doe".to_string(),
+                    message: "Test message".to_string(),
+                    data: None,
+                    form_id: None,
+                },
+                None,
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_replay_returns_message_for_same_request() {
+        let (service, mock_repo) = create_service();
+        let key = idempotency_key(serde_json::json!({ "message": "Test message" }));
+
+        assert_eq!(service.replay(&key).await.unwrap(), None);
+        let message_id = create_idempotent(&service, key.clone()).await.unwrap();
+
+        assert_eq!(
+            service.replay(&key).await.unwrap(),
+            Some(message_id.clone())
+        );
+        assert_eq!(mock_repo.get_all_contact_messages().len(), 1);
+        assert_eq!(
+            mock_repo.get_all_idempotency_records()[0].message_id,
+            message_id
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replay_rejects_key_reused_with_different_body() {
+        let (service, _mock_repo) = create_service();
+        create_idempotent(
+            &service,
+            idempotency_key(serde_json::json!({ "message": "First" })),
+        )
+        .await
+        .unwrap();
+
+        let result = service
+            .replay(&idempotency_key(serde_json::json!({ "message": "Second" })))
+            .await;
+
+        match result.unwrap_err() {
+            AppError::UnprocessableEntity(_) => {}
+            _ => panic!("Expected UnprocessableEntity"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_idempotent_message_returns_winner_of_race() {
+        let (service, mock_repo) = create_service();
+        let key = idempotency_key(serde_json::json!({ "message": "Test message" }));
+        let now = Utc::now().timestamp();
+        mock_repo.insert_idempotency_record(IdempotencyRecord {
+            key: key.key.clone(),
+            request_hash: key.request_hash.clone(),
+            message_id: "earlier-message".to_string(),
+            created_at: now,
+            expires_at: now + IdempotencyRecord::TTL_SECONDS,
+        });
+
+        let message_id = create_idempotent(&service, key).await.unwrap();
+
+        assert_eq!(message_id, "earlier-message");
+        assert!(mock_repo.get_all_contact_messages().is_empty());
+        assert!(mock_repo.get_all_outbox_events().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_expired_key_can_be_reused() {
+        let (service, mock_repo) = create_service();
+        let key = idempotency_key(serde_json::json!({ "message": "Test message" }));
+        mock_repo.insert_idempotency_record(IdempotencyRecord {
+            key: key.key.clone(),
+            request_hash: "other-request".to_string(),
+            message_id: "old-message".to_string(),
+            created_at: 0,
+            expires_at: 1,
+        });
+
+        assert_eq!(service.replay(&key).await.unwrap(), None);
+        let message_id = create_idempotent(&service, key).await.unwrap();
+
+        assert_ne!(message_id, "old-message");
+        assert_eq!(mock_repo.get_all_idempotency_records().len(), 1);
+        assert_eq!(service.purge_expired_idempotency_keys(1).await.unwrap(), 0);
+    }
+
+    fn create_service_with_detection(
+        policy: DuplicatePolicy,
+    ) -> (ContactMessageService, Arc<MockContactMessageRepository>) {
+        let (mut service, mock_repo) = create_service();
+        service.duplicate_detection = Some(DuplicateDetection {
+            window_seconds: 600,
+            policy,
+        });
+        (service, mock_repo)
+    }
+
+    async fn submit(service: &ContactMessageService, message: &str) -> Result<(), AppError> {
+        service
+            .create_message(
+                NewContactMessage {
+                    category: "ERROR".to_string(),
+                    email: "test@example.com".to_string(),
+                    name: "John Doe".to_string(),
+                    message: message.to_string(),
+                    data: None,
+                    form_id: None,
+                },
+                None,
+            )
+            .await
+    }
+
+    #[test]
+    fn test_parse_duplicate_policy() {
+        assert_eq!(
+            DuplicatePolicy::from_str(" Reject ").unwrap(),
+            DuplicatePolicy::Reject
+        );
+        assert_eq!(
+            DuplicatePolicy::from_str("collapse").unwrap(),
+            DuplicatePolicy::Collapse
+        );
+        assert!(DuplicatePolicy::from_str("ignore").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_duplicates_are_collapsed() {
+        let (service, mock_repo) = create_service_with_detection(DuplicatePolicy::Collapse);
+
+        submit(&service, "The app  crashes on start").await.unwrap();
+        submit(&service, "The app crashes\non start ")
+            .await
+            .unwrap();
+
+        assert_eq!(mock_repo.get_all_contact_messages().len(), 1);
+        assert_eq!(mock_repo.get_all_outbox_events().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_duplicates_are_rejected() {
+        let (service, mock_repo) = create_service_with_detection(DuplicatePolicy::Reject);
+
+        submit(&service, "The app crashes on start").await.unwrap();
+        let result = submit(&service, "The app crashes on start").await;
+
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+        assert_eq!(mock_repo.get_all_contact_messages().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_duplicates_on_different_sites_are_stored() {
+        let (service, mock_repo) = create_service_with_detection(DuplicatePolicy::Reject);
+        let shop = test_site("shop", vec![]);
+        let blog = test_site("blog", vec![]);
+
+        for site in [Some(&shop), Some(&blog), None] {
+            service
+                .create_message(
+                    NewContactMessage {
+                        category: "ERROR".to_string(),
+                        email: "test@example.com".to_string(),
+                        name: "John Doe".to_string(),
+                        message: "The app crashes on start".to_string(),
+                        data: None,
+                        form_id: None,
+                    },
+                    site,
+                )
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(mock_repo.get_all_contact_messages().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_outside_window_is_stored() {
+        let (service, mock_repo) = create_service_with_detection(DuplicatePolicy::Reject);
+        let mut earlier = ContactMessage::create(
+            ContactMessageCategory::ERROR,
+            "test@example.com".to_string(),
+            "John Doe".to_string(),
+            "The app crashes on start".to_string(),
+            None,
+            &FormSchema::unstructured(),
+        )
+        .unwrap();
+        earlier.created_at -= 601;
+        mock_repo
+            .save(&earlier, &OutboxEvent::message_created(&earlier), None)
+            .await
+            .unwrap();
+
+        submit(&service, "The app crashes on start").await.unwrap();
+
+        assert_eq!(mock_repo.get_all_contact_messages().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_duplicates_are_stored_when_detection_is_disabled() {
+        let (service, mock_repo) = create_service();
+
+        submit(&service, "The app crashes on start").await.unwrap();
+        submit(&service, "The app crashes on start").await.unwrap();
+
+        assert_eq!(mock_repo.get_all_contact_messages().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_collapsed_idempotent_message_returns_original_id() {
+        let (service, _mock_repo) = create_service_with_detection(DuplicatePolicy::Collapse);
+        let first = create_idempotent(
+            &service,
+            idempotency_key(serde_json::json!({ "attempt": 1 })),
+        )
+        .await
+        .unwrap();
+
+        let second = service
+            .create_idempotent_message(
+                IdempotencyKey::new("key-2".to_string(), &serde_json::json!({})).unwrap(),
+                NewContactMessage {
+                    category: "ERROR".to_string(),
+                    email: "Test@Example.com".to_string(),
+                    name: "John Doe".to_string(),
+                    message: "Test message".to_string(),
+                    data: None,
+                    form_id: None,
+                },
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(first, second);
+    }
+
+    #[tokio::test]
+    async fn test_collapsed_idempotent_message_stores_key() {
+        let (service, mock_repo) = create_service_with_detection(DuplicatePolicy::Collapse);
+        let first = create_idempotent(
+            &service,
+            idempotency_key(serde_json::json!({ "attempt": 1 })),
+        )
+        .await
+        .unwrap();
+        let key = IdempotencyKey::new("key-2".to_string(), &serde_json::json!({})).unwrap();
+        create_idempotent(&service, key.clone()).await.unwrap();
+
+        assert_eq!(service.replay(&key).await.unwrap(), Some(first));
+        assert_eq!(mock_repo.get_all_idempotency_records().len(), 2);
     }
 }
@@ -0,0 +1,17 @@
+use crate::application::exceptions::AppError;
+use async_trait::async_trait;
+
+/// A plain-text email, sent from the provider's configured address.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutgoingEmail {
+    pub to: Vec<String>,
+    pub reply_to: Option<String>,
+    pub subject: String,
+    pub text: String,
+}
+
+/// Delivers email through a transactional email service.
+#[async_trait(?Send)]
+pub trait EmailProviderTrait: Send + Sync {
+    async fn send(&self, email: &OutgoingEmail) -> Result<(), AppError>;
+}
@@ -0,0 +1,113 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    application::{event_consumer::PostProcessingStepTrait, exceptions::AppError},
+    domain::{
+        entity::ContactMessage,
+        repository::ContactMessageRepository as ContactMessageRepositoryInterface,
+    },
+};
+
+/// Records how many messages the sender has sent before, so the team can
+/// tell returning contacts from new ones.
+pub struct EnrichmentStep {
+    repo: Arc<dyn ContactMessageRepositoryInterface>,
+}
+
+impl EnrichmentStep {
+    pub fn create(
+        repo: Arc<dyn ContactMessageRepositoryInterface>,
+    ) -> Arc<dyn PostProcessingStepTrait> {
+        Arc::new(Self { repo })
+    }
+}
+
+#[async_trait(?Send)]
+impl PostProcessingStepTrait for EnrichmentStep {
+    fn name(&self) -> &'static str {
+        "enrichment"
+    }
+
+    async fn run(&self, message: &mut ContactMessage) -> Result<(), AppError> {
+        if message.previous_messages.is_some() {
+            return Ok(());
+        }
+
+        let previous_messages = self
+            .repo
+            .count_previous(message)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        message.previous_messages = Some(previous_messages);
+
+        self.repo
+            .update_enrichment(message)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::test_support::MockContactMessageRepository;
+    use crate::domain::{
+        entity::OutboxEvent, enums::ContactMessageCategory, form_schema::FormSchema,
+        repository::ContactMessageRepository,
+    };
+
+    async fn stored_message(repo: &MockContactMessageRepository, email: &str) -> ContactMessage {
+        let message = ContactMessage::create(
+            ContactMessageCategory::IDEA,
+            email.to_string(),
+            "Jane Smith".to_string(),
+            "I have an idea".to_string(),
+            None,
+            &FormSchema::unstructured(),
+        )
+        .unwrap();
+        repo.save(&message, &OutboxEvent::message_created(&message), None)
+            .await
+            .unwrap();
+        message
+    }
+
+    #[tokio::test]
+    async fn test_counts_earlier_messages_from_the_same_sender() {
+        let repo = Arc::new(MockContactMessageRepository::new());
+        stored_message(&repo, "jane@example.com").await;
+        stored_message(&repo, "other@example.com").await;
+        let mut message = stored_message(&repo, "Jane@Example.com").await;
+        stored_message(&repo, "jane@example.com").await;
+
+        EnrichmentStep::create(repo.clone())
+            .run(&mut message)
+            .await
+            .unwrap();
+
+        assert_eq!(message.previous_messages, Some(1));
+        assert_eq!(
+            repo.find_by_id(&message.id)
+                .await
+                .unwrap()
+                .previous_messages,
+            Some(1)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_first_message_has_no_previous_messages() {
+        let repo = Arc::new(MockContactMessageRepository::new());
+        let mut message = stored_message(&repo, "jane@example.com").await;
+
+        EnrichmentStep::create(repo.clone())
+            .run(&mut message)
+            .await
+            .unwrap();
+
+        assert_eq!(message.previous_messages, Some(0));
+    }
+}
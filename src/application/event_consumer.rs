@@ -0,0 +1,264 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    application::{exceptions::AppError, notification_service::NotificationServiceTrait},
+    domain::{
+        entity::ContactMessage,
+        enums::ContactMessageStatus,
+        events::{DomainEvent, MessageCreated},
+        exceptions::RepositoryError,
+        repository::ContactMessageRepository as ContactMessageRepositoryInterface,
+    },
+    log_error, log_info,
+};
+
+/// A unit of post-processing run by the queue consumer for each new message.
+///
+/// Steps run in order and may update the message (e.g. mark it as spam) for
+/// the steps after them. An error stops the pipeline so the queue retries
+/// the whole event, which means earlier steps must tolerate running twice.
+#[async_trait(?Send)]
+pub trait PostProcessingStepTrait: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn run(&self, message: &mut ContactMessage) -> Result<(), AppError>;
+}
+
+/// Tells the team about a new message, unless it has been marked as spam.
+///
+/// A failed notifier is logged rather than returned: retrying the event would
+/// resend every notification that did get through.
+pub struct NotificationStep {
+    notification_services: Vec<Arc<dyn NotificationServiceTrait>>,
+}
+
+impl NotificationStep {
+    pub fn create(
+        notification_services: Vec<Arc<dyn NotificationServiceTrait>>,
+    ) -> Arc<dyn PostProcessingStepTrait> {
+        Arc::new(Self {
+            notification_services,
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl PostProcessingStepTrait for NotificationStep {
+    fn name(&self) -> &'static str {
+        "notification"
+    }
+
+    async fn run(&self, message: &mut ContactMessage) -> Result<(), AppError> {
+        if message.status == ContactMessageStatus::SPAM {
+            return Ok(());
+        }
+
+        for notification_service in &self.notification_services {
+            if let Err(e) = notification_service.notify(message).await {
+                log_error!(
+                    "Failed to send notification for message {}: {}",
+                    message.id,
+                    e
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Handles events delivered by the queue.
+pub struct EventConsumer {
+    repo: Arc<dyn ContactMessageRepositoryInterface>,
+    steps: Vec<Arc<dyn PostProcessingStepTrait>>,
+}
+
+impl EventConsumer {
+    pub fn create(
+        repo: Arc<dyn ContactMessageRepositoryInterface>,
+        steps: Vec<Arc<dyn PostProcessingStepTrait>>,
+    ) -> Self {
+        Self { repo, steps }
+    }
+
+    /// Returns an error when the event should be retried.
+    pub async fn handle(&self, event: &DomainEvent) -> Result<(), AppError> {
+        match event {
+            DomainEvent::MessageCreated(event) => self.handle_message_created(event).await,
+        }
+    }
+
+    async fn handle_message_created(&self, event: &MessageCreated) -> Result<(), AppError> {
+        let mut message = match self.repo.find_by_id(&event.message_id).await {
+            Ok(message) => message,
+            Err(RepositoryError::NotFound(_)) => {
+                log_info!(
+                    "Skipping event {}: message {} no longer exists",
+                    event.event_id,
+                    event.message_id
+                );
+                return Ok(());
+            }
+            Err(e) => return Err(AppError::DatabaseError(e.to_string())),
+        };
+
+        for step in &self.steps {
+            step.run(&mut message).await.map_err(|e| {
+                log_error!(
+                    "Step '{}' failed for message {}: {}",
+                    step.name(),
+                    message.id,
+                    e
+                );
+                e
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::{
+        auto_reply::{AutoReplyStep, AutoReplyTemplates},
+        test_support::{MockContactMessageRepository, MockEmailProvider, MockNotificationService},
+    };
+    use crate::domain::form_schema::FormSchema;
+    use crate::domain::{
+        entity::OutboxEvent, enums::ContactMessageCategory, repository::ContactMessageRepository,
+    };
+    use std::sync::Mutex;
+
+    struct RecordingStep {
+        name: &'static str,
+        calls: Arc<Mutex<Vec<&'static str>>>,
+        mark_spam: bool,
+    }
+
+    #[async_trait(?Send)]
+    impl PostProcessingStepTrait for RecordingStep {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        async fn run(&self, message: &mut ContactMessage) -> Result<(), AppError> {
+            self.calls.lock().unwrap().push(self.name);
+            if self.mark_spam {
+                message.status = ContactMessageStatus::SPAM;
+            }
+            Ok(())
+        }
+    }
+
+    async fn stored_event(repo: &MockContactMessageRepository) -> (ContactMessage, DomainEvent) {
+        let contact = ContactMessage::create(
+            ContactMessageCategory::ERROR,
+            "test@example.com".to_string(),
+            "John Doe".to_string(),
+            "Test message".to_string(),
+            None,
+            &FormSchema::unstructured(),
+        )
+        .unwrap();
+        let outbox_event = OutboxEvent::message_created(&contact);
+        repo.save(&contact, &outbox_event, None).await.unwrap();
+        (contact, outbox_event.domain_event().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_message_created_runs_steps_in_order() {
+        let repo = Arc::new(MockContactMessageRepository::new());
+        let calls = Arc::new(Mutex::new(vec![]));
+        let steps: Vec<Arc<dyn PostProcessingStepTrait>> = vec![
+            Arc::new(RecordingStep {
+                name: "first",
+                calls: calls.clone(),
+                mark_spam: false,
+            }),
+            Arc::new(RecordingStep {
+                name: "second",
+                calls: calls.clone(),
+                mark_spam: false,
+            }),
+        ];
+        let (_, event) = stored_event(&repo).await;
+
+        let result = EventConsumer::create(repo.clone(), steps)
+            .handle(&event)
+            .await;
+
+        assert!(result.is_ok());
+        assert_eq!(*calls.lock().unwrap(), vec!["first", "second"]);
+    }
+
+    #[tokio::test]
+    async fn test_notification_failure_does_not_retry_event() {
+        let repo = Arc::new(MockContactMessageRepository::new());
+        let failing = Arc::new(MockNotificationService {
+            should_fail: true,
+            ..Default::default()
+        });
+        let working = Arc::new(MockNotificationService::default());
+        let provider = Arc::new(MockEmailProvider::default());
+        let templates =
+            AutoReplyTemplates::parse(r#"{"*": {"subject": "Thanks", "body": "Hi {{name}}"}}"#)
+                .unwrap();
+        let steps: Vec<Arc<dyn PostProcessingStepTrait>> = vec![
+            NotificationStep::create(vec![failing.clone(), working.clone()]),
+            AutoReplyStep::create(repo.clone(), provider.clone(), templates),
+        ];
+        let (contact, event) = stored_event(&repo).await;
+
+        let result = EventConsumer::create(repo.clone(), steps)
+            .handle(&event)
+            .await;
+
+        // The event is acknowledged, so nothing is notified a second time.
+        assert!(result.is_ok());
+        assert_eq!(*failing.notified.lock().unwrap(), vec![contact.id.clone()]);
+        assert_eq!(*working.notified.lock().unwrap(), vec![contact.id]);
+        assert_eq!(provider.sent.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_spam_is_not_notified() {
+        let repo = Arc::new(MockContactMessageRepository::new());
+        let notifier = Arc::new(MockNotificationService::default());
+        let steps: Vec<Arc<dyn PostProcessingStepTrait>> = vec![
+            Arc::new(RecordingStep {
+                name: "spam",
+                calls: Arc::new(Mutex::new(vec![])),
+                mark_spam: true,
+            }),
+            NotificationStep::create(vec![notifier.clone()]),
+        ];
+        let (_, event) = stored_event(&repo).await;
+
+        let result = EventConsumer::create(repo.clone(), steps)
+            .handle(&event)
+            .await;
+
+        assert!(result.is_ok());
+        assert!(notifier.notified.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_missing_message_is_acknowledged() {
+        let repo = Arc::new(MockContactMessageRepository::new());
+        let notifier = Arc::new(MockNotificationService::default());
+        let (_, event) = stored_event(&MockContactMessageRepository::new()).await;
+
+        let result = EventConsumer::create(
+            repo.clone(),
+            vec![NotificationStep::create(vec![notifier.clone()])],
+        )
+        .handle(&event)
+        .await;
+
+        assert!(result.is_ok());
+        assert!(notifier.notified.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,8 @@
+use async_trait::async_trait;
+
+use crate::{application::exceptions::AppError, domain::events::DomainEvent};
+
+#[async_trait(?Send)]
+pub trait EventPublisherTrait: Send + Sync {
+    async fn publish(&self, event: &DomainEvent) -> Result<(), AppError>;
+}
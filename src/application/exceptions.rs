@@ -1,10 +1,15 @@
+use crate::domain::exceptions::{ValidationError, ValidationErrors};
+
 #[derive(Debug)]
 pub enum AppError {
     DatabaseError(String),
     NotFound(String),
     Unauthorised(String),
+    Forbidden(String),
+    Conflict(String),
     InternalError,
-    ValidationError(String),
+    ValidationError(ValidationErrors),
+    UnprocessableEntity(String),
 }
 
 impl std::fmt::Display for AppError {
@@ -13,10 +18,25 @@ impl std::fmt::Display for AppError {
             AppError::DatabaseError(msg) => write!(f, "Database error: {msg}"),
             AppError::NotFound(msg) => write!(f, "Not found: {msg}"),
             AppError::Unauthorised(msg) => write!(f, "Unauthorised: {msg}"),
+            AppError::Forbidden(msg) => write!(f, "Forbidden: {msg}"),
+            AppError::Conflict(msg) => write!(f, "Conflict: {msg}"),
             AppError::InternalError => write!(f, "Internal server error"),
-            AppError::ValidationError(msg) => write!(f, "Validation error: {msg}"),
+            AppError::ValidationError(e) => write!(f, "Validation error: {e}"),
+            AppError::UnprocessableEntity(msg) => write!(f, "Unprocessable entity: {msg}"),
         }
     }
 }
 
 impl std::error::Error for AppError {}
+
+impl From<ValidationErrors> for AppError {
+    fn from(errors: ValidationErrors) -> Self {
+        AppError::ValidationError(errors)
+    }
+}
+
+impl From<ValidationError> for AppError {
+    fn from(error: ValidationError) -> Self {
+        AppError::ValidationError(error.into())
+    }
+}
@@ -1,3 +1,18 @@
+pub mod authentication_service;
+pub mod auto_reply;
+pub mod category_service;
 pub mod contact_message_service;
+pub mod email_provider;
+pub mod enrichment;
+pub mod event_consumer;
+pub mod event_publisher;
 pub mod exceptions;
+pub mod notification_service;
+pub mod outbox_processor;
+pub mod rate_limiter;
 pub mod request_validation_service;
+pub mod site_service;
+pub mod spam_scoring;
+
+#[cfg(test)]
+pub mod test_support;
@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+use crate::{application::exceptions::AppError, domain::entity::ContactMessage};
+use async_trait::async_trait;
+
+#[async_trait(?Send)]
+pub trait NotificationServiceTrait: Send + Sync {
+    async fn notify(&self, message: &ContactMessage) -> Result<(), AppError>;
+}
+
+/// Maps message categories to the team members who should hear about them.
+///
+/// Parsed from `CATEGORY=a@example.com,b@example.com;*=team@example.com`,
+/// where `*` is the fallback for categories without their own entry.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecipientRouting {
+    by_category: HashMap<String, Vec<String>>,
+    default: Vec<String>,
+}
+
+impl RecipientRouting {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let mut routing = Self::default();
+
+        for entry in value.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+            let (category, recipients) = entry.split_once('=').ok_or_else(|| {
+                format!("Routing entry '{entry}' must have the form CATEGORY=emails")
+            })?;
+
+            let recipients: Vec<String> = recipients
+                .split(',')
+                .map(|r| r.trim().to_string())
+                .filter(|r| !r.is_empty())
+                .collect();
+
+            match category.trim() {
+                "*" => routing.default = recipients,
+                category => {
+                    routing
+                        .by_category
+                        .insert(category.to_uppercase(), recipients);
+                }
+            }
+        }
+
+        Ok(routing)
+    }
+
+    pub fn recipients_for(&self, category: &str) -> &[String] {
+        self.by_category
+            .get(&category.to_uppercase())
+            .unwrap_or(&self.default)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_routing() {
+        let routing = RecipientRouting::parse(
+            "ERROR=oncall@example.com, dev@example.com; idea=product@example.com; *=team@example.com",
+        )
+        .unwrap();
+
+        assert_eq!(
+            routing.recipients_for("ERROR"),
+            ["oncall@example.com", "dev@example.com"]
+        );
+        assert_eq!(routing.recipients_for("IDEA"), ["product@example.com"]);
+        assert_eq!(routing.recipients_for("OTHER"), ["team@example.com"]);
+    }
+
+    #[test]
+    fn test_parse_routing_without_default() {
+        let routing = RecipientRouting::parse("ERROR=oncall@example.com").unwrap();
+
+        assert_eq!(routing.recipients_for("error"), ["oncall@example.com"]);
+        assert!(routing.recipients_for("TESTIMONIAL").is_empty());
+    }
+
+    #[test]
+    fn test_parse_routing_empty_and_invalid() {
+        assert_eq!(
+            RecipientRouting::parse("").unwrap(),
+            RecipientRouting::default()
+        );
+        assert!(RecipientRouting::parse("ERROR").is_err());
+    }
+}
@@ -0,0 +1,282 @@
+use std::sync::Arc;
+
+use crate::{
+    application::{event_publisher::EventPublisherTrait, exceptions::AppError},
+    domain::{
+        entity::OutboxEvent, enums::OutboxStatus,
+        repository::OutboxRepository as OutboxRepositoryInterface,
+    },
+    log_error,
+};
+
+/// Number of due events handled per scheduled run.
+pub const DEFAULT_BATCH_SIZE: u32 = 50;
+
+/// How long a claimed event is hidden from other runs. An attempt that dies
+/// before recording its outcome is picked up again once the lease runs out.
+pub const LEASE_SECONDS: i64 = 60;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DrainSummary {
+    pub delivered: u32,
+    pub retrying: u32,
+    pub dead: u32,
+}
+
+/// Relays outbox events to the event publisher and records the outcome of
+/// each attempt. Delivery is at-least-once, so consumers may see an event twice.
+pub struct OutboxProcessor {
+    outbox_repo: Arc<dyn OutboxRepositoryInterface>,
+    event_publisher: Arc<dyn EventPublisherTrait>,
+}
+
+impl OutboxProcessor {
+    pub fn create(
+        outbox_repo: Arc<dyn OutboxRepositoryInterface>,
+        event_publisher: Arc<dyn EventPublisherTrait>,
+    ) -> Self {
+        Self {
+            outbox_repo,
+            event_publisher,
+        }
+    }
+
+    /// Claims `event`, attempts its delivery and persists its new state.
+    /// Returns `false` without publishing if another run has claimed it.
+    pub async fn dispatch(&self, event: &mut OutboxEvent, now: i64) -> Result<bool, AppError> {
+        let claimed = self
+            .outbox_repo
+            .claim(&event.id, now, now + LEASE_SECONDS)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        if !claimed {
+            return Ok(false);
+        }
+
+        match self.deliver(event).await {
+            Ok(()) => event.mark_delivered(now),
+            Err(e) => {
+                log_error!(
+                    "Outbox event {} failed (attempt {}): {}",
+                    event.id,
+                    event.attempts + 1,
+                    e
+                );
+                event.record_failure(e, now);
+            }
+        }
+
+        self.outbox_repo
+            .update(event)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        Ok(true)
+    }
+
+    /// Dispatches up to `batch_size` events that are due at `now`.
+    pub async fn drain(&self, now: i64, batch_size: u32) -> Result<DrainSummary, AppError> {
+        let events = self
+            .outbox_repo
+            .find_due(now, batch_size)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let mut summary = DrainSummary::default();
+        for mut event in events {
+            match self.dispatch(&mut event, now).await {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(e) => {
+                    log_error!("Failed to record outbox event {}: {}", event.id, e);
+                    continue;
+                }
+            }
+
+            match event.status {
+                OutboxStatus::DELIVERED => summary.delivered += 1,
+                OutboxStatus::PENDING => summary.retrying += 1,
+                OutboxStatus::DEAD => summary.dead += 1,
+            }
+        }
+
+        Ok(summary)
+    }
+
+    async fn deliver(&self, event: &OutboxEvent) -> Result<(), String> {
+        let domain_event = event.domain_event()?;
+
+        self.event_publisher
+            .publish(&domain_event)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::test_support::{MockContactMessageRepository, MockEventPublisher};
+    use crate::domain::form_schema::FormSchema;
+    use crate::domain::repository::ContactMessageRepository;
+    use crate::domain::{entity::ContactMessage, enums::ContactMessageCategory};
+
+    async fn stored_message(repo: &MockContactMessageRepository) -> (ContactMessage, OutboxEvent) {
+        let contact = ContactMessage::create(
+            ContactMessageCategory::ERROR,
+            "test@example.com".to_string(),
+            "John Doe".to_string(),
+            "Test message".to_string(),
+            None,
+            &FormSchema::unstructured(),
+        )
+        .unwrap();
+        let event = OutboxEvent::message_created(&contact);
+        repo.save(&contact, &event, None).await.unwrap();
+        (contact, event)
+    }
+
+    fn processor(
+        repo: &Arc<MockContactMessageRepository>,
+        publisher: &Arc<MockEventPublisher>,
+    ) -> OutboxProcessor {
+        OutboxProcessor::create(repo.clone(), publisher.clone())
+    }
+
+    #[tokio::test]
+    async fn test_drain_delivers_due_events() {
+        let repo = Arc::new(MockContactMessageRepository::new());
+        let publisher = Arc::new(MockEventPublisher::default());
+        let (_, event) = stored_message(&repo).await;
+
+        let summary = processor(&repo, &publisher)
+            .drain(event.next_attempt_at, DEFAULT_BATCH_SIZE)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            summary,
+            DrainSummary {
+                delivered: 1,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            *publisher.published.lock().unwrap(),
+            vec![event.domain_event().unwrap()]
+        );
+        assert_eq!(
+            repo.get_all_outbox_events()[0].status,
+            OutboxStatus::DELIVERED
+        );
+    }
+
+    #[tokio::test]
+    async fn test_drain_skips_events_not_yet_due() {
+        let repo = Arc::new(MockContactMessageRepository::new());
+        let publisher = Arc::new(MockEventPublisher::default());
+        let (_, event) = stored_message(&repo).await;
+
+        let summary = processor(&repo, &publisher)
+            .drain(event.next_attempt_at - 1, DEFAULT_BATCH_SIZE)
+            .await
+            .unwrap();
+
+        assert_eq!(summary, DrainSummary::default());
+        assert!(publisher.published.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_failed_delivery_is_retried_with_backoff() {
+        let repo = Arc::new(MockContactMessageRepository::new());
+        let publisher = Arc::new(MockEventPublisher {
+            should_fail: true,
+            ..Default::default()
+        });
+        let (_, event) = stored_message(&repo).await;
+        let now = event.next_attempt_at;
+
+        let summary = processor(&repo, &publisher)
+            .drain(now, DEFAULT_BATCH_SIZE)
+            .await
+            .unwrap();
+
+        assert_eq!(summary.retrying, 1);
+        let stored = &repo.get_all_outbox_events()[0];
+        assert_eq!(stored.status, OutboxStatus::PENDING);
+        assert_eq!(stored.attempts, 1);
+        assert_eq!(
+            stored.next_attempt_at,
+            now + OutboxEvent::backoff_seconds(1)
+        );
+        assert!(stored.last_error.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_event_is_dead_lettered_after_max_attempts() {
+        let repo = Arc::new(MockContactMessageRepository::new());
+        let publisher = Arc::new(MockEventPublisher {
+            should_fail: true,
+            ..Default::default()
+        });
+        let (_, mut event) = stored_message(&repo).await;
+        let processor = processor(&repo, &publisher);
+
+        for _ in 0..OutboxEvent::MAX_ATTEMPTS {
+            let now = event.next_attempt_at;
+            processor.dispatch(&mut event, now).await.unwrap();
+        }
+
+        assert_eq!(event.status, OutboxStatus::DEAD);
+        assert_eq!(repo.get_all_outbox_events()[0].status, OutboxStatus::DEAD);
+        assert_eq!(
+            processor.drain(i64::MAX, DEFAULT_BATCH_SIZE).await.unwrap(),
+            DrainSummary::default()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_claimed_event_is_not_published_again() {
+        let repo = Arc::new(MockContactMessageRepository::new());
+        let publisher = Arc::new(MockEventPublisher::default());
+        let (_, mut event) = stored_message(&repo).await;
+        let now = event.next_attempt_at;
+        let processor = processor(&repo, &publisher);
+
+        // An inline dispatch still in flight holds the claim.
+        assert!(
+            repo.claim(&event.id, now, now + LEASE_SECONDS)
+                .await
+                .unwrap()
+        );
+
+        assert_eq!(
+            processor.drain(now, DEFAULT_BATCH_SIZE).await.unwrap(),
+            DrainSummary::default()
+        );
+        assert!(!processor.dispatch(&mut event, now).await.unwrap());
+        assert!(publisher.published.lock().unwrap().is_empty());
+
+        let summary = processor
+            .drain(now + LEASE_SECONDS, DEFAULT_BATCH_SIZE)
+            .await
+            .unwrap();
+        assert_eq!(summary.delivered, 1);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_payload_fails() {
+        let repo = Arc::new(MockContactMessageRepository::new());
+        let publisher = Arc::new(MockEventPublisher::default());
+        let (_, mut event) = stored_message(&repo).await;
+        event.payload = r#"{"type":"contact_message.unknown"}"#.to_string();
+
+        let now = event.next_attempt_at;
+        processor(&repo, &publisher)
+            .dispatch(&mut event, now)
+            .await
+            .unwrap();
+
+        assert_eq!(event.attempts, 1);
+        assert!(publisher.published.lock().unwrap().is_empty());
+    }
+}
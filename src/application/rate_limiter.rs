@@ -0,0 +1,375 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{application::exceptions::AppError, log_error};
+
+/// Allows `capacity` requests per `window_seconds`, refilled continuously.
+///
+/// Parsed from `REQUESTS/SECONDS`, e.g. `5/60` for five requests a minute.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct RateLimit {
+    pub capacity: u32,
+    pub window_seconds: u32,
+}
+
+impl RateLimit {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let (capacity, window_seconds) = value
+            .split_once('/')
+            .ok_or_else(|| format!("Rate limit '{value}' must have the form REQUESTS/SECONDS"))?;
+
+        let capacity: u32 = capacity
+            .trim()
+            .parse()
+            .map_err(|_| format!("Invalid request count in rate limit '{value}'"))?;
+        let window_seconds: u32 = window_seconds
+            .trim()
+            .parse()
+            .map_err(|_| format!("Invalid window in rate limit '{value}'"))?;
+
+        if capacity == 0 || window_seconds == 0 {
+            return Err(format!("Rate limit '{value}' must be greater than zero"));
+        }
+
+        Ok(Self {
+            capacity,
+            window_seconds,
+        })
+    }
+
+    fn window_ms(&self) -> f64 {
+        self.window_seconds as f64 * 1000.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum RateLimitDecision {
+    Allowed,
+    Limited { retry_after_seconds: u64 },
+}
+
+/// A token bucket holding up to `RateLimit::capacity` tokens. Each request
+/// takes one token and tokens are refilled at `capacity / window` per second.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct TokenBucket {
+    pub tokens: f64,
+    pub updated_at_ms: i64,
+}
+
+impl TokenBucket {
+    pub fn full(limit: &RateLimit, now_ms: i64) -> Self {
+        Self {
+            tokens: limit.capacity as f64,
+            updated_at_ms: now_ms,
+        }
+    }
+
+    pub fn try_acquire(&mut self, limit: &RateLimit, now_ms: i64) -> RateLimitDecision {
+        let capacity = limit.capacity as f64;
+        let elapsed_ms = (now_ms - self.updated_at_ms).max(0) as f64;
+
+        self.tokens = (self.tokens + elapsed_ms * capacity / limit.window_ms()).min(capacity);
+        self.updated_at_ms = now_ms.max(self.updated_at_ms);
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return RateLimitDecision::Allowed;
+        }
+
+        let wait_ms = (1.0 - self.tokens) * limit.window_ms() / capacity;
+        RateLimitDecision::Limited {
+            retry_after_seconds: (wait_ms / 1000.0).ceil().max(1.0) as u64,
+        }
+    }
+}
+
+#[async_trait(?Send)]
+pub trait RateLimiterTrait: Send + Sync {
+    /// Takes one request from the bucket identified by `key`.
+    async fn check(&self, key: &str, limit: &RateLimit) -> Result<RateLimitDecision, AppError>;
+}
+
+/// Applies the per-IP and per-email limits to contact form submissions.
+pub struct RateLimitService {
+    limiter: Arc<dyn RateLimiterTrait>,
+    per_ip: Option<RateLimit>,
+    per_email: Option<RateLimit>,
+}
+
+impl RateLimitService {
+    pub fn create(
+        limiter: Arc<dyn RateLimiterTrait>,
+        per_ip: Option<RateLimit>,
+        per_email: Option<RateLimit>,
+    ) -> Self {
+        Self {
+            limiter,
+            per_ip,
+            per_email,
+        }
+    }
+
+    /// Lowercases the address and drops any `+tag` so that trivial variations
+    /// of one mailbox share a bucket.
+    pub fn normalize_email(email: &str) -> String {
+        let email = email.trim().to_lowercase();
+        match email.split_once('@') {
+            Some((local, domain)) => {
+                let local = local.split_once('+').map_or(local, |(base, _)| base);
+                format!("{local}@{domain}")
+            }
+            None => email,
+        }
+    }
+
+    /// Returns the longest wait required by any exceeded limit. If the limiter
+    /// itself is unavailable the submission is allowed, so an outage of the
+    /// rate-limiting backend never takes the contact form down with it.
+    ///
+    /// Without a known client IP only the per-email limit applies, rather than
+    /// every such client sharing one bucket.
+    pub async fn check_submission(
+        &self,
+        client_ip: Option<&str>,
+        email: &str,
+    ) -> RateLimitDecision {
+        let email_key = hex::encode(Sha256::digest(Self::normalize_email(email).as_bytes()));
+        let checks = [
+            (
+                self.per_ip.filter(|_| client_ip.is_some()),
+                format!("ip:{}", client_ip.unwrap_or_default()),
+            ),
+            (self.per_email, format!("email:{email_key}")),
+        ];
+
+        let mut decision = RateLimitDecision::Allowed;
+        for (limit, key) in checks {
+            let Some(limit) = limit else {
+                continue;
+            };
+
+            match self.limiter.check(&key, &limit).await {
+                Ok(RateLimitDecision::Limited {
+                    retry_after_seconds,
+                }) => {
+                    let longest = match decision {
+                        RateLimitDecision::Limited {
+                            retry_after_seconds: current,
+                        } => current.max(retry_after_seconds),
+                        RateLimitDecision::Allowed => retry_after_seconds,
+                    };
+                    decision = RateLimitDecision::Limited {
+                        retry_after_seconds: longest,
+                    };
+                }
+                Ok(RateLimitDecision::Allowed) => {}
+                Err(e) => log_error!("Rate limiter unavailable for {}: {}", key, e),
+            }
+        }
+
+        decision
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    const LIMIT: RateLimit = RateLimit {
+        capacity: 3,
+        window_seconds: 60,
+    };
+
+    #[test]
+    fn test_parse_rate_limit() {
+        assert_eq!(RateLimit::parse("5/60").unwrap().capacity, 5);
+        assert_eq!(RateLimit::parse(" 3 / 3600 ").unwrap().window_seconds, 3600);
+        assert!(RateLimit::parse("5").is_err());
+        assert!(RateLimit::parse("0/60").is_err());
+        assert!(RateLimit::parse("five/60").is_err());
+    }
+
+    #[test]
+    fn test_bucket_allows_burst_up_to_capacity() {
+        let mut bucket = TokenBucket::full(&LIMIT, 0);
+
+        for _ in 0..3 {
+            assert_eq!(bucket.try_acquire(&LIMIT, 0), RateLimitDecision::Allowed);
+        }
+        assert_eq!(
+            bucket.try_acquire(&LIMIT, 0),
+            RateLimitDecision::Limited {
+                retry_after_seconds: 20
+            }
+        );
+    }
+
+    #[test]
+    fn test_bucket_refills_over_time() {
+        let mut bucket = TokenBucket::full(&LIMIT, 0);
+        for _ in 0..3 {
+            bucket.try_acquire(&LIMIT, 0);
+        }
+
+        // One token is refilled every 20 seconds.
+        assert_eq!(
+            bucket.try_acquire(&LIMIT, 15_000),
+            RateLimitDecision::Limited {
+                retry_after_seconds: 5
+            }
+        );
+        assert_eq!(
+            bucket.try_acquire(&LIMIT, 20_000),
+            RateLimitDecision::Allowed
+        );
+        assert!(matches!(
+            bucket.try_acquire(&LIMIT, 20_000),
+            RateLimitDecision::Limited { .. }
+        ));
+    }
+
+    #[test]
+    fn test_bucket_never_exceeds_capacity() {
+        let mut bucket = TokenBucket::full(&LIMIT, 0);
+
+        bucket.try_acquire(&LIMIT, 3_600_000);
+
+        assert_eq!(bucket.tokens, 2.0);
+    }
+
+    #[test]
+    fn test_bucket_ignores_clock_going_backwards() {
+        let mut bucket = TokenBucket::full(&LIMIT, 10_000);
+        bucket.tokens = 0.0;
+
+        let decision = bucket.try_acquire(&LIMIT, 5_000);
+
+        assert!(matches!(decision, RateLimitDecision::Limited { .. }));
+        assert_eq!(bucket.updated_at_ms, 10_000);
+    }
+
+    #[test]
+    fn test_normalize_email() {
+        assert_eq!(
+            RateLimitService::normalize_email(" Jane.Smith+spam@Example.COM "),
+            "jane.smith@example.com"
+        );
+        assert_eq!(
+            RateLimitService::normalize_email("not-an-email"),
+            "not-an-email"
+        );
+    }
+
+    #[derive(Default)]
+    struct InMemoryRateLimiter {
+        buckets: Mutex<HashMap<String, TokenBucket>>,
+        should_fail: bool,
+    }
+
+    #[async_trait(?Send)]
+    impl RateLimiterTrait for InMemoryRateLimiter {
+        async fn check(&self, key: &str, limit: &RateLimit) -> Result<RateLimitDecision, AppError> {
+            if self.should_fail {
+                return Err(AppError::InternalError);
+            }
+            let mut buckets = self.buckets.lock().unwrap();
+            let bucket = buckets
+                .entry(key.to_string())
+                .or_insert_with(|| TokenBucket::full(limit, 0));
+            Ok(bucket.try_acquire(limit, 0))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_submission_limits_by_email_across_ips() {
+        let service = RateLimitService::create(
+            Arc::new(InMemoryRateLimiter::default()),
+            Some(LIMIT),
+            Some(RateLimit {
+                capacity: 1,
+                window_seconds: 3600,
+            }),
+        );
+
+        assert_eq!(
+            service
+                .check_submission(Some("1.1.1.1"), "user@example.com")
+                .await,
+            RateLimitDecision::Allowed
+        );
+        assert_eq!(
+            service
+                .check_submission(Some("2.2.2.2"), "User+again@example.com")
+                .await,
+            RateLimitDecision::Limited {
+                retry_after_seconds: 3600
+            }
+        );
+        assert_eq!(
+            service
+                .check_submission(Some("2.2.2.2"), "other@example.com")
+                .await,
+            RateLimitDecision::Allowed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_check_submission_limits_by_ip() {
+        let service =
+            RateLimitService::create(Arc::new(InMemoryRateLimiter::default()), Some(LIMIT), None);
+
+        for i in 0..3 {
+            assert_eq!(
+                service
+                    .check_submission(Some("1.1.1.1"), &format!("user{i}@example.com"))
+                    .await,
+                RateLimitDecision::Allowed
+            );
+        }
+        assert!(matches!(
+            service
+                .check_submission(Some("1.1.1.1"), "user4@example.com")
+                .await,
+            RateLimitDecision::Limited { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_check_submission_without_ip_skips_ip_limit() {
+        let service =
+            RateLimitService::create(Arc::new(InMemoryRateLimiter::default()), Some(LIMIT), None);
+
+        for i in 0..5 {
+            assert_eq!(
+                service
+                    .check_submission(None, &format!("user{i}@example.com"))
+                    .await,
+                RateLimitDecision::Allowed
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_submission_fails_open() {
+        let service = RateLimitService::create(
+            Arc::new(InMemoryRateLimiter {
+                should_fail: true,
+                ..Default::default()
+            }),
+            Some(LIMIT),
+            Some(LIMIT),
+        );
+
+        assert_eq!(
+            service
+                .check_submission(Some("1.1.1.1"), "user@example.com")
+                .await,
+            RateLimitDecision::Allowed
+        );
+    }
+}
@@ -1,7 +1,206 @@
-use crate::application::exceptions::AppError;
+use std::{str::FromStr, sync::Arc};
+
+use crate::log_error;
 use async_trait::async_trait;
 
+/// The anti-bot evidence sent with a submission. Each validation service
+/// checks the fields it understands and ignores the rest.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VerificationRequest {
+    /// Response token from a challenge widget such as Turnstile.
+    pub token: Option<String>,
+    /// Value of the hidden honeypot field, which people leave empty.
+    pub honeypot: Option<String>,
+    /// Signed render timestamp from `GET /api/v1/contact-us/form-token/`.
+    pub form_token: Option<String>,
+    pub remote_ip: String,
+    /// The submitting site's own Turnstile secret, used instead of the
+    /// configured one when set.
+    pub site_secret_key: Option<String>,
+}
+
+/// Why a submission's anti-bot evidence was not accepted.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VerificationError {
+    /// The token is missing, forged or doesn't match this site.
+    TokenInvalid(String),
+    /// The token was valid once but is too old or has already been redeemed.
+    TokenExpired(String),
+    /// The provider rejected our own credentials or settings.
+    ProviderMisconfigured(String),
+    /// The provider could not be reached or failed to answer.
+    ProviderUnreachable(String),
+    /// Our own checks could not be completed, e.g. the form token nonce store
+    /// failed. Unlike `ProviderUnreachable`, never waived by `FailurePolicy::Open`.
+    Internal(String),
+}
+
+impl VerificationError {
+    /// Stable identifier for API clients.
+    pub fn code(&self) -> &'static str {
+        match self {
+            VerificationError::TokenInvalid(_) => "token_invalid",
+            VerificationError::TokenExpired(_) => "token_expired",
+            VerificationError::ProviderMisconfigured(_) => "provider_misconfigured",
+            VerificationError::ProviderUnreachable(_) => "provider_unreachable",
+            VerificationError::Internal(_) => "internal_error",
+        }
+    }
+}
+
+impl std::fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VerificationError::TokenInvalid(msg) => write!(f, "Token invalid: {msg}"),
+            VerificationError::TokenExpired(msg) => write!(f, "Token expired: {msg}"),
+            VerificationError::ProviderMisconfigured(msg) => {
+                write!(f, "Provider misconfigured: {msg}")
+            }
+            VerificationError::ProviderUnreachable(msg) => {
+                write!(f, "Provider unreachable: {msg}")
+            }
+            VerificationError::Internal(msg) => write!(f, "Internal error: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for VerificationError {}
+
 #[async_trait(?Send)]
 pub trait RequestValidationServiceTrait: Send + Sync {
-    async fn verify(&self, token: String, ip: String) -> Result<(), AppError>;
+    async fn verify(&self, request: &VerificationRequest) -> Result<(), VerificationError>;
+}
+
+/// What to do with submissions while the captcha provider is unreachable.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FailurePolicy {
+    /// Accept them, relying on rate limiting and spam scoring.
+    Open,
+    /// Reject them with `VerificationError::ProviderUnreachable`.
+    Closed,
+}
+
+impl FromStr for FailurePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "open" => Ok(FailurePolicy::Open),
+            "closed" => Ok(FailurePolicy::Closed),
+            _ => Err(format!(
+                "Unknown failure policy '{s}', expected 'open' or 'closed'"
+            )),
+        }
+    }
+}
+
+/// Applies a `FailurePolicy` to another validation service.
+pub struct FailurePolicyRequestValidationService {
+    inner: Arc<dyn RequestValidationServiceTrait>,
+    policy: FailurePolicy,
+}
+
+impl FailurePolicyRequestValidationService {
+    pub fn create(
+        inner: Arc<dyn RequestValidationServiceTrait>,
+        policy: FailurePolicy,
+    ) -> Arc<dyn RequestValidationServiceTrait> {
+        Arc::new(Self { inner, policy })
+    }
+}
+
+#[async_trait(?Send)]
+impl RequestValidationServiceTrait for FailurePolicyRequestValidationService {
+    async fn verify(&self, request: &VerificationRequest) -> Result<(), VerificationError> {
+        match self.inner.verify(request).await {
+            Err(VerificationError::ProviderUnreachable(msg))
+                if self.policy == FailurePolicy::Open =>
+            {
+                log_error!(
+                    "Captcha provider unavailable, accepting submission from {}: {}",
+                    request.remote_ip,
+                    msg
+                );
+                Ok(())
+            }
+            result => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FailingService(fn() -> VerificationError);
+
+    #[async_trait(?Send)]
+    impl RequestValidationServiceTrait for FailingService {
+        async fn verify(&self, _request: &VerificationRequest) -> Result<(), VerificationError> {
+            Err((self.0)())
+        }
+    }
+
+    fn unavailable() -> VerificationError {
+        VerificationError::ProviderUnreachable("timed out".to_string())
+    }
+
+    fn rejected() -> VerificationError {
+        VerificationError::TokenInvalid("invalid token".to_string())
+    }
+
+    #[test]
+    fn test_parse_failure_policy() {
+        assert_eq!(
+            FailurePolicy::from_str("Open").unwrap(),
+            FailurePolicy::Open
+        );
+        assert_eq!(
+            FailurePolicy::from_str(" closed ").unwrap(),
+            FailurePolicy::Closed
+        );
+        assert!(FailurePolicy::from_str("ajar").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fail_open_accepts_when_provider_is_unavailable() {
+        let service = FailurePolicyRequestValidationService::create(
+            Arc::new(FailingService(unavailable)),
+            FailurePolicy::Open,
+        );
+
+        assert!(
+            service
+                .verify(&VerificationRequest::default())
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fail_open_still_rejects_invalid_tokens() {
+        let service = FailurePolicyRequestValidationService::create(
+            Arc::new(FailingService(rejected)),
+            FailurePolicy::Open,
+        );
+
+        let result = service.verify(&VerificationRequest::default()).await;
+
+        assert!(matches!(result, Err(VerificationError::TokenInvalid(_))));
+    }
+
+    #[tokio::test]
+    async fn test_fail_closed_rejects_when_provider_is_unavailable() {
+        let service = FailurePolicyRequestValidationService::create(
+            Arc::new(FailingService(unavailable)),
+            FailurePolicy::Closed,
+        );
+
+        let result = service.verify(&VerificationRequest::default()).await;
+
+        assert!(matches!(
+            result,
+            Err(VerificationError::ProviderUnreachable(_))
+        ));
+    }
 }
@@ -0,0 +1,97 @@
+use std::sync::Arc;
+
+use crate::{
+    application::exceptions::AppError,
+    domain::{entity::Site, repository::SiteRepository as SiteRepositoryInterface},
+};
+
+/// Works out which site a submission belongs to.
+pub struct SiteService {
+    repo: Arc<dyn SiteRepositoryInterface>,
+}
+
+impl SiteService {
+    pub fn create(repo: Arc<dyn SiteRepositoryInterface>) -> Self {
+        Self { repo }
+    }
+
+    /// Looks the site up by `site_id` when the request names one, otherwise
+    /// by `origin`. Returns `None` when no site claims the origin, so
+    /// single-site deployments keep working without a `sites` row.
+    pub async fn resolve(
+        &self,
+        site_id: Option<&str>,
+        origin: Option<&str>,
+    ) -> Result<Option<Site>, AppError> {
+        if let Some(site_id) = site_id {
+            return match self.repo.find_by_id(site_id).await {
+                Ok(Some(site)) => Ok(Some(site)),
+                Ok(None) => Err(AppError::NotFound(format!("Site {site_id} not found"))),
+                Err(e) => Err(AppError::DatabaseError(e.to_string())),
+            };
+        }
+
+        match origin {
+            Some(origin) => self
+                .repo
+                .find_by_origin(origin)
+                .await
+                .map_err(|e| AppError::DatabaseError(e.to_string())),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::test_support::{MockSiteRepository, test_site};
+
+    fn service() -> SiteService {
+        SiteService::create(Arc::new(MockSiteRepository::with_sites(vec![
+            test_site("shop", vec![]),
+            test_site("blog", vec![]),
+        ])))
+    }
+
+    #[tokio::test]
+    async fn test_resolves_site_from_id_before_origin() {
+        let site = service()
+            .resolve(Some("blog"), Some("https://shop.example.com"))
+            .await
+            .unwrap();
+
+        assert_eq!(site.unwrap().id, "blog");
+    }
+
+    #[tokio::test]
+    async fn test_unknown_site_id_is_not_found() {
+        let result = service().resolve(Some("missing"), None).await;
+
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_resolves_site_from_origin() {
+        let site = service()
+            .resolve(None, Some("https://shop.example.com"))
+            .await
+            .unwrap();
+
+        assert_eq!(site.unwrap().id, "shop");
+    }
+
+    #[tokio::test]
+    async fn test_unclaimed_origin_resolves_to_no_site() {
+        let service = service();
+
+        assert_eq!(
+            service
+                .resolve(None, Some("https://other.example.com"))
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(service.resolve(None, None).await.unwrap(), None);
+    }
+}
@@ -0,0 +1,593 @@
+use std::{collections::HashSet, sync::Arc};
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use crate::{
+    application::{event_consumer::PostProcessingStepTrait, exceptions::AppError},
+    domain::{
+        entity::ContactMessage,
+        repository::ContactMessageRepository as ContactMessageRepositoryInterface,
+    },
+    log_info,
+};
+
+/// A single heuristic contributing points to a message's spam score.
+///
+/// Implement this to add a rule and pass it to `SpamScorer::create`.
+pub trait SpamRuleTrait: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Returns the points this rule adds for `message`; 0 if it doesn't apply.
+    fn score(&self, message: &ContactMessage) -> u32;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpamAssessment {
+    pub score: u32,
+    pub is_spam: bool,
+    /// Names of the rules that contributed, with their points.
+    pub matched_rules: Vec<(&'static str, u32)>,
+}
+
+pub struct SpamScorer {
+    rules: Vec<Box<dyn SpamRuleTrait>>,
+    threshold: u32,
+}
+
+impl SpamScorer {
+    pub const DEFAULT_THRESHOLD: u32 = 50;
+
+    pub fn create(rules: Vec<Box<dyn SpamRuleTrait>>, threshold: u32) -> Self {
+        Self { rules, threshold }
+    }
+
+    /// The built-in rules, with `extra_keywords` added to the keyword list.
+    pub fn default_rules(
+        expected_locale: &str,
+        extra_keywords: &[String],
+    ) -> Vec<Box<dyn SpamRuleTrait>> {
+        vec![
+            Box::new(LinkCountRule::default()),
+            Box::new(KeywordRule::with_defaults(extra_keywords)),
+            Box::new(ScriptMismatchRule::for_locale(expected_locale)),
+            Box::new(RepeatedCharactersRule::default()),
+            Box::new(DisposableEmailRule::with_defaults()),
+        ]
+    }
+
+    pub fn assess(&self, message: &ContactMessage) -> SpamAssessment {
+        let matched_rules: Vec<(&'static str, u32)> = self
+            .rules
+            .iter()
+            .map(|rule| (rule.name(), rule.score(message)))
+            .filter(|(_, points)| *points > 0)
+            .collect();
+        let score = matched_rules.iter().map(|(_, points)| points).sum();
+
+        SpamAssessment {
+            score,
+            is_spam: score >= self.threshold,
+            matched_rules,
+        }
+    }
+}
+
+/// Penalises messages with more links than a genuine enquiry usually needs.
+pub struct LinkCountRule {
+    pub allowed_links: usize,
+    pub points_per_link: u32,
+    pub max_points: u32,
+}
+
+impl Default for LinkCountRule {
+    fn default() -> Self {
+        Self {
+            allowed_links: 1,
+            points_per_link: 20,
+            max_points: 60,
+        }
+    }
+}
+
+impl SpamRuleTrait for LinkCountRule {
+    fn name(&self) -> &'static str {
+        "link_count"
+    }
+
+    fn score(&self, message: &ContactMessage) -> u32 {
+        let text = message.message.to_lowercase();
+        let links = ["http://", "https://", "www."]
+            .iter()
+            .map(|marker| text.matches(marker).count())
+            .sum::<usize>()
+            // "https://www." counts once
+            - text.matches("://www.").count();
+
+        let extra = links.saturating_sub(self.allowed_links) as u32;
+        (extra * self.points_per_link).min(self.max_points)
+    }
+}
+
+/// Matches phrases that almost only appear in unsolicited marketing.
+pub struct KeywordRule {
+    keywords: Vec<String>,
+    pub points_per_keyword: u32,
+    pub max_points: u32,
+}
+
+impl KeywordRule {
+    const DEFAULT_KEYWORDS: [&'static str; 16] = [
+        "viagra",
+        "cialis",
+        "casino",
+        "crypto investment",
+        "bitcoin",
+        "forex",
+        "payday loan",
+        "seo services",
+        "backlinks",
+        "rank your website",
+        "first page of google",
+        "guest post",
+        "escort",
+        "porn",
+        "work from home",
+        "100% free",
+    ];
+
+    pub fn new(keywords: Vec<String>) -> Self {
+        Self {
+            keywords: keywords.into_iter().map(|k| k.to_lowercase()).collect(),
+            points_per_keyword: 25,
+            max_points: 75,
+        }
+    }
+
+    pub fn with_defaults(extra_keywords: &[String]) -> Self {
+        let keywords = Self::DEFAULT_KEYWORDS
+            .iter()
+            .map(|k| k.to_string())
+            .chain(extra_keywords.iter().cloned())
+            .collect();
+        Self::new(keywords)
+    }
+}
+
+impl SpamRuleTrait for KeywordRule {
+    fn name(&self) -> &'static str {
+        "keywords"
+    }
+
+    fn score(&self, message: &ContactMessage) -> u32 {
+        let text = format!("{} {}", message.name, message.message).to_lowercase();
+        let matches = self
+            .keywords
+            .iter()
+            .filter(|keyword| text.contains(keyword.as_str()))
+            .count() as u32;
+
+        (matches * self.points_per_keyword).min(self.max_points)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Script {
+    Latin,
+    Cyrillic,
+    Cjk,
+}
+
+impl Script {
+    fn of(c: char) -> Option<Self> {
+        match c as u32 {
+            0x0400..=0x052F => Some(Script::Cyrillic),
+            0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF => {
+                Some(Script::Cjk)
+            }
+            _ if c.is_alphabetic() => Some(Script::Latin),
+            _ => None,
+        }
+    }
+}
+
+/// Flags Cyrillic or CJK text sent to a form whose audience writes in another
+/// script, e.g. Russian-language spam sent to an English-language site.
+pub struct ScriptMismatchRule {
+    expected: Script,
+    pub min_ratio: f64,
+    pub points: u32,
+}
+
+impl ScriptMismatchRule {
+    pub fn for_locale(locale: &str) -> Self {
+        let language = locale
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        let expected = match language.as_str() {
+            "ru" | "uk" | "be" | "bg" | "sr" | "mk" | "kk" => Script::Cyrillic,
+            "zh" | "ja" | "ko" => Script::Cjk,
+            _ => Script::Latin,
+        };
+
+        Self {
+            expected,
+            min_ratio: 0.3,
+            points: 40,
+        }
+    }
+}
+
+impl SpamRuleTrait for ScriptMismatchRule {
+    fn name(&self) -> &'static str {
+        "script_mismatch"
+    }
+
+    fn score(&self, message: &ContactMessage) -> u32 {
+        let (mut letters, mut foreign) = (0usize, 0usize);
+        for script in message.message.chars().filter_map(Script::of) {
+            letters += 1;
+            if script != self.expected && script != Script::Latin {
+                foreign += 1;
+            }
+        }
+
+        if letters > 0 && foreign as f64 / letters as f64 >= self.min_ratio {
+            self.points
+        } else {
+            0
+        }
+    }
+}
+
+/// Catches keyboard mashing and shouting such as "!!!!!!!!" or "aaaaaaaa".
+pub struct RepeatedCharactersRule {
+    pub min_run: usize,
+    pub points_per_run: u32,
+    pub max_points: u32,
+}
+
+impl Default for RepeatedCharactersRule {
+    fn default() -> Self {
+        Self {
+            min_run: 6,
+            points_per_run: 10,
+            max_points: 30,
+        }
+    }
+}
+
+impl SpamRuleTrait for RepeatedCharactersRule {
+    fn name(&self) -> &'static str {
+        "repeated_characters"
+    }
+
+    fn score(&self, message: &ContactMessage) -> u32 {
+        let mut runs = 0u32;
+        let mut previous = None;
+        let mut length = 0usize;
+
+        for c in message.message.chars() {
+            if Some(c) == previous {
+                length += 1;
+            } else {
+                previous = Some(c);
+                length = 1;
+            }
+            if length == self.min_run && !c.is_whitespace() {
+                runs += 1;
+            }
+        }
+
+        (runs * self.points_per_run).min(self.max_points)
+    }
+}
+
+/// Flags addresses from throwaway mailbox providers.
+pub struct DisposableEmailRule {
+    domains: HashSet<String>,
+    pub points: u32,
+}
+
+impl DisposableEmailRule {
+    const DEFAULT_DOMAINS: [&'static str; 14] = [
+        "10minutemail.com",
+        "dispostable.com",
+        "fakeinbox.com",
+        "getnada.com",
+        "guerrillamail.com",
+        "maildrop.cc",
+        "mailinator.com",
+        "mintemail.com",
+        "sharklasers.com",
+        "temp-mail.org",
+        "tempmail.com",
+        "throwawaymail.com",
+        "trashmail.com",
+        "yopmail.com",
+    ];
+
+    pub fn new(domains: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            domains: domains.into_iter().map(|d| d.to_lowercase()).collect(),
+            points: 40,
+        }
+    }
+
+    pub fn with_defaults() -> Self {
+        Self::new(Self::DEFAULT_DOMAINS.iter().map(|d| d.to_string()))
+    }
+}
+
+impl SpamRuleTrait for DisposableEmailRule {
+    fn name(&self) -> &'static str {
+        "disposable_email"
+    }
+
+    fn score(&self, message: &ContactMessage) -> u32 {
+        let domain = message
+            .email
+            .rsplit_once('@')
+            .map(|(_, domain)| domain.trim().to_lowercase())
+            .unwrap_or_default();
+
+        if self.domains.contains(&domain) {
+            self.points
+        } else {
+            0
+        }
+    }
+}
+
+/// Scores each new message before anyone is notified about it.
+pub struct SpamScoringStep {
+    repo: Arc<dyn ContactMessageRepositoryInterface>,
+    scorer: SpamScorer,
+}
+
+impl SpamScoringStep {
+    pub fn create(
+        repo: Arc<dyn ContactMessageRepositoryInterface>,
+        scorer: SpamScorer,
+    ) -> Arc<dyn PostProcessingStepTrait> {
+        Arc::new(Self { repo, scorer })
+    }
+}
+
+#[async_trait(?Send)]
+impl PostProcessingStepTrait for SpamScoringStep {
+    fn name(&self) -> &'static str {
+        "spam_scoring"
+    }
+
+    async fn run(&self, message: &mut ContactMessage) -> Result<(), AppError> {
+        // Redelivered events must not re-flag a message an admin has since restored.
+        if message.spam_score.is_some() {
+            return Ok(());
+        }
+
+        let assessment = self.scorer.assess(message);
+        let previous_status = message.status;
+        message.record_spam_score(assessment.score, assessment.is_spam, Utc::now().timestamp());
+
+        let updated = self
+            .repo
+            .update_spam_score(message, previous_status)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        if !updated {
+            return Err(AppError::Conflict(
+                "Message status was changed while scoring".to_string(),
+            ));
+        }
+
+        if assessment.is_spam {
+            log_info!(
+                "Marked message {} as spam (score {}, rules {:?})",
+                message.id,
+                assessment.score,
+                assessment.matched_rules
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::test_support::MockContactMessageRepository;
+    use crate::domain::form_schema::FormSchema;
+    use crate::domain::{
+        entity::OutboxEvent,
+        enums::{ContactMessageCategory, ContactMessageStatus},
+        repository::ContactMessageRepository,
+    };
+
+    fn message(email: &str, text: &str) -> ContactMessage {
+        ContactMessage::create(
+            ContactMessageCategory::OTHER,
+            email.to_string(),
+            "Jane Smith".to_string(),
+            text.to_string(),
+            None,
+            &FormSchema::unstructured(),
+        )
+        .unwrap()
+    }
+
+    fn scorer() -> SpamScorer {
+        SpamScorer::create(
+            SpamScorer::default_rules("en-GB", &[]),
+            SpamScorer::DEFAULT_THRESHOLD,
+        )
+    }
+
+    #[test]
+    fn test_genuine_message_scores_zero() {
+        let assessment = scorer().assess(&message(
+            "jane@example.com",
+            "The export button on https://example.com/settings doesn't work for me.",
+        ));
+
+        assert_eq!(assessment.score, 0);
+        assert!(!assessment.is_spam);
+    }
+
+    #[test]
+    fn test_link_count_rule() {
+        let rule = LinkCountRule::default();
+
+        assert_eq!(
+            rule.score(&message("a@example.com", "See https://www.a.com")),
+            0
+        );
+        assert_eq!(
+            rule.score(&message(
+                "a@example.com",
+                "http://a.com https://b.com www.c.com"
+            )),
+            40
+        );
+        assert_eq!(
+            rule.score(&message("a@example.com", &"https://spam.com ".repeat(10))),
+            60
+        );
+    }
+
+    #[test]
+    fn test_keyword_rule_with_extra_keywords() {
+        let rule = KeywordRule::with_defaults(&["Miracle Cure".to_string()]);
+
+        assert_eq!(
+            rule.score(&message("a@example.com", "Cheap VIAGRA and a miracle cure")),
+            50
+        );
+        assert_eq!(rule.score(&message("a@example.com", "Great app!")), 0);
+    }
+
+    #[test]
+    fn test_script_mismatch_rule() {
+        let english = ScriptMismatchRule::for_locale("en");
+        let russian = ScriptMismatchRule::for_locale("ru-RU");
+        let cyrillic = message("a@example.com", "Купить дешёвые часы прямо сейчас");
+        let cjk = message("a@example.com", "这是一个测试消息");
+
+        assert_eq!(english.score(&cyrillic), 40);
+        assert_eq!(english.score(&cjk), 40);
+        assert_eq!(russian.score(&cyrillic), 0);
+        assert_eq!(
+            english.score(&message(
+                "a@example.com",
+                "Thanks for the app, it works great. Спасибо"
+            )),
+            0
+        );
+    }
+
+    #[test]
+    fn test_repeated_characters_rule() {
+        let rule = RepeatedCharactersRule::default();
+
+        assert_eq!(rule.score(&message("a@example.com", "Hello!!")), 0);
+        assert_eq!(rule.score(&message("a@example.com", "BUY NOW!!!!!!!!")), 10);
+        assert_eq!(
+            rule.score(&message("a@example.com", "aaaaaaa bbbbbbb ccccccc ddddddd")),
+            30
+        );
+        assert_eq!(rule.score(&message("a@example.com", "a          b")), 0);
+    }
+
+    #[test]
+    fn test_disposable_email_rule() {
+        let rule = DisposableEmailRule::with_defaults();
+
+        assert_eq!(rule.score(&message("bot@Mailinator.com", "Hello")), 40);
+        assert_eq!(rule.score(&message("jane@example.com", "Hello")), 0);
+    }
+
+    #[test]
+    fn test_scores_add_up_to_spam() {
+        let assessment = scorer().assess(&message(
+            "bot@yopmail.com",
+            "Get on the first page of google with our SEO services https://a.com https://b.com",
+        ));
+
+        assert!(assessment.is_spam);
+        assert_eq!(assessment.score, 110);
+        assert_eq!(
+            assessment.matched_rules,
+            vec![
+                ("link_count", 20),
+                ("keywords", 50),
+                ("disposable_email", 40)
+            ]
+        );
+    }
+
+    struct AlwaysRule(u32);
+
+    impl SpamRuleTrait for AlwaysRule {
+        fn name(&self) -> &'static str {
+            "always"
+        }
+
+        fn score(&self, _message: &ContactMessage) -> u32 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_custom_rules_can_be_plugged_in() {
+        let scorer =
+            SpamScorer::create(vec![Box::new(AlwaysRule(30)), Box::new(AlwaysRule(30))], 60);
+
+        let assessment = scorer.assess(&message("a@example.com", "Hello"));
+
+        assert_eq!(assessment.score, 60);
+        assert!(assessment.is_spam);
+    }
+
+    async fn stored(repo: &MockContactMessageRepository, contact: &ContactMessage) {
+        repo.save(contact, &OutboxEvent::message_created(contact), None)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_step_stores_score_and_marks_spam() {
+        let repo = Arc::new(MockContactMessageRepository::new());
+        let mut contact = message("bot@yopmail.com", "Best casino bonus, 100% free!!!!!!!!");
+        stored(&repo, &contact).await;
+
+        SpamScoringStep::create(repo.clone(), scorer())
+            .run(&mut contact)
+            .await
+            .unwrap();
+
+        let saved = &repo.get_all_contact_messages()[0];
+        assert_eq!(saved.status, ContactMessageStatus::SPAM);
+        assert_eq!(saved.spam_score, Some(100));
+        assert_eq!(contact.status, ContactMessageStatus::SPAM);
+    }
+
+    #[tokio::test]
+    async fn test_step_skips_already_scored_messages() {
+        let repo = Arc::new(MockContactMessageRepository::new());
+        let mut contact = message("bot@yopmail.com", "Best casino bonus, 100% free!!!!!!!!");
+        contact.spam_score = Some(0);
+        stored(&repo, &contact).await;
+
+        SpamScoringStep::create(repo.clone(), scorer())
+            .run(&mut contact)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            repo.get_all_contact_messages()[0].status,
+            ContactMessageStatus::NEW
+        );
+    }
+}
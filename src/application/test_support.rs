@@ -0,0 +1,544 @@
+//! In-memory doubles shared by the application service tests.
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+
+use crate::{
+    application::{
+        email_provider::{EmailProviderTrait, OutgoingEmail},
+        event_publisher::EventPublisherTrait,
+        exceptions::AppError,
+        notification_service::NotificationServiceTrait,
+    },
+    domain::{
+        entity::{Category, ContactMessage, Form, IdempotencyRecord, OutboxEvent, Site},
+        enums::{
+            ContactMessageCategory, ContactMessagePriority, ContactMessageStatus, OutboxStatus,
+        },
+        events::DomainEvent,
+        exceptions::RepositoryError,
+        repository::{
+            CategoryRepository, ContactMessageRepository, FormRepository, FormTokenRepository,
+            IdempotencyRepository, OutboxRepository, SiteRepository,
+        },
+    },
+};
+
+/// Stores messages, outbox events and idempotency records side by side, like
+/// the D1 database does.
+#[derive(Default)]
+pub struct MockContactMessageRepository {
+    contact_messages: Arc<Mutex<Vec<ContactMessage>>>,
+    outbox_events: Arc<Mutex<Vec<OutboxEvent>>>,
+    idempotency_records: Arc<Mutex<Vec<IdempotencyRecord>>>,
+    should_save_fail: Arc<Mutex<bool>>,
+}
+
+impl MockContactMessageRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_save_should_fail(&self, should_fail: bool) {
+        *self.should_save_fail.lock().unwrap() = should_fail;
+    }
+
+    pub fn get_all_contact_messages(&self) -> Vec<ContactMessage> {
+        self.contact_messages.lock().unwrap().clone()
+    }
+
+    pub fn get_all_outbox_events(&self) -> Vec<OutboxEvent> {
+        self.outbox_events.lock().unwrap().clone()
+    }
+
+    pub fn get_all_idempotency_records(&self) -> Vec<IdempotencyRecord> {
+        self.idempotency_records.lock().unwrap().clone()
+    }
+
+    pub fn insert_idempotency_record(&self, record: IdempotencyRecord) {
+        self.idempotency_records.lock().unwrap().push(record);
+    }
+}
+
+#[async_trait(?Send)]
+impl ContactMessageRepository for MockContactMessageRepository {
+    async fn save(
+        &self,
+        contact: &ContactMessage,
+        event: &OutboxEvent,
+        idempotency: Option<&IdempotencyRecord>,
+    ) -> Result<bool, RepositoryError> {
+        if *self.should_save_fail.lock().unwrap() {
+            return Err(RepositoryError::DatabaseError(
+                "Mock database error on save".into(),
+            ));
+        }
+
+        if let Some(record) = idempotency {
+            IdempotencyRepository::insert(self, record).await?;
+        }
+
+        let mut contact_messages = self.contact_messages.lock().unwrap();
+
+        if let Some(existing_index) = contact_messages.iter().position(|c| c.id == contact.id) {
+            contact_messages[existing_index] = contact.clone();
+        } else {
+            contact_messages.push(contact.clone());
+        }
+        self.outbox_events.lock().unwrap().push(event.clone());
+
+        Ok(true)
+    }
+
+    async fn find_by_id(&self, id: &str) -> Result<ContactMessage, RepositoryError> {
+        self.contact_messages
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|c| c.id == id)
+            .cloned()
+            .ok_or_else(|| RepositoryError::NotFound(format!("Contact message {id} not found")))
+    }
+
+    async fn find_recent_by_fingerprint(
+        &self,
+        fingerprint: &str,
+        site_id: Option<&str>,
+        since: i64,
+    ) -> Result<Option<ContactMessage>, RepositoryError> {
+        Ok(self
+            .contact_messages
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|c| {
+                c.fingerprint == fingerprint
+                    && c.site_id.as_deref() == site_id
+                    && c.created_at >= since
+            })
+            .max_by_key(|c| c.created_at)
+            .cloned())
+    }
+
+    async fn list(&self, limit: u32, offset: u32) -> Result<Vec<ContactMessage>, RepositoryError> {
+        Ok(self
+            .contact_messages
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn update_status(
+        &self,
+        contact: &ContactMessage,
+        previous_status: ContactMessageStatus,
+    ) -> Result<bool, RepositoryError> {
+        let mut contact_messages = self.contact_messages.lock().unwrap();
+
+        match contact_messages
+            .iter_mut()
+            .find(|c| c.id == contact.id && c.status == previous_status)
+        {
+            Some(existing) => {
+                *existing = contact.clone();
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn update_spam_score(
+        &self,
+        contact: &ContactMessage,
+        previous_status: ContactMessageStatus,
+    ) -> Result<bool, RepositoryError> {
+        self.update_status(contact, previous_status).await
+    }
+
+    async fn count_previous(&self, contact: &ContactMessage) -> Result<u32, RepositoryError> {
+        Ok(self
+            .contact_messages
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|c| {
+                c.email.eq_ignore_ascii_case(&contact.email)
+                    && c.created_at <= contact.created_at
+                    && c.id < contact.id
+            })
+            .count() as u32)
+    }
+
+    async fn update_enrichment(&self, contact: &ContactMessage) -> Result<bool, RepositoryError> {
+        let mut contact_messages = self.contact_messages.lock().unwrap();
+
+        match contact_messages.iter_mut().find(|c| c.id == contact.id) {
+            Some(existing) => {
+                existing.previous_messages = contact.previous_messages;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn mark_auto_replied(&self, id: &str, now: i64) -> Result<bool, RepositoryError> {
+        let mut contact_messages = self.contact_messages.lock().unwrap();
+
+        match contact_messages
+            .iter_mut()
+            .find(|c| c.id == id && c.auto_replied_at.is_none())
+        {
+            Some(existing) => {
+                existing.auto_replied_at = Some(now);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl OutboxRepository for MockContactMessageRepository {
+    async fn find_due(&self, now: i64, limit: u32) -> Result<Vec<OutboxEvent>, RepositoryError> {
+        let mut due: Vec<OutboxEvent> = self
+            .outbox_events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| e.status == OutboxStatus::PENDING && e.next_attempt_at <= now)
+            .cloned()
+            .collect();
+        due.sort_by_key(|e| e.next_attempt_at);
+        due.truncate(limit as usize);
+        Ok(due)
+    }
+
+    async fn claim(&self, id: &str, now: i64, lease_until: i64) -> Result<bool, RepositoryError> {
+        let mut outbox_events = self.outbox_events.lock().unwrap();
+
+        match outbox_events
+            .iter_mut()
+            .find(|e| e.id == id && e.status == OutboxStatus::PENDING && e.next_attempt_at <= now)
+        {
+            Some(event) => {
+                event.next_attempt_at = lease_until;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn update(&self, event: &OutboxEvent) -> Result<bool, RepositoryError> {
+        let mut outbox_events = self.outbox_events.lock().unwrap();
+
+        match outbox_events.iter_mut().find(|e| e.id == event.id) {
+            Some(existing) => {
+                *existing = event.clone();
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl IdempotencyRepository for MockContactMessageRepository {
+    async fn find(
+        &self,
+        key: &str,
+        now: i64,
+    ) -> Result<Option<IdempotencyRecord>, RepositoryError> {
+        Ok(self
+            .idempotency_records
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|r| r.key == key && r.expires_at > now)
+            .cloned())
+    }
+
+    async fn insert(&self, record: &IdempotencyRecord) -> Result<bool, RepositoryError> {
+        let mut records = self.idempotency_records.lock().unwrap();
+        records.retain(|r| r.key != record.key || r.expires_at > record.created_at);
+        if records.iter().any(|r| r.key == record.key) {
+            return Err(RepositoryError::DatabaseError(
+                "UNIQUE constraint failed: idempotency_keys.key".into(),
+            ));
+        }
+        records.push(record.clone());
+        Ok(true)
+    }
+
+    async fn delete_expired(&self, now: i64) -> Result<u64, RepositoryError> {
+        let mut records = self.idempotency_records.lock().unwrap();
+        let before = records.len();
+        records.retain(|r| r.expires_at > now);
+        Ok((before - records.len()) as u64)
+    }
+}
+
+#[derive(Default)]
+pub struct MockNotificationService {
+    pub notified: Mutex<Vec<String>>,
+    pub should_fail: bool,
+}
+
+#[async_trait(?Send)]
+impl NotificationServiceTrait for MockNotificationService {
+    async fn notify(&self, message: &ContactMessage) -> Result<(), AppError> {
+        self.notified.lock().unwrap().push(message.id.clone());
+        if self.should_fail {
+            return Err(AppError::InternalError);
+        }
+        Ok(())
+    }
+}
+
+/// Records every email it is asked to send, including ones it fails.
+#[derive(Default)]
+pub struct MockEmailProvider {
+    pub sent: Mutex<Vec<OutgoingEmail>>,
+    pub should_fail: bool,
+}
+
+#[async_trait(?Send)]
+impl EmailProviderTrait for MockEmailProvider {
+    async fn send(&self, email: &OutgoingEmail) -> Result<(), AppError> {
+        self.sent.lock().unwrap().push(email.clone());
+        if self.should_fail {
+            return Err(AppError::InternalError);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct MockEventPublisher {
+    pub published: Mutex<Vec<DomainEvent>>,
+    pub should_fail: bool,
+}
+
+#[async_trait(?Send)]
+impl EventPublisherTrait for MockEventPublisher {
+    async fn publish(&self, event: &DomainEvent) -> Result<(), AppError> {
+        if self.should_fail {
+            return Err(AppError::InternalError);
+        }
+        self.published.lock().unwrap().push(event.clone());
+        Ok(())
+    }
+}
+
+/// A site accepting `categories` from `https://{id}.example.com`.
+pub fn test_site(id: &str, categories: Vec<ContactMessageCategory>) -> Site {
+    Site {
+        id: id.to_string(),
+        name: format!("Site {id}"),
+        allowed_origins: vec![format!("https://{id}.example.com")],
+        turnstile_secret_key: None,
+        turnstile_site_key: None,
+        categories,
+        notification_recipients: String::new(),
+        created_at: 0,
+        updated_at: 0,
+    }
+}
+
+/// Remembers redeemed nonces with their expiry, like the D1 table does.
+#[derive(Default)]
+pub struct MockFormTokenRepository {
+    pub redeemed: Mutex<Vec<(String, i64)>>,
+    pub should_fail: bool,
+}
+
+#[async_trait(?Send)]
+impl FormTokenRepository for MockFormTokenRepository {
+    async fn redeem(
+        &self,
+        nonce: &str,
+        expires_at: i64,
+        now: i64,
+    ) -> Result<bool, RepositoryError> {
+        if self.should_fail {
+            return Err(RepositoryError::DatabaseError(
+                "Mock database error on redeem".into(),
+            ));
+        }
+        let mut redeemed = self.redeemed.lock().unwrap();
+        redeemed.retain(|(_, expiry)| *expiry > now);
+        if redeemed.iter().any(|(used, _)| used == nonce) {
+            return Ok(false);
+        }
+        redeemed.push((nonce.to_string(), expires_at));
+        Ok(true)
+    }
+}
+
+#[derive(Default)]
+pub struct MockSiteRepository {
+    pub sites: Mutex<Vec<Site>>,
+}
+
+impl MockSiteRepository {
+    pub fn with_sites(sites: Vec<Site>) -> Self {
+        Self {
+            sites: Mutex::new(sites),
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl SiteRepository for MockSiteRepository {
+    async fn find_by_id(&self, id: &str) -> Result<Option<Site>, RepositoryError> {
+        Ok(self
+            .sites
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|s| s.id == id)
+            .cloned())
+    }
+
+    async fn find_by_origin(&self, origin: &str) -> Result<Option<Site>, RepositoryError> {
+        Ok(self
+            .sites
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|s| s.allowed_origins.iter().any(|o| o == origin))
+            .cloned())
+    }
+}
+
+#[derive(Default)]
+pub struct MockFormRepository {
+    pub forms: Mutex<Vec<Form>>,
+}
+
+impl MockFormRepository {
+    pub fn with_forms(forms: Vec<Form>) -> Self {
+        Self {
+            forms: Mutex::new(forms),
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl FormRepository for MockFormRepository {
+    async fn find_by_id(&self, id: &str) -> Result<Option<Form>, RepositoryError> {
+        Ok(self
+            .forms
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|f| f.id == id)
+            .cloned())
+    }
+}
+
+/// The categories seeded by the `0009_categories` migration.
+pub fn test_categories() -> Vec<Category> {
+    let category = |slug: ContactMessageCategory,
+                    display_name: &str,
+                    sort_order: i64,
+                    default_priority: ContactMessagePriority| Category {
+        slug,
+        display_name: display_name.to_string(),
+        description: String::new(),
+        active: true,
+        sort_order,
+        default_priority,
+        created_at: 0,
+        updated_at: 0,
+    };
+    vec![
+        category(
+            ContactMessageCategory::ERROR,
+            "Error report",
+            10,
+            ContactMessagePriority::HIGH,
+        ),
+        category(
+            ContactMessageCategory::IDEA,
+            "Idea",
+            20,
+            ContactMessagePriority::NORMAL,
+        ),
+        category(
+            ContactMessageCategory::TESTIMONIAL,
+            "Testimonial",
+            30,
+            ContactMessagePriority::LOW,
+        ),
+        category(
+            ContactMessageCategory::OTHER,
+            "Other",
+            40,
+            ContactMessagePriority::NORMAL,
+        ),
+    ]
+}
+
+#[derive(Default)]
+pub struct MockCategoryRepository {
+    pub categories: Mutex<Vec<Category>>,
+    /// Categories with messages filed under them, which cannot be deleted.
+    pub in_use: Mutex<Vec<ContactMessageCategory>>,
+    pub list_calls: Mutex<u32>,
+}
+
+impl MockCategoryRepository {
+    pub fn with_categories(categories: Vec<Category>) -> Self {
+        Self {
+            categories: Mutex::new(categories),
+            ..Self::default()
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl CategoryRepository for MockCategoryRepository {
+    async fn list(&self) -> Result<Vec<Category>, RepositoryError> {
+        *self.list_calls.lock().unwrap() += 1;
+        let mut categories = self.categories.lock().unwrap().clone();
+        categories
+            .sort_by(|a, b| (a.sort_order, a.slug.as_str()).cmp(&(b.sort_order, b.slug.as_str())));
+        Ok(categories)
+    }
+
+    async fn insert(&self, category: &Category) -> Result<bool, RepositoryError> {
+        let mut categories = self.categories.lock().unwrap();
+        if categories.iter().any(|c| c.slug == category.slug) {
+            return Ok(false);
+        }
+        categories.push(category.clone());
+        Ok(true)
+    }
+
+    async fn update(&self, category: &Category) -> Result<bool, RepositoryError> {
+        let mut categories = self.categories.lock().unwrap();
+        match categories.iter_mut().find(|c| c.slug == category.slug) {
+            Some(existing) => {
+                *existing = category.clone();
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn delete(&self, slug: &ContactMessageCategory) -> Result<bool, RepositoryError> {
+        if self.in_use.lock().unwrap().contains(slug) {
+            return Ok(false);
+        }
+        let mut categories = self.categories.lock().unwrap();
+        let before = categories.len();
+        categories.retain(|c| &c.slug != slug);
+        Ok(categories.len() < before)
+    }
+}
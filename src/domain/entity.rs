@@ -1,12 +1,16 @@
-use std::collections::HashMap;
-
+use chrono::Utc;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
-use crate::domain::exceptions::ValidationError;
+use crate::domain::exceptions::{InvalidStatusTransition, ValidationError, ValidationErrors};
 
-use super::enums::ContactMessageCategory;
+use super::enums::{
+    ContactMessageCategory, ContactMessagePriority, ContactMessageStatus, OutboxStatus,
+};
+use super::events::{DomainEvent, MessageCreated};
+use super::form_schema::{FormData, FormSchema};
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ContactMessage {
@@ -15,21 +19,44 @@ pub struct ContactMessage {
     pub email: String,
     pub name: String,
     pub message: String,
-    pub data: Option<HashMap<String, String>>,
+    pub data: Option<FormData>,
+    pub status: ContactMessageStatus,
+    /// The default priority of the category at the time of submission.
+    pub priority: ContactMessagePriority,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub resolved_at: Option<i64>,
+    pub spam_score: Option<u32>,
+    /// How many earlier messages the same email address has sent, once the
+    /// queue consumer has looked it up.
+    pub previous_messages: Option<u32>,
+    /// When the submitter was sent an acknowledgement, if they were.
+    pub auto_replied_at: Option<i64>,
+    pub fingerprint: String,
+    /// The site the message was submitted through, if any.
+    pub site_id: Option<String>,
+    /// The form whose schema `data` was validated against, if any.
+    pub form_id: Option<String>,
 }
 
 impl ContactMessage {
+    pub const MAX_EMAIL_LENGTH: usize = 254;
+    pub const MAX_NAME_LENGTH: usize = 100;
+    pub const MAX_MESSAGE_LENGTH: usize = 5000;
+
+    /// Validates the submission, storing `data` typed by `schema`.
     pub fn create(
         category: ContactMessageCategory,
         email: String,
         name: String,
         message: String,
-        data: Option<HashMap<String, String>>,
-    ) -> Result<Self, ValidationError> {
-        Self::validate_email(&email)?;
-        Self::validate_name(&name)?;
-        Self::validate_message(&message)?;
-        Self::validate_data(&data)?;
+        data: Option<FormData>,
+        schema: &FormSchema,
+    ) -> Result<Self, ValidationErrors> {
+        let data = Self::validate(&email, &name, &message, data.as_ref(), schema)?;
+
+        let now = Utc::now().timestamp();
+        let fingerprint = Self::content_fingerprint(&email, &category, &message);
 
         Ok(Self {
             id: Uuid::now_v7().into(),
@@ -38,14 +65,90 @@ impl ContactMessage {
             name,
             message,
             data,
+            status: ContactMessageStatus::NEW,
+            priority: ContactMessagePriority::default(),
+            created_at: now,
+            updated_at: now,
+            resolved_at: None,
+            spam_score: None,
+            previous_messages: None,
+            auto_replied_at: None,
+            fingerprint,
+            site_id: None,
+            form_id: None,
         })
     }
 
+    /// SHA-256 of the lowercased email, the category and the message with runs
+    /// of whitespace collapsed, so resubmissions that only differ in spacing or
+    /// email case share a fingerprint.
+    pub fn content_fingerprint(
+        email: &str,
+        category: &ContactMessageCategory,
+        message: &str,
+    ) -> String {
+        let message = message.split_whitespace().collect::<Vec<_>>().join(" ");
+        let content = format!("{}\n{}\n{}", email.trim().to_lowercase(), category, message);
+        hex::encode(Sha256::digest(content.as_bytes()))
+    }
+
+    pub fn update_status(
+        &mut self,
+        status: ContactMessageStatus,
+        now: i64,
+    ) -> Result<(), InvalidStatusTransition> {
+        if !self.status.can_transition_to(status) {
+            return Err(InvalidStatusTransition {
+                from: self.status,
+                to: status,
+            });
+        }
+
+        self.status = status;
+        self.updated_at = now;
+        self.resolved_at = match status {
+            ContactMessageStatus::RESOLVED => Some(now),
+            _ => None,
+        };
+
+        Ok(())
+    }
+
+    /// Stores the spam score and, if it crossed the threshold, moves a message
+    /// nobody has looked at yet to `SPAM`. Triaged messages keep their status.
+    pub fn record_spam_score(&mut self, score: u32, is_spam: bool, now: i64) {
+        self.spam_score = Some(score);
+        self.updated_at = now;
+
+        if is_spam && self.status == ContactMessageStatus::NEW {
+            self.status = ContactMessageStatus::SPAM;
+        }
+    }
+
+    /// Checks every field and reports all that are invalid. Returns `data`
+    /// typed by `schema`, or `None` when there is none.
+    pub fn validate(
+        email: &str,
+        name: &str,
+        message: &str,
+        data: Option<&FormData>,
+        schema: &FormSchema,
+    ) -> Result<Option<FormData>, ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        errors.check(Self::validate_email(email));
+        errors.check(Self::validate_name(name));
+        errors.check(Self::validate_message(message));
+        let data = schema.validate(data, &mut errors);
+        errors.into_result()?;
+        Ok(Some(data).filter(|data| !data.is_empty()))
+    }
+
     fn validate_email(email: &str) -> Result<(), ValidationError> {
-        if email.is_empty() || email.len() > 254 {
-            return Err(ValidationError::InvalidEmail(
-                "Email must be between 1 and 254 characters".into(),
-            ));
+        if email.is_empty() || email.len() > Self::MAX_EMAIL_LENGTH {
+            return Err(ValidationError::InvalidEmail(format!(
+                "Email must be between 1 and {} characters",
+                Self::MAX_EMAIL_LENGTH
+            )));
         }
 
         let email_regex = Regex::new(r"^[^\s@]+@[^\s@]+\.[^\s@]+$").unwrap();
@@ -64,10 +167,11 @@ impl ContactMessage {
             return Err(ValidationError::InvalidName("Name cannot be empty".into()));
         }
 
-        if trimmed.len() > 100 {
-            return Err(ValidationError::InvalidName(
-                "Name must be 100 characters or less".into(),
-            ));
+        if trimmed.len() > Self::MAX_NAME_LENGTH {
+            return Err(ValidationError::InvalidName(format!(
+                "Name must be {} characters or less",
+                Self::MAX_NAME_LENGTH
+            )));
         }
 
         Ok(())
@@ -82,45 +186,254 @@ impl ContactMessage {
             ));
         }
 
-        if trimmed.len() > 5000 {
-            return Err(ValidationError::InvalidMessage(
-                "Message must be 5000 characters or less".into(),
-            ));
+        if trimmed.len() > Self::MAX_MESSAGE_LENGTH {
+            return Err(ValidationError::InvalidMessage(format!(
+                "Message must be {} characters or less",
+                Self::MAX_MESSAGE_LENGTH
+            )));
         }
 
         Ok(())
     }
+}
 
-    fn validate_data(data: &Option<HashMap<String, String>>) -> Result<(), ValidationError> {
-        if let Some(map) = data {
-            if map.len() > 20 {
-                return Err(ValidationError::InvalidData(
-                    "Data cannot contain more than 20 key-value pairs".into(),
-                ));
-            }
+/// One of several websites sharing this worker, each with its own origins,
+/// Turnstile secret, categories and notification recipients.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Site {
+    pub id: String,
+    pub name: String,
+    /// Empty allows any origin.
+    pub allowed_origins: Vec<String>,
+    /// Used instead of the configured Turnstile secret when set.
+    pub turnstile_secret_key: Option<String>,
+    /// The public key of the site's own Turnstile widget, handed to clients
+    /// instead of the configured one when set.
+    pub turnstile_site_key: Option<String>,
+    /// Empty accepts every category.
+    pub categories: Vec<ContactMessageCategory>,
+    /// A `RecipientRouting` specification; empty uses the global routing.
+    pub notification_recipients: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
 
-            for (key, value) in map.iter() {
-                if key.len() > 200 {
-                    return Err(ValidationError::InvalidData(
-                        "Data keys must be 200 characters or less".into(),
-                    ));
-                }
-
-                if value.len() > 1000 {
-                    return Err(ValidationError::InvalidData(
-                        "Data values must be 1000 characters or less".into(),
-                    ));
-                }
-            }
+impl Site {
+    pub fn accepts_category(&self, category: &ContactMessageCategory) -> bool {
+        self.categories.is_empty() || self.categories.contains(category)
+    }
+}
+
+/// A form whose submissions carry the fields declared by its schema.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Form {
+    pub id: String,
+    /// The site the form belongs to; `None` lets any site use it.
+    pub site_id: Option<String>,
+    pub name: String,
+    pub schema: FormSchema,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl Form {
+    pub fn belongs_to(&self, site: Option<&Site>) -> bool {
+        match &self.site_id {
+            Some(site_id) => site.is_some_and(|site| &site.id == site_id),
+            None => true,
         }
+    }
+}
 
-        Ok(())
+/// A category messages are filed under, managed through the admin API.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Category {
+    pub slug: ContactMessageCategory,
+    pub display_name: String,
+    pub description: String,
+    /// Inactive categories keep their messages but accept no new ones.
+    pub active: bool,
+    /// Position in listings, lowest first.
+    pub sort_order: i64,
+    /// The priority given to messages filed under the category.
+    pub default_priority: ContactMessagePriority,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl Category {
+    pub const MAX_DISPLAY_NAME_LENGTH: usize = 100;
+    pub const MAX_DESCRIPTION_LENGTH: usize = 500;
+
+    pub fn validate(display_name: &str, description: &str) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        let display_name = display_name.trim();
+        if display_name.is_empty() || display_name.len() > Self::MAX_DISPLAY_NAME_LENGTH {
+            errors.push(
+                "display_name",
+                ValidationError::InvalidCategory(format!(
+                    "Display name must be between 1 and {} characters",
+                    Self::MAX_DISPLAY_NAME_LENGTH
+                )),
+            );
+        }
+        if description.len() > Self::MAX_DESCRIPTION_LENGTH {
+            errors.push(
+                "description",
+                ValidationError::InvalidCategory(format!(
+                    "Description must be {} characters or less",
+                    Self::MAX_DESCRIPTION_LENGTH
+                )),
+            );
+        }
+
+        errors.into_result()
+    }
+}
+
+/// A side effect recorded in the same write as the change that caused it,
+/// so it can be retried until delivered even if the worker dies mid-request.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct OutboxEvent {
+    pub id: String,
+    pub event_type: String,
+    pub aggregate_id: String,
+    pub payload: String,
+    pub status: OutboxStatus,
+    pub attempts: u32,
+    pub next_attempt_at: i64,
+    pub last_error: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl OutboxEvent {
+    pub const MAX_ATTEMPTS: u32 = 8;
+    const BASE_BACKOFF_SECONDS: i64 = 30;
+    const MAX_BACKOFF_SECONDS: i64 = 60 * 60;
+
+    pub fn message_created(contact: &ContactMessage) -> Self {
+        let id: String = Uuid::now_v7().into();
+        let event = DomainEvent::MessageCreated(MessageCreated::new(id.clone(), contact));
+
+        Self {
+            id,
+            event_type: event.event_type().to_string(),
+            aggregate_id: contact.id.clone(),
+            payload: serde_json::to_string(&event).expect("domain events serialize to JSON"),
+            status: OutboxStatus::PENDING,
+            attempts: 0,
+            next_attempt_at: contact.created_at,
+            last_error: None,
+            created_at: contact.created_at,
+            updated_at: contact.created_at,
+        }
+    }
+
+    /// The event to publish, as stored in the payload.
+    pub fn domain_event(&self) -> Result<DomainEvent, String> {
+        serde_json::from_str(&self.payload)
+            .map_err(|e| format!("Invalid {} payload: {e}", self.event_type))
+    }
+
+    /// Seconds to wait after the given number of failed attempts:
+    /// 30s, 60s, 120s, ... capped at one hour.
+    pub fn backoff_seconds(attempts: u32) -> i64 {
+        let exponent = attempts.saturating_sub(1).min(16);
+        (Self::BASE_BACKOFF_SECONDS << exponent).min(Self::MAX_BACKOFF_SECONDS)
+    }
+
+    pub fn mark_delivered(&mut self, now: i64) {
+        self.status = OutboxStatus::DELIVERED;
+        self.last_error = None;
+        self.updated_at = now;
+    }
+
+    /// Schedules a retry with exponential backoff, or dead-letters the event
+    /// once `MAX_ATTEMPTS` have failed.
+    pub fn record_failure(&mut self, error: String, now: i64) {
+        self.attempts += 1;
+        self.last_error = Some(error);
+        self.updated_at = now;
+
+        if self.attempts >= Self::MAX_ATTEMPTS {
+            self.status = OutboxStatus::DEAD;
+        } else {
+            self.next_attempt_at = now + Self::backoff_seconds(self.attempts);
+        }
+    }
+}
+
+/// A client-supplied `Idempotency-Key` together with a fingerprint of the
+/// request it was first used with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdempotencyKey {
+    pub key: String,
+    pub request_hash: String,
+}
+
+impl IdempotencyKey {
+    const MAX_LENGTH: usize = 255;
+
+    /// The hash is taken over the parsed JSON body, whose object keys
+    /// serialize in sorted order, so formatting differences don't count as a
+    /// different request.
+    pub fn new(key: String, request: &serde_json::Value) -> Result<Self, ValidationError> {
+        if key.is_empty() || key.len() > Self::MAX_LENGTH {
+            return Err(ValidationError::InvalidIdempotencyKey(format!(
+                "Key must be between 1 and {} characters",
+                Self::MAX_LENGTH
+            )));
+        }
+
+        if !key.chars().all(|c| c.is_ascii_graphic()) {
+            return Err(ValidationError::InvalidIdempotencyKey(
+                "Key must only contain printable ASCII characters".into(),
+            ));
+        }
+
+        Ok(Self {
+            key,
+            request_hash: hex::encode(Sha256::digest(request.to_string().as_bytes())),
+        })
+    }
+}
+
+/// Records which message a key created, so a repeated request can be
+/// answered without creating another one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdempotencyRecord {
+    pub key: String,
+    pub request_hash: String,
+    pub message_id: String,
+    pub created_at: i64,
+    pub expires_at: i64,
+}
+
+impl IdempotencyRecord {
+    pub const TTL_SECONDS: i64 = 24 * 60 * 60;
+
+    pub fn new(key: &IdempotencyKey, contact: &ContactMessage) -> Self {
+        Self {
+            key: key.key.clone(),
+            request_hash: key.request_hash.clone(),
+            message_id: contact.id.clone(),
+            created_at: contact.created_at,
+            expires_at: contact.created_at + Self::TTL_SECONDS,
+        }
+    }
+
+    pub fn matches(&self, key: &IdempotencyKey) -> bool {
+        self.request_hash == key.request_hash
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::events::MESSAGE_CREATED_EVENT;
+    use crate::domain::form_schema::{FormData, FormSchema};
 
     #[test]
     fn test_create_valid_contact_message() {
@@ -130,6 +443,7 @@ mod tests {
             "John Doe".to_string(),
             "This is a valid message".to_string(),
             None,
+            &FormSchema::unstructured(),
         );
 
         assert!(result.is_ok());
@@ -138,6 +452,47 @@ mod tests {
         assert_eq!(contact.name, "John Doe");
         assert_eq!(contact.message, "This is a valid message");
         assert_eq!(contact.category, ContactMessageCategory::IDEA);
+        assert_eq!(contact.status, ContactMessageStatus::NEW);
+        assert_eq!(contact.updated_at, contact.created_at);
+        assert_eq!(contact.resolved_at, None);
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_whitespace_and_email_case() {
+        let first = valid_contact_message();
+        let second = ContactMessage::create(
+            first.category.clone(),
+            "TEST@example.com".to_string(),
+            "Someone Else".to_string(),
+            format!("  {}\n\n", first.message.replace(' ', "\t ")),
+            None,
+            &FormSchema::unstructured(),
+        )
+        .unwrap();
+
+        assert_eq!(first.fingerprint, second.fingerprint);
+        assert_eq!(first.fingerprint.len(), 64);
+    }
+
+    #[test]
+    fn test_fingerprint_differs_by_category_and_content() {
+        let message = valid_contact_message();
+        let fingerprint = |category, text: &str| {
+            ContactMessage::content_fingerprint(&message.email, category, text)
+        };
+
+        assert_eq!(
+            fingerprint(&message.category, &message.message),
+            message.fingerprint
+        );
+        assert_ne!(
+            fingerprint(&ContactMessageCategory::OTHER, &message.message),
+            message.fingerprint
+        );
+        assert_ne!(
+            fingerprint(&message.category, &message.message.to_uppercase()),
+            message.fingerprint
+        );
     }
 
     #[test]
@@ -148,11 +503,12 @@ mod tests {
             "John Doe".to_string(),
             "Valid message here".to_string(),
             None,
+            &FormSchema::unstructured(),
         );
 
         assert!(result.is_err());
         assert!(matches!(
-            result.unwrap_err(),
+            result.unwrap_err().violations()[0].error,
             ValidationError::InvalidEmail(_)
         ));
     }
@@ -166,11 +522,12 @@ mod tests {
             "John Doe".to_string(),
             "Valid message here".to_string(),
             None,
+            &FormSchema::unstructured(),
         );
 
         assert!(result.is_err());
         assert!(matches!(
-            result.unwrap_err(),
+            result.unwrap_err().violations()[0].error,
             ValidationError::InvalidEmail(_)
         ));
     }
@@ -192,11 +549,12 @@ mod tests {
                 "John Doe".to_string(),
                 "Valid message here".to_string(),
                 None,
+                &FormSchema::unstructured(),
             );
 
             assert!(result.is_err(), "Email '{}' should be invalid", email);
             assert!(matches!(
-                result.unwrap_err(),
+                result.unwrap_err().violations()[0].error,
                 ValidationError::InvalidEmail(_)
             ));
         }
@@ -218,6 +576,7 @@ mod tests {
                 "John Doe".to_string(),
                 "Valid message here".to_string(),
                 None,
+                &FormSchema::unstructured(),
             );
 
             assert!(result.is_ok(), "Email '{}' should be valid", email);
@@ -232,11 +591,12 @@ mod tests {
             "".to_string(),
             "Valid message here".to_string(),
             None,
+            &FormSchema::unstructured(),
         );
 
         assert!(result.is_err());
         assert!(matches!(
-            result.unwrap_err(),
+            result.unwrap_err().violations()[0].error,
             ValidationError::InvalidName(_)
         ));
     }
@@ -249,11 +609,12 @@ mod tests {
             "   ".to_string(),
             "Valid message here".to_string(),
             None,
+            &FormSchema::unstructured(),
         );
 
         assert!(result.is_err());
         assert!(matches!(
-            result.unwrap_err(),
+            result.unwrap_err().violations()[0].error,
             ValidationError::InvalidName(_)
         ));
     }
@@ -267,11 +628,12 @@ mod tests {
             long_name,
             "Valid message here".to_string(),
             None,
+            &FormSchema::unstructured(),
         );
 
         assert!(result.is_err());
         assert!(matches!(
-            result.unwrap_err(),
+            result.unwrap_err().violations()[0].error,
             ValidationError::InvalidName(_)
         ));
     }
@@ -284,11 +646,12 @@ mod tests {
             "John Doe".to_string(),
             "".to_string(),
             None,
+            &FormSchema::unstructured(),
         );
 
         assert!(result.is_err());
         assert!(matches!(
-            result.unwrap_err(),
+            result.unwrap_err().violations()[0].error,
             ValidationError::InvalidMessage(_)
         ));
     }
@@ -301,11 +664,12 @@ mod tests {
             "John Doe".to_string(),
             "     ".to_string(),
             None,
+            &FormSchema::unstructured(),
         );
 
         assert!(result.is_err());
         assert!(matches!(
-            result.unwrap_err(),
+            result.unwrap_err().violations()[0].error,
             ValidationError::InvalidMessage(_)
         ));
     }
@@ -319,20 +683,21 @@ mod tests {
             "John Doe".to_string(),
             long_message,
             None,
+            &FormSchema::unstructured(),
         );
 
         assert!(result.is_err());
         assert!(matches!(
-            result.unwrap_err(),
+            result.unwrap_err().violations()[0].error,
             ValidationError::InvalidMessage(_)
         ));
     }
 
     #[test]
     fn test_validate_data_too_many_pairs() {
-        let mut data = HashMap::new();
+        let mut data = FormData::new();
         for i in 0..21 {
-            data.insert(format!("key{}", i), format!("value{}", i));
+            data.insert(format!("key{}", i), format!("value{}", i).into());
         }
 
         let result = ContactMessage::create(
@@ -341,19 +706,20 @@ mod tests {
             "John Doe".to_string(),
             "Valid message here".to_string(),
             Some(data),
+            &FormSchema::unstructured(),
         );
 
         assert!(result.is_err());
         assert!(matches!(
-            result.unwrap_err(),
+            result.unwrap_err().violations()[0].error,
             ValidationError::InvalidData(_)
         ));
     }
 
     #[test]
     fn test_validate_data_key_too_long() {
-        let mut data = HashMap::new();
-        data.insert("a".repeat(201), "value".to_string());
+        let mut data = FormData::new();
+        data.insert("a".repeat(201), "value".to_string().into());
 
         let result = ContactMessage::create(
             ContactMessageCategory::IDEA,
@@ -361,19 +727,20 @@ mod tests {
             "John Doe".to_string(),
             "Valid message here".to_string(),
             Some(data),
+            &FormSchema::unstructured(),
         );
 
         assert!(result.is_err());
         assert!(matches!(
-            result.unwrap_err(),
+            result.unwrap_err().violations()[0].error,
             ValidationError::InvalidData(_)
         ));
     }
 
     #[test]
     fn test_validate_data_value_too_long() {
-        let mut data = HashMap::new();
-        data.insert("key".to_string(), "a".repeat(1001));
+        let mut data = FormData::new();
+        data.insert("key".to_string(), "a".repeat(1001).into());
 
         let result = ContactMessage::create(
             ContactMessageCategory::IDEA,
@@ -381,20 +748,21 @@ mod tests {
             "John Doe".to_string(),
             "Valid message here".to_string(),
             Some(data),
+            &FormSchema::unstructured(),
         );
 
         assert!(result.is_err());
         assert!(matches!(
-            result.unwrap_err(),
+            result.unwrap_err().violations()[0].error,
             ValidationError::InvalidData(_)
         ));
     }
 
     #[test]
     fn test_validate_data_valid() {
-        let mut data = HashMap::new();
-        data.insert("rating".to_string(), "5".to_string());
-        data.insert("source".to_string(), "website".to_string());
+        let mut data = FormData::new();
+        data.insert("rating".to_string(), "5".to_string().into());
+        data.insert("source".to_string(), "website".to_string().into());
 
         let result = ContactMessage::create(
             ContactMessageCategory::IDEA,
@@ -402,10 +770,254 @@ mod tests {
             "John Doe".to_string(),
             "Valid message here".to_string(),
             Some(data.clone()),
+            &FormSchema::unstructured(),
         );
 
         assert!(result.is_ok());
         let contact = result.unwrap();
         assert_eq!(contact.data, Some(data));
     }
+
+    #[test]
+    fn test_validate_reports_every_invalid_field() {
+        let mut data = FormData::new();
+        data.insert("order_id".to_string(), "1".repeat(1001).into());
+        data.insert("notes".to_string(), "2".repeat(1001).into());
+
+        let errors = ContactMessage::create(
+            ContactMessageCategory::IDEA,
+            "not-an-email".to_string(),
+            "John Doe".to_string(),
+            "   ".to_string(),
+            Some(data),
+            &FormSchema::unstructured(),
+        )
+        .unwrap_err();
+
+        let paths: Vec<&str> = errors
+            .violations()
+            .iter()
+            .map(|violation| violation.path.as_str())
+            .collect();
+        assert_eq!(
+            paths,
+            vec!["email", "message", "data.notes", "data.order_id"]
+        );
+        assert!(matches!(
+            errors.violations()[1].error,
+            ValidationError::InvalidMessage(_)
+        ));
+    }
+
+    fn valid_contact_message() -> ContactMessage {
+        ContactMessage::create(
+            ContactMessageCategory::ERROR,
+            "test@example.com".to_string(),
+            "John Doe".to_string(),
+            "Valid message here".to_string(),
+            None,
+            &FormSchema::unstructured(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_update_status_resolved_sets_resolved_at() {
+        let mut contact = valid_contact_message();
+        let now = contact.created_at + 60;
+
+        contact
+            .update_status(ContactMessageStatus::RESOLVED, now)
+            .unwrap();
+
+        assert_eq!(contact.status, ContactMessageStatus::RESOLVED);
+        assert_eq!(contact.updated_at, now);
+        assert_eq!(contact.resolved_at, Some(now));
+    }
+
+    #[test]
+    fn test_update_status_reopen_clears_resolved_at() {
+        let mut contact = valid_contact_message();
+        let now = contact.created_at;
+
+        contact
+            .update_status(ContactMessageStatus::RESOLVED, now + 10)
+            .unwrap();
+        contact
+            .update_status(ContactMessageStatus::IN_PROGRESS, now + 20)
+            .unwrap();
+
+        assert_eq!(contact.status, ContactMessageStatus::IN_PROGRESS);
+        assert_eq!(contact.updated_at, now + 20);
+        assert_eq!(contact.resolved_at, None);
+    }
+
+    #[test]
+    fn test_update_status_illegal_transition() {
+        let mut contact = valid_contact_message();
+        let created_at = contact.created_at;
+
+        let result = contact.update_status(ContactMessageStatus::NEW, created_at + 10);
+
+        assert_eq!(
+            result.unwrap_err(),
+            InvalidStatusTransition {
+                from: ContactMessageStatus::NEW,
+                to: ContactMessageStatus::NEW,
+            }
+        );
+        assert_eq!(contact.updated_at, created_at);
+    }
+
+    #[test]
+    fn test_outbox_event_message_created() {
+        let contact = valid_contact_message();
+
+        let event = OutboxEvent::message_created(&contact);
+
+        assert_eq!(event.event_type, MESSAGE_CREATED_EVENT);
+        assert_eq!(event.aggregate_id, contact.id);
+        assert_eq!(
+            event.domain_event().unwrap(),
+            DomainEvent::MessageCreated(MessageCreated::new(event.id.clone(), &contact))
+        );
+        assert_eq!(event.status, OutboxStatus::PENDING);
+        assert_eq!(event.attempts, 0);
+        assert_eq!(event.next_attempt_at, contact.created_at);
+    }
+
+    #[test]
+    fn test_outbox_backoff_is_exponential_and_capped() {
+        assert_eq!(OutboxEvent::backoff_seconds(1), 30);
+        assert_eq!(OutboxEvent::backoff_seconds(2), 60);
+        assert_eq!(OutboxEvent::backoff_seconds(3), 120);
+        assert_eq!(OutboxEvent::backoff_seconds(7), 1920);
+        assert_eq!(OutboxEvent::backoff_seconds(8), 3600);
+        assert_eq!(OutboxEvent::backoff_seconds(100), 3600);
+    }
+
+    #[test]
+    fn test_outbox_record_failure_schedules_retry() {
+        let mut event = OutboxEvent::message_created(&valid_contact_message());
+
+        event.record_failure("timeout".to_string(), 1000);
+        assert_eq!(event.status, OutboxStatus::PENDING);
+        assert_eq!(event.attempts, 1);
+        assert_eq!(event.next_attempt_at, 1030);
+        assert_eq!(event.last_error.as_deref(), Some("timeout"));
+
+        event.record_failure("timeout".to_string(), 2000);
+        assert_eq!(event.attempts, 2);
+        assert_eq!(event.next_attempt_at, 2060);
+
+        event.mark_delivered(3000);
+        assert_eq!(event.status, OutboxStatus::DELIVERED);
+        assert_eq!(event.last_error, None);
+    }
+
+    #[test]
+    fn test_outbox_record_failure_dead_letters() {
+        let mut event = OutboxEvent::message_created(&valid_contact_message());
+
+        for i in 0..OutboxEvent::MAX_ATTEMPTS {
+            assert_eq!(event.status, OutboxStatus::PENDING);
+            event.record_failure("provider down".to_string(), i as i64);
+        }
+
+        assert_eq!(event.status, OutboxStatus::DEAD);
+        assert_eq!(event.attempts, OutboxEvent::MAX_ATTEMPTS);
+    }
+
+    #[test]
+    fn test_idempotency_key_hash_ignores_formatting_and_key_order() {
+        let first: serde_json::Value =
+            serde_json::from_str(r#"{"email": "a@example.com", "name": "A"}"#).unwrap();
+        let second: serde_json::Value =
+            serde_json::from_str(r#"{"name":"A","email":"a@example.com"}"#).unwrap();
+        let different: serde_json::Value =
+            serde_json::from_str(r#"{"name":"B","email":"a@example.com"}"#).unwrap();
+
+        let first = IdempotencyKey::new("key-1".to_string(), &first).unwrap();
+        let second = IdempotencyKey::new("key-1".to_string(), &second).unwrap();
+        let different = IdempotencyKey::new("key-1".to_string(), &different).unwrap();
+
+        assert_eq!(first.request_hash, second.request_hash);
+        assert_ne!(first.request_hash, different.request_hash);
+    }
+
+    #[test]
+    fn test_idempotency_key_validation() {
+        let body = serde_json::json!({});
+
+        assert!(IdempotencyKey::new("".to_string(), &body).is_err());
+        assert!(IdempotencyKey::new("a".repeat(256), &body).is_err());
+        assert!(IdempotencyKey::new("has space".to_string(), &body).is_err());
+        assert!(
+            IdempotencyKey::new("0190c0de-7a1b-7c3d-8e4f-1234567890ab".to_string(), &body).is_ok()
+        );
+    }
+
+    #[test]
+    fn test_idempotency_record() {
+        let contact = valid_contact_message();
+        let key = IdempotencyKey::new("key-1".to_string(), &serde_json::json!({"a": 1})).unwrap();
+        let other = IdempotencyKey::new("key-1".to_string(), &serde_json::json!({"a": 2})).unwrap();
+
+        let record = IdempotencyRecord::new(&key, &contact);
+
+        assert_eq!(record.message_id, contact.id);
+        assert_eq!(
+            record.expires_at,
+            contact.created_at + IdempotencyRecord::TTL_SECONDS
+        );
+        assert!(record.matches(&key));
+        assert!(!record.matches(&other));
+    }
+
+    #[test]
+    fn test_record_spam_score_marks_new_message_as_spam() {
+        let mut contact = valid_contact_message();
+
+        contact.record_spam_score(80, true, 100);
+
+        assert_eq!(contact.spam_score, Some(80));
+        assert_eq!(contact.status, ContactMessageStatus::SPAM);
+        assert_eq!(contact.updated_at, 100);
+    }
+
+    #[test]
+    fn test_record_spam_score_below_threshold_keeps_status() {
+        let mut contact = valid_contact_message();
+
+        contact.record_spam_score(10, false, 100);
+
+        assert_eq!(contact.spam_score, Some(10));
+        assert_eq!(contact.status, ContactMessageStatus::NEW);
+    }
+
+    #[test]
+    fn test_record_spam_score_keeps_triaged_status() {
+        let mut contact = valid_contact_message();
+        contact
+            .update_status(ContactMessageStatus::TRIAGED, 50)
+            .unwrap();
+
+        contact.record_spam_score(80, true, 100);
+
+        assert_eq!(contact.status, ContactMessageStatus::TRIAGED);
+    }
+
+    #[test]
+    fn test_category_validation_reports_display_name_and_description() {
+        let errors = Category::validate("  ", &"x".repeat(Category::MAX_DESCRIPTION_LENGTH + 1))
+            .unwrap_err();
+
+        let paths: Vec<&str> = errors
+            .violations()
+            .iter()
+            .map(|violation| violation.path.as_str())
+            .collect();
+        assert_eq!(paths, vec!["display_name", "description"]);
+        assert!(Category::validate("Idea", "").is_ok());
+    }
 }
@@ -1,32 +1,151 @@
+use std::{borrow::Cow, str::FromStr};
+
 use serde::{Deserialize, Serialize};
 use strum_macros::EnumString;
 
-#[derive(Debug, Clone, Deserialize, Serialize, EnumString, PartialEq)]
-pub enum ContactMessageCategory {
-    #[strum(serialize = "ERROR", serialize = "error")]
-    ERROR,
-    #[strum(serialize = "IDEA", serialize = "idea")]
-    IDEA,
-    #[strum(serialize = "TESTIMONIAL", serialize = "testimonial")]
-    TESTIMONIAL,
-    #[strum(serialize = "OTHER", serialize = "other")]
-    OTHER,
+/// The slug of a row in the `categories` table, always uppercase.
+///
+/// Parsing only checks the slug's shape; whether a category exists and is
+/// active is up to the categories stored in D1.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(transparent)]
+pub struct ContactMessageCategory(Cow<'static, str>);
+
+impl ContactMessageCategory {
+    pub const ERROR: Self = Self(Cow::Borrowed("ERROR"));
+    pub const IDEA: Self = Self(Cow::Borrowed("IDEA"));
+    pub const TESTIMONIAL: Self = Self(Cow::Borrowed("TESTIMONIAL"));
+    pub const OTHER: Self = Self(Cow::Borrowed("OTHER"));
+
+    pub const MAX_LENGTH: usize = 50;
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for ContactMessageCategory {
+    type Err = String;
+
+    /// Accepts slugs in any case, made of letters, digits and underscores.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let slug = s.trim().to_uppercase();
+        if slug.is_empty()
+            || slug.len() > Self::MAX_LENGTH
+            || !slug
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+        {
+            return Err(format!(
+                "Category slugs are 1 to {} letters, digits or underscores",
+                Self::MAX_LENGTH
+            ));
+        }
+        Ok(Self(Cow::Owned(slug)))
+    }
 }
 
 impl std::fmt::Display for ContactMessageCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, EnumString, PartialEq)]
+pub enum ContactMessagePriority {
+    #[strum(serialize = "LOW", serialize = "low")]
+    LOW,
+    #[default]
+    #[strum(serialize = "NORMAL", serialize = "normal")]
+    NORMAL,
+    #[strum(serialize = "HIGH", serialize = "high")]
+    HIGH,
+    #[strum(serialize = "URGENT", serialize = "urgent")]
+    URGENT,
+}
+
+impl std::fmt::Display for ContactMessagePriority {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            ContactMessageCategory::ERROR => write!(f, "ERROR"),
-            ContactMessageCategory::IDEA => write!(f, "IDEA"),
-            ContactMessageCategory::TESTIMONIAL => write!(f, "TESTIMONIAL"),
-            ContactMessageCategory::OTHER => write!(f, "OTHER"),
+            ContactMessagePriority::LOW => write!(f, "LOW"),
+            ContactMessagePriority::NORMAL => write!(f, "NORMAL"),
+            ContactMessagePriority::HIGH => write!(f, "HIGH"),
+            ContactMessagePriority::URGENT => write!(f, "URGENT"),
+        }
+    }
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, EnumString, PartialEq)]
+pub enum ContactMessageStatus {
+    #[strum(serialize = "NEW", serialize = "new")]
+    NEW,
+    #[strum(serialize = "TRIAGED", serialize = "triaged")]
+    TRIAGED,
+    #[strum(serialize = "IN_PROGRESS", serialize = "in_progress")]
+    IN_PROGRESS,
+    #[strum(serialize = "RESOLVED", serialize = "resolved")]
+    RESOLVED,
+    #[strum(serialize = "SPAM", serialize = "spam")]
+    SPAM,
+}
+
+impl ContactMessageStatus {
+    /// Returns whether a message in this status may be moved to `next`.
+    ///
+    /// Resolved messages can be reopened and messages marked as spam can be
+    /// returned to `NEW` if they were flagged by mistake.
+    pub fn can_transition_to(&self, next: ContactMessageStatus) -> bool {
+        use ContactMessageStatus::*;
+
+        matches!(
+            (self, next),
+            (NEW, TRIAGED | IN_PROGRESS | RESOLVED | SPAM)
+                | (TRIAGED, IN_PROGRESS | RESOLVED | SPAM)
+                | (IN_PROGRESS, TRIAGED | RESOLVED)
+                | (RESOLVED, IN_PROGRESS)
+                | (SPAM, NEW)
+        )
+    }
+}
+
+impl std::fmt::Display for ContactMessageStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ContactMessageStatus::NEW => write!(f, "NEW"),
+            ContactMessageStatus::TRIAGED => write!(f, "TRIAGED"),
+            ContactMessageStatus::IN_PROGRESS => write!(f, "IN_PROGRESS"),
+            ContactMessageStatus::RESOLVED => write!(f, "RESOLVED"),
+            ContactMessageStatus::SPAM => write!(f, "SPAM"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, EnumString, PartialEq)]
+pub enum OutboxStatus {
+    #[strum(serialize = "PENDING", serialize = "pending")]
+    PENDING,
+    #[strum(serialize = "DELIVERED", serialize = "delivered")]
+    DELIVERED,
+    #[strum(serialize = "DEAD", serialize = "dead")]
+    DEAD,
+}
+
+impl std::fmt::Display for OutboxStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OutboxStatus::PENDING => write!(f, "PENDING"),
+            OutboxStatus::DELIVERED => write!(f, "DELIVERED"),
+            OutboxStatus::DEAD => write!(f, "DEAD"),
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::ContactMessageCategory;
+    use std::str::FromStr;
+
+    use super::{ContactMessageCategory, ContactMessagePriority, ContactMessageStatus};
 
     #[test]
     fn display_formats_correctly() {
@@ -38,4 +157,73 @@ mod tests {
         );
         assert_eq!(ContactMessageCategory::OTHER.to_string(), "OTHER");
     }
+
+    #[test]
+    fn category_parsing_is_case_insensitive() {
+        assert_eq!(
+            ContactMessageCategory::from_str("error").unwrap(),
+            ContactMessageCategory::ERROR
+        );
+        assert_eq!(
+            ContactMessageCategory::from_str(" Billing_2 ")
+                .unwrap()
+                .as_str(),
+            "BILLING_2"
+        );
+        assert!(ContactMessageCategory::from_str("").is_err());
+        assert!(ContactMessageCategory::from_str("not a slug").is_err());
+        assert!(ContactMessageCategory::from_str(&"A".repeat(51)).is_err());
+    }
+
+    #[test]
+    fn priority_parses_in_either_case() {
+        assert_eq!(
+            ContactMessagePriority::from_str("high").unwrap(),
+            ContactMessagePriority::HIGH
+        );
+        assert_eq!(ContactMessagePriority::URGENT.to_string(), "URGENT");
+        assert!(ContactMessagePriority::from_str("CRITICAL").is_err());
+    }
+
+    #[test]
+    fn status_display_round_trips() {
+        let statuses = [
+            ContactMessageStatus::NEW,
+            ContactMessageStatus::TRIAGED,
+            ContactMessageStatus::IN_PROGRESS,
+            ContactMessageStatus::RESOLVED,
+            ContactMessageStatus::SPAM,
+        ];
+
+        for status in statuses {
+            assert_eq!(
+                ContactMessageStatus::from_str(&status.to_string()).unwrap(),
+                status
+            );
+        }
+        assert_eq!(
+            ContactMessageStatus::from_str("in_progress").unwrap(),
+            ContactMessageStatus::IN_PROGRESS
+        );
+        assert!(ContactMessageStatus::from_str("CLOSED").is_err());
+    }
+
+    #[test]
+    fn status_transitions() {
+        use ContactMessageStatus::*;
+
+        assert!(NEW.can_transition_to(TRIAGED));
+        assert!(NEW.can_transition_to(SPAM));
+        assert!(TRIAGED.can_transition_to(IN_PROGRESS));
+        assert!(IN_PROGRESS.can_transition_to(RESOLVED));
+        assert!(RESOLVED.can_transition_to(IN_PROGRESS));
+        assert!(SPAM.can_transition_to(NEW));
+
+        assert!(!NEW.can_transition_to(NEW));
+        assert!(!IN_PROGRESS.can_transition_to(NEW));
+        assert!(!IN_PROGRESS.can_transition_to(SPAM));
+        assert!(!RESOLVED.can_transition_to(SPAM));
+        assert!(!RESOLVED.can_transition_to(NEW));
+        assert!(!SPAM.can_transition_to(RESOLVED));
+    }
 }
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+
+use super::{entity::ContactMessage, enums::ContactMessageCategory};
+
+pub const MESSAGE_CREATED_EVENT: &str = "contact_message.created";
+
+/// Events published for asynchronous processing, serialized with a `type`
+/// tag so consumers can tell them apart on the wire.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type")]
+pub enum DomainEvent {
+    #[serde(rename = "contact_message.created")]
+    MessageCreated(MessageCreated),
+}
+
+impl DomainEvent {
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            DomainEvent::MessageCreated(_) => MESSAGE_CREATED_EVENT,
+        }
+    }
+
+    pub fn event_id(&self) -> &str {
+        match self {
+            DomainEvent::MessageCreated(event) => &event.event_id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct MessageCreated {
+    pub event_id: String,
+    pub message_id: String,
+    pub category: ContactMessageCategory,
+    pub occurred_at: i64,
+}
+
+impl MessageCreated {
+    pub fn new(event_id: String, message: &ContactMessage) -> Self {
+        Self {
+            event_id,
+            message_id: message.id.clone(),
+            category: message.category.clone(),
+            occurred_at: message.created_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::form_schema::FormSchema;
+    use serde_json::json;
+
+    fn message_created() -> DomainEvent {
+        DomainEvent::MessageCreated(MessageCreated {
+            event_id: "event-1".to_string(),
+            message_id: "message-1".to_string(),
+            category: ContactMessageCategory::IDEA,
+            occurred_at: 1_700_000_000,
+        })
+    }
+
+    #[test]
+    fn test_message_created_serializes_with_type_tag() {
+        let value = serde_json::to_value(message_created()).unwrap();
+
+        assert_eq!(
+            value,
+            json!({
+                "type": "contact_message.created",
+                "event_id": "event-1",
+                "message_id": "message-1",
+                "category": "IDEA",
+                "occurred_at": 1_700_000_000,
+            })
+        );
+    }
+
+    #[test]
+    fn test_message_created_round_trip() {
+        let event = message_created();
+
+        let json = serde_json::to_string(&event).unwrap();
+        let parsed: DomainEvent = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed, event);
+        assert_eq!(parsed.event_type(), MESSAGE_CREATED_EVENT);
+        assert_eq!(parsed.event_id(), "event-1");
+    }
+
+    #[test]
+    fn test_message_created_from_message() {
+        let message = ContactMessage::create(
+            ContactMessageCategory::ERROR,
+            "test@example.com".to_string(),
+            "John Doe".to_string(),
+            "Test message".to_string(),
+            None,
+            &FormSchema::unstructured(),
+        )
+        .unwrap();
+
+        let event = MessageCreated::new("event-1".to_string(), &message);
+
+        assert_eq!(event.message_id, message.id);
+        assert_eq!(event.category, ContactMessageCategory::ERROR);
+        assert_eq!(event.occurred_at, message.created_at);
+    }
+
+    #[test]
+    fn test_unknown_event_type_is_rejected() {
+        let result = serde_json::from_value::<DomainEvent>(json!({
+            "type": "contact_message.deleted",
+            "event_id": "event-1",
+        }));
+
+        assert!(result.is_err());
+    }
+}
@@ -1,9 +1,16 @@
+use super::enums::ContactMessageStatus;
+
 #[derive(Debug, Clone)]
 pub enum ValidationError {
     InvalidEmail(String),
     InvalidName(String),
     InvalidMessage(String),
     InvalidData(String),
+    InvalidCategory(String),
+    InvalidStatus(String),
+    InvalidIdempotencyKey(String),
+    InvalidForm(String),
+    InvalidPriority(String),
 }
 
 impl std::fmt::Display for ValidationError {
@@ -13,12 +20,117 @@ impl std::fmt::Display for ValidationError {
             ValidationError::InvalidName(msg) => write!(f, "Invalid name: {msg}"),
             ValidationError::InvalidMessage(msg) => write!(f, "Invalid message: {msg}"),
             ValidationError::InvalidData(msg) => write!(f, "Invalid data: {msg}"),
+            ValidationError::InvalidCategory(msg) => write!(f, "Invalid category: {msg}"),
+            ValidationError::InvalidStatus(msg) => write!(f, "Invalid status: {msg}"),
+            ValidationError::InvalidIdempotencyKey(msg) => {
+                write!(f, "Invalid idempotency key: {msg}")
+            }
+            ValidationError::InvalidForm(msg) => write!(f, "Invalid form: {msg}"),
+            ValidationError::InvalidPriority(msg) => write!(f, "Invalid priority: {msg}"),
         }
     }
 }
 
 impl std::error::Error for ValidationError {}
 
+impl ValidationError {
+    /// The request field this kind of error is reported against by default.
+    pub fn field(&self) -> &'static str {
+        match self {
+            ValidationError::InvalidEmail(_) => "email",
+            ValidationError::InvalidName(_) => "name",
+            ValidationError::InvalidMessage(_) => "message",
+            ValidationError::InvalidData(_) => "data",
+            ValidationError::InvalidCategory(_) => "category",
+            ValidationError::InvalidStatus(_) => "status",
+            ValidationError::InvalidIdempotencyKey(_) => "Idempotency-Key",
+            ValidationError::InvalidForm(_) => "form_id",
+            ValidationError::InvalidPriority(_) => "default_priority",
+        }
+    }
+}
+
+/// A `ValidationError` and the path of the field it applies to, such as
+/// `email` or `data.order_id`.
+#[derive(Debug, Clone)]
+pub struct FieldViolation {
+    pub path: String,
+    pub error: ValidationError,
+}
+
+/// Every field that failed validation, so all of them can be reported at once.
+#[derive(Debug, Clone, Default)]
+pub struct ValidationErrors {
+    violations: Vec<FieldViolation>,
+}
+
+impl ValidationErrors {
+    pub fn push(&mut self, path: impl Into<String>, error: ValidationError) {
+        self.violations.push(FieldViolation {
+            path: path.into(),
+            error,
+        });
+    }
+
+    /// Records the error of `result`, if any, against its default field.
+    pub fn check(&mut self, result: Result<(), ValidationError>) {
+        if let Err(error) = result {
+            self.push(error.field(), error);
+        }
+    }
+
+    pub fn extend(&mut self, other: ValidationErrors) {
+        self.violations.extend(other.violations);
+    }
+
+    pub fn violations(&self) -> &[FieldViolation] {
+        &self.violations
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    pub fn into_result(self) -> Result<(), ValidationErrors> {
+        if self.is_empty() { Ok(()) } else { Err(self) }
+    }
+}
+
+impl From<ValidationError> for ValidationErrors {
+    fn from(error: ValidationError) -> Self {
+        let mut errors = Self::default();
+        errors.push(error.field(), error);
+        errors
+    }
+}
+
+impl std::fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let messages: Vec<String> = self
+            .violations
+            .iter()
+            .map(|violation| violation.error.to_string())
+            .collect();
+        write!(f, "{}", messages.join("; "))
+    }
+}
+
+impl std::error::Error for ValidationErrors {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidStatusTransition {
+    pub from: ContactMessageStatus,
+    pub to: ContactMessageStatus,
+}
+
+impl std::fmt::Display for InvalidStatusTransition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Cannot move message from {} to {}", self.from, self.to)
+    }
+}
+
+impl std::error::Error for InvalidStatusTransition {}
+
 #[derive(Debug, Clone)]
 pub enum RepositoryError {
     SaveFailed(String),
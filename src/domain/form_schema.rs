@@ -0,0 +1,487 @@
+use std::collections::HashSet;
+
+use chrono::NaiveDate;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+
+use super::exceptions::{ValidationError, ValidationErrors};
+
+/// The extra fields of a submission, keyed by field name, with values typed
+/// by the form schema they were validated against.
+pub type FormData = Map<String, Value>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    Text,
+    Email,
+    Url,
+    Number,
+    Select,
+    Checkbox,
+    Date,
+}
+
+/// One declared field of a form and the constraints its value must meet.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct FieldSchema {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub field_type: FieldType,
+    #[serde(default)]
+    pub required: bool,
+    /// Character limits for `text`, `email` and `url` values.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_length: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+    /// Bounds for `number` values.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    /// A regular expression the whole of a `text` value must match.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// The values a `select` field accepts.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<String>,
+}
+
+/// Declares the extra fields a form submits in `data`.
+///
+/// Values are coerced to their field's type, so a form post's `"42"` is
+/// stored as the number `42` and a checkbox's `"on"` as `true`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct FormSchema {
+    pub fields: Vec<FieldSchema>,
+    /// Whether fields that aren't declared are kept, as short text.
+    #[serde(default)]
+    pub allow_additional_fields: bool,
+}
+
+impl FormSchema {
+    pub const MAX_FIELDS: usize = 20;
+    pub const MAX_KEY_LENGTH: usize = 200;
+    /// Applies to text values without a `max_length` of their own.
+    pub const DEFAULT_MAX_LENGTH: usize = 1000;
+
+    /// The schema used when a submission names no form: any fields, as text.
+    pub fn unstructured() -> Self {
+        Self {
+            fields: vec![],
+            allow_additional_fields: true,
+        }
+    }
+
+    /// Checks the schema itself: field names are unique, patterns compile and
+    /// select fields have options.
+    pub fn check(&self) -> Result<(), String> {
+        let mut names = HashSet::new();
+        for field in &self.fields {
+            if field.name.is_empty() || field.name.len() > Self::MAX_KEY_LENGTH {
+                return Err(format!(
+                    "Field names must be between 1 and {} characters",
+                    Self::MAX_KEY_LENGTH
+                ));
+            }
+            if !names.insert(field.name.as_str()) {
+                return Err(format!("Field '{}' is declared twice", field.name));
+            }
+            if let Some(pattern) = &field.pattern {
+                Regex::new(pattern)
+                    .map_err(|e| format!("Field '{}' has an invalid pattern: {e}", field.name))?;
+            }
+            if field.field_type == FieldType::Select && field.options.is_empty() {
+                return Err(format!("Select field '{}' has no options", field.name));
+            }
+        }
+        Ok(())
+    }
+
+    /// Validates `data` against the schema, reporting each problem against
+    /// `data.{field}`, and returns it with every value converted to its type.
+    pub fn validate(&self, data: Option<&FormData>, errors: &mut ValidationErrors) -> FormData {
+        let empty = FormData::new();
+        let data = data.unwrap_or(&empty);
+        let mut typed = FormData::new();
+
+        if data.len() > Self::MAX_FIELDS {
+            errors.push(
+                "data",
+                ValidationError::InvalidData(format!(
+                    "Data cannot contain more than {} fields",
+                    Self::MAX_FIELDS
+                )),
+            );
+        }
+
+        for field in &self.fields {
+            match field.validate(data.get(&field.name)) {
+                Ok(Some(value)) => {
+                    typed.insert(field.name.clone(), value);
+                }
+                Ok(None) => {}
+                Err(msg) => errors.push(
+                    format!("data.{}", field.name),
+                    ValidationError::InvalidData(msg),
+                ),
+            }
+        }
+
+        let mut additional: Vec<_> = data
+            .iter()
+            .filter(|(key, _)| !self.fields.iter().any(|f| &f.name == *key))
+            .collect();
+        additional.sort_by_key(|(key, _)| *key);
+        for (key, value) in additional {
+            if key.len() > Self::MAX_KEY_LENGTH {
+                errors.push(
+                    "data",
+                    ValidationError::InvalidData(format!(
+                        "Data keys must be {} characters or less",
+                        Self::MAX_KEY_LENGTH
+                    )),
+                );
+                continue;
+            }
+            if !self.allow_additional_fields {
+                errors.push(
+                    format!("data.{key}"),
+                    ValidationError::InvalidData("Field is not part of this form".into()),
+                );
+                continue;
+            }
+            match Self::additional_value(value) {
+                Ok(value) => {
+                    typed.insert(key.clone(), value);
+                }
+                Err(msg) => errors.push(format!("data.{key}"), ValidationError::InvalidData(msg)),
+            }
+        }
+
+        typed
+    }
+
+    fn additional_value(value: &Value) -> Result<Value, String> {
+        let text = match value {
+            Value::String(text) => text.clone(),
+            Value::Number(_) | Value::Bool(_) => value.to_string(),
+            _ => return Err("Value must be text".into()),
+        };
+        if text.chars().count() > Self::DEFAULT_MAX_LENGTH {
+            return Err(format!(
+                "Data values must be {} characters or less",
+                Self::DEFAULT_MAX_LENGTH
+            ));
+        }
+        Ok(Value::String(text))
+    }
+}
+
+impl FieldSchema {
+    /// Returns the typed value, or `None` when an optional field was left empty.
+    fn validate(&self, value: Option<&Value>) -> Result<Option<Value>, String> {
+        let value = value.filter(|v| !v.is_null() && v.as_str() != Some(""));
+
+        if self.field_type == FieldType::Checkbox {
+            let checked = match value {
+                None => false,
+                Some(value) => Self::checkbox(value)?,
+            };
+            if self.required && !checked {
+                return Err("Field must be checked".into());
+            }
+            return Ok(Some(Value::Bool(checked)));
+        }
+
+        let Some(value) = value else {
+            return match self.required {
+                true => Err("Field is required".into()),
+                false => Ok(None),
+            };
+        };
+
+        match self.field_type {
+            FieldType::Number => self.number(value).map(Some),
+            _ => {
+                let Value::String(text) = value else {
+                    return Err("Value must be text".into());
+                };
+                self.text(text.trim()).map(|text| Some(Value::String(text)))
+            }
+        }
+    }
+
+    fn text(&self, text: &str) -> Result<String, String> {
+        let length = text.chars().count();
+        if let Some(min_length) = self.min_length
+            && length < min_length
+        {
+            return Err(format!("Value must be at least {min_length} characters"));
+        }
+        let max_length = self.max_length.unwrap_or(FormSchema::DEFAULT_MAX_LENGTH);
+        if length > max_length {
+            return Err(format!("Value must be {max_length} characters or less"));
+        }
+
+        match self.field_type {
+            FieldType::Email => {
+                let email_regex = Regex::new(r"^[^\s@]+@[^\s@]+\.[^\s@]+$").unwrap();
+                if !email_regex.is_match(text) {
+                    return Err("Invalid email format".into());
+                }
+            }
+            FieldType::Url => {
+                let url_regex =
+                    Regex::new(r"^https?://[^\s/?#]+\.[^\s/?#]+(?:[/?#]\S*)?$").unwrap();
+                if !url_regex.is_match(text) {
+                    return Err("Value must be an http or https URL".into());
+                }
+            }
+            FieldType::Select if !self.options.iter().any(|option| option == text) => {
+                return Err(format!("Value must be one of: {}", self.options.join(", ")));
+            }
+            FieldType::Date => {
+                let date = NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                    .map_err(|_| "Value must be a date in YYYY-MM-DD format".to_string())?;
+                return Ok(date.format("%Y-%m-%d").to_string());
+            }
+            _ => {}
+        }
+
+        if let Some(pattern) = &self.pattern {
+            let matches = Regex::new(&format!("^(?:{pattern})$"))
+                .map(|regex| regex.is_match(text))
+                .unwrap_or(false);
+            if !matches {
+                return Err("Value does not match the expected format".into());
+            }
+        }
+
+        Ok(text.to_string())
+    }
+
+    fn number(&self, value: &Value) -> Result<Value, String> {
+        let number = match value {
+            Value::Number(number) => number.clone(),
+            Value::String(text) => {
+                let text = text.trim();
+                match text.parse::<i64>() {
+                    Ok(integer) => Number::from(integer),
+                    Err(_) => text
+                        .parse::<f64>()
+                        .ok()
+                        .and_then(Number::from_f64)
+                        .ok_or_else(|| "Value must be a number".to_string())?,
+                }
+            }
+            _ => return Err("Value must be a number".into()),
+        };
+
+        let as_f64 = number.as_f64().unwrap_or_default();
+        if let Some(min) = self.min
+            && as_f64 < min
+        {
+            return Err(format!("Value must be at least {min}"));
+        }
+        if let Some(max) = self.max
+            && as_f64 > max
+        {
+            return Err(format!("Value must be at most {max}"));
+        }
+        Ok(Value::Number(number))
+    }
+
+    fn checkbox(value: &Value) -> Result<bool, String> {
+        match value {
+            Value::Bool(checked) => Ok(*checked),
+            Value::String(text) => match text.trim().to_lowercase().as_str() {
+                "true" | "on" | "yes" | "1" => Ok(true),
+                "false" | "off" | "no" | "0" => Ok(false),
+                _ => Err("Value must be true or false".into()),
+            },
+            _ => Err("Value must be true or false".into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> FormSchema {
+        serde_json::from_value(json!({
+            "fields": [
+                { "name": "order_id", "type": "text", "required": true, "pattern": "[A-Z]{2}-\\d+" },
+                { "name": "quantity", "type": "number", "min": 1, "max": 10 },
+                { "name": "website", "type": "url" },
+                { "name": "contact", "type": "email" },
+                { "name": "plan", "type": "select", "options": ["free", "pro"] },
+                { "name": "consent", "type": "checkbox", "required": true },
+                { "name": "newsletter", "type": "checkbox" },
+                { "name": "visit", "type": "date" },
+                { "name": "notes", "type": "text", "min_length": 3, "max_length": 10 },
+            ]
+        }))
+        .unwrap()
+    }
+
+    fn data(value: Value) -> FormData {
+        value.as_object().unwrap().clone()
+    }
+
+    fn validate(schema: &FormSchema, value: Value) -> Result<FormData, Vec<String>> {
+        let mut errors = ValidationErrors::default();
+        let typed = schema.validate(Some(&data(value)), &mut errors);
+        match errors.is_empty() {
+            true => Ok(typed),
+            false => Err(errors.violations().iter().map(|v| v.path.clone()).collect()),
+        }
+    }
+
+    #[test]
+    fn test_form_post_strings_are_typed() {
+        let typed = validate(
+            &schema(),
+            json!({
+                "order_id": "AB-12",
+                "quantity": "3",
+                "website": "https://example.com/shop",
+                "contact": "buyer@example.com",
+                "plan": "pro",
+                "consent": "on",
+                "visit": "2026-03-01",
+                "notes": "",
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            Value::Object(typed),
+            json!({
+                "order_id": "AB-12",
+                "quantity": 3,
+                "website": "https://example.com/shop",
+                "contact": "buyer@example.com",
+                "plan": "pro",
+                "consent": true,
+                "newsletter": false,
+                "visit": "2026-03-01",
+            })
+        );
+    }
+
+    #[test]
+    fn test_json_values_are_accepted() {
+        let typed = validate(
+            &schema(),
+            json!({ "order_id": "XY-9", "quantity": 2.5, "consent": true }),
+        )
+        .unwrap();
+
+        assert_eq!(typed["quantity"], json!(2.5));
+        assert_eq!(typed["consent"], json!(true));
+    }
+
+    #[test]
+    fn test_every_invalid_field_is_reported() {
+        let errors = validate(
+            &schema(),
+            json!({
+                "order_id": "12",
+                "quantity": "11",
+                "website": "ftp://example.com",
+                "contact": "nope",
+                "plan": "enterprise",
+                "consent": "off",
+                "visit": "01/03/2026",
+                "notes": "ab",
+            }),
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            errors,
+            vec![
+                "data.order_id",
+                "data.quantity",
+                "data.website",
+                "data.contact",
+                "data.plan",
+                "data.consent",
+                "data.visit",
+                "data.notes",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_required_fields_must_be_present() {
+        let errors = validate(&schema(), json!({})).unwrap_err();
+
+        assert_eq!(errors, vec!["data.order_id", "data.consent"]);
+    }
+
+    #[test]
+    fn test_undeclared_fields_are_rejected() {
+        let errors = validate(
+            &schema(),
+            json!({ "order_id": "AB-1", "consent": true, "coupon": "FREE" }),
+        )
+        .unwrap_err();
+
+        assert_eq!(errors, vec!["data.coupon"]);
+    }
+
+    #[test]
+    fn test_unstructured_schema_keeps_any_short_text() {
+        let schema = FormSchema::unstructured();
+
+        let typed = validate(&schema, json!({ "rating": 5, "source": "website" })).unwrap();
+        assert_eq!(
+            Value::Object(typed),
+            json!({ "rating": "5", "source": "website" })
+        );
+
+        let errors = validate(
+            &schema,
+            json!({ "notes": "a".repeat(1001), "nested": { "a": 1 } }),
+        )
+        .unwrap_err();
+        assert_eq!(errors, vec!["data.nested", "data.notes"]);
+    }
+
+    #[test]
+    fn test_field_count_and_key_length_are_limited() {
+        let schema = FormSchema::unstructured();
+        let many: FormData = (0..21)
+            .map(|i| (format!("key{i}"), json!("value")))
+            .collect();
+        let mut errors = ValidationErrors::default();
+        schema.validate(Some(&many), &mut errors);
+        assert_eq!(errors.violations()[0].path, "data");
+
+        let errors = validate(&schema, json!({ "a".repeat(201): "value" })).unwrap_err();
+        assert_eq!(errors, vec!["data"]);
+    }
+
+    #[test]
+    fn test_check_rejects_broken_schemas() {
+        assert!(schema().check().is_ok());
+
+        let mut duplicate = schema();
+        duplicate.fields.push(duplicate.fields[0].clone());
+        assert!(duplicate.check().is_err());
+
+        let mut bad_pattern = schema();
+        bad_pattern.fields[0].pattern = Some("(".to_string());
+        assert!(bad_pattern.check().is_err());
+
+        let mut no_options = schema();
+        no_options.fields[4].options.clear();
+        assert!(no_options.check().is_err());
+    }
+}
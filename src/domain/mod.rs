@@ -1,4 +1,6 @@
 pub mod entity;
 pub mod enums;
+pub mod events;
 pub mod exceptions;
+pub mod form_schema;
 pub mod repository;
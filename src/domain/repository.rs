@@ -1,4 +1,5 @@
-use super::entity::ContactMessage;
+use super::entity::{Category, ContactMessage, Form, IdempotencyRecord, OutboxEvent, Site};
+use super::enums::{ContactMessageCategory, ContactMessageStatus};
 use super::exceptions::RepositoryError;
 
 use async_trait::async_trait;
@@ -7,5 +8,127 @@ use async_trait::async_trait;
 /// Trait representing repository-level operations for Contact Message entities.
 /// Provides methods for saving, retrieving, updating, and deleting Contact Messages in the database.
 pub trait ContactMessageRepository: Send + Sync {
-    async fn save(&self, contact: &ContactMessage) -> Result<bool, RepositoryError>;
+    /// Stores the message together with its outbox event and, if given, its
+    /// idempotency record in a single batch. Fails without storing anything if
+    /// an unexpired record already exists for the key.
+    async fn save(
+        &self,
+        contact: &ContactMessage,
+        event: &OutboxEvent,
+        idempotency: Option<&IdempotencyRecord>,
+    ) -> Result<bool, RepositoryError>;
+
+    /// Returns `RepositoryError::NotFound` when no message exists with the given id.
+    async fn find_by_id(&self, id: &str) -> Result<ContactMessage, RepositoryError>;
+
+    /// Returns the newest message with the given content fingerprint sent
+    /// through the same site (or none) and created at or after `since`, if any.
+    async fn find_recent_by_fingerprint(
+        &self,
+        fingerprint: &str,
+        site_id: Option<&str>,
+        since: i64,
+    ) -> Result<Option<ContactMessage>, RepositoryError>;
+
+    /// Returns messages ordered newest first.
+    async fn list(&self, limit: u32, offset: u32) -> Result<Vec<ContactMessage>, RepositoryError>;
+
+    /// Persists the status fields of `contact`, provided the stored status is
+    /// still `previous_status`. Returns `false` if it was changed concurrently.
+    async fn update_status(
+        &self,
+        contact: &ContactMessage,
+        previous_status: ContactMessageStatus,
+    ) -> Result<bool, RepositoryError>;
+
+    /// Persists the spam score and status fields of `contact`, provided the
+    /// stored status is still `previous_status`.
+    async fn update_spam_score(
+        &self,
+        contact: &ContactMessage,
+        previous_status: ContactMessageStatus,
+    ) -> Result<bool, RepositoryError>;
+
+    /// Counts the messages sent before `contact` from the same email address,
+    /// compared case-insensitively.
+    async fn count_previous(&self, contact: &ContactMessage) -> Result<u32, RepositoryError>;
+
+    /// Persists the enrichment fields of `contact`.
+    async fn update_enrichment(&self, contact: &ContactMessage) -> Result<bool, RepositoryError>;
+
+    /// Sets `auto_replied_at` to `now` unless it is already set. Returns
+    /// `false` if the submitter was already sent an acknowledgement.
+    async fn mark_auto_replied(&self, id: &str, now: i64) -> Result<bool, RepositoryError>;
+}
+
+#[async_trait(?Send)]
+/// Trait representing repository-level operations for outbox events.
+pub trait OutboxRepository: Send + Sync {
+    /// Returns pending events whose next attempt is due, oldest first.
+    async fn find_due(&self, now: i64, limit: u32) -> Result<Vec<OutboxEvent>, RepositoryError>;
+
+    /// Moves the next attempt of a due pending event to `lease_until`, so no
+    /// other run picks it up meanwhile. Returns `false` if the event was not
+    /// due, typically because another run has already claimed it.
+    async fn claim(&self, id: &str, now: i64, lease_until: i64) -> Result<bool, RepositoryError>;
+
+    /// Persists the delivery state (status, attempts, schedule and error) of `event`.
+    async fn update(&self, event: &OutboxEvent) -> Result<bool, RepositoryError>;
+}
+
+#[async_trait(?Send)]
+/// Trait representing repository-level operations for idempotency records.
+pub trait IdempotencyRepository: Send + Sync {
+    /// Returns the record for `key` unless it has expired by `now`.
+    async fn find(&self, key: &str, now: i64)
+    -> Result<Option<IdempotencyRecord>, RepositoryError>;
+
+    /// Stores `record` on its own, for a request answered with a message that
+    /// already exists. Fails like `ContactMessageRepository::save` if an
+    /// unexpired record already exists for the key.
+    async fn insert(&self, record: &IdempotencyRecord) -> Result<bool, RepositoryError>;
+
+    /// Removes records that expired before `now`, returning how many were deleted.
+    async fn delete_expired(&self, now: i64) -> Result<u64, RepositoryError>;
+}
+
+#[async_trait(?Send)]
+/// Trait representing repository-level operations for redeemed form tokens.
+pub trait FormTokenRepository: Send + Sync {
+    /// Records `nonce` as used until `expires_at`, forgetting nonces that
+    /// expired before `now`. Returns `false` if the nonce was already used.
+    async fn redeem(&self, nonce: &str, expires_at: i64, now: i64)
+    -> Result<bool, RepositoryError>;
+}
+
+#[async_trait(?Send)]
+/// Trait representing repository-level operations for sites.
+pub trait SiteRepository: Send + Sync {
+    async fn find_by_id(&self, id: &str) -> Result<Option<Site>, RepositoryError>;
+
+    /// Returns the site listing `origin` among its allowed origins, if any.
+    async fn find_by_origin(&self, origin: &str) -> Result<Option<Site>, RepositoryError>;
+}
+
+#[async_trait(?Send)]
+/// Trait representing repository-level operations for forms.
+pub trait FormRepository: Send + Sync {
+    async fn find_by_id(&self, id: &str) -> Result<Option<Form>, RepositoryError>;
+}
+
+#[async_trait(?Send)]
+/// Trait representing repository-level operations for categories.
+pub trait CategoryRepository: Send + Sync {
+    /// Returns every category, active or not, ordered by sort order then slug.
+    async fn list(&self) -> Result<Vec<Category>, RepositoryError>;
+
+    /// Returns `false` without storing anything if the slug is already taken.
+    async fn insert(&self, category: &Category) -> Result<bool, RepositoryError>;
+
+    /// Returns `false` if no category has the slug of `category`.
+    async fn update(&self, category: &Category) -> Result<bool, RepositoryError>;
+
+    /// Deletes the category unless messages are filed under it. Returns
+    /// whether a row was deleted.
+    async fn delete(&self, slug: &ContactMessageCategory) -> Result<bool, RepositoryError>;
 }
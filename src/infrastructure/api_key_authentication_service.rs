@@ -0,0 +1,226 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::application::{
+    authentication_service::{AuthenticationServiceTrait, Credentials, Principal, Scope},
+    exceptions::AppError,
+};
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+
+/// An admin API key as configured in `ADMIN_API_KEYS`.
+///
+/// Entries have the form `name:scope+scope:sha256hex`, e.g.
+/// `ops:read+write:5e8848...`. Only the SHA-256 hash of the key is stored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiKey {
+    pub name: String,
+    pub scopes: Vec<Scope>,
+    hash: [u8; 32],
+}
+
+impl ApiKey {
+    pub fn parse(entry: &str) -> Result<Self, String> {
+        let parts: Vec<&str> = entry.trim().split(':').collect();
+        let [name, scopes, hash] = parts.as_slice() else {
+            return Err("API key must have the form name:scopes:sha256".to_string());
+        };
+
+        if name.is_empty() {
+            return Err("API key name cannot be empty".to_string());
+        }
+
+        let scopes = scopes
+            .split('+')
+            .map(|s| Scope::from_str(s.trim()).map_err(|_| format!("Unknown scope '{s}'")))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let hash: [u8; 32] = hex::decode(hash)
+            .map_err(|_| "API key hash must be hex encoded".to_string())?
+            .try_into()
+            .map_err(|_| "API key hash must be a SHA-256 digest".to_string())?;
+
+        Ok(Self {
+            name: name.to_string(),
+            scopes,
+            hash,
+        })
+    }
+
+    fn matches(&self, presented_hash: &[u8; 32]) -> bool {
+        constant_time_eq(&self.hash, presented_hash)
+    }
+}
+
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.ct_eq(b).into()
+}
+
+/// Extracts the token from an `Authorization: Bearer <token>` header value.
+pub fn parse_bearer_token(header: &str) -> Option<&str> {
+    let (scheme, token) = header.trim().split_once(' ')?;
+    let token = token.trim();
+
+    if scheme.eq_ignore_ascii_case("bearer") && !token.is_empty() {
+        Some(token)
+    } else {
+        None
+    }
+}
+
+pub struct ApiKeyAuthenticationService {
+    keys: Vec<ApiKey>,
+}
+
+impl ApiKeyAuthenticationService {
+    pub fn create(entries: &[String]) -> Result<Arc<dyn AuthenticationServiceTrait>, String> {
+        Ok(Arc::new(Self::new(entries)?))
+    }
+
+    pub fn new(entries: &[String]) -> Result<Self, String> {
+        let keys = entries
+            .iter()
+            .filter(|e| !e.trim().is_empty())
+            .map(|e| ApiKey::parse(e))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { keys })
+    }
+
+    fn find_key(&self, token: &str) -> Option<&ApiKey> {
+        let presented_hash: [u8; 32] = Sha256::digest(token.as_bytes()).into();
+
+        // Check every key so the time taken does not reveal which one matched.
+        self.keys.iter().fold(None, |found, key| {
+            if key.matches(&presented_hash) {
+                Some(key)
+            } else {
+                found
+            }
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl AuthenticationServiceTrait for ApiKeyAuthenticationService {
+    async fn authenticate(&self, credentials: &Credentials) -> Result<Principal, AppError> {
+        let token = credentials
+            .authorization
+            .as_deref()
+            .and_then(parse_bearer_token)
+            .ok_or_else(|| AppError::Unauthorised("Missing bearer token".to_string()))?;
+
+        let key = self
+            .find_key(token)
+            .ok_or_else(|| AppError::Unauthorised("Invalid API key".to_string()))?;
+
+        Ok(Principal {
+            subject: key.name.clone(),
+            email: None,
+            scopes: key.scopes.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // sha256("test-admin-key")
+    const TEST_KEY_HASH: &str = "944650a7cd0f9e14d5c4fb15edbffb7fa45fb9ed36a4fa9be3d7e5476ae51bd9";
+
+    fn credentials(authorization: &str) -> Credentials {
+        Credentials {
+            authorization: Some(authorization.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_api_key_valid() {
+        let key = ApiKey::parse(&format!("ops:read+write:{TEST_KEY_HASH}")).unwrap();
+
+        assert_eq!(key.name, "ops");
+        assert_eq!(key.scopes, vec![Scope::Read, Scope::Write]);
+    }
+
+    #[test]
+    fn test_parse_api_key_invalid() {
+        let invalid_entries = vec![
+            "".to_string(),
+            "ops".to_string(),
+            format!(":read:{TEST_KEY_HASH}"),
+            format!("ops:admin:{TEST_KEY_HASH}"),
+            "ops:read:not-hex".to_string(),
+            "ops:read:abcd".to_string(),
+            format!("ops:read:{TEST_KEY_HASH}:extra"),
+        ];
+
+        for entry in invalid_entries {
+            assert!(
+                ApiKey::parse(&entry).is_err(),
+                "'{}' should be invalid",
+                entry
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_bearer_token() {
+        assert_eq!(parse_bearer_token("Bearer abc123"), Some("abc123"));
+        assert_eq!(parse_bearer_token("bearer abc123"), Some("abc123"));
+        assert_eq!(parse_bearer_token("  Bearer   abc123  "), Some("abc123"));
+
+        assert_eq!(parse_bearer_token("Basic abc123"), None);
+        assert_eq!(parse_bearer_token("Bearer"), None);
+        assert_eq!(parse_bearer_token("Bearer   "), None);
+        assert_eq!(parse_bearer_token("abc123"), None);
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret-longer"));
+        assert!(!constant_time_eq(b"", b"secret"));
+    }
+
+    #[test]
+    fn test_create_skips_empty_entries() {
+        let service = ApiKeyAuthenticationService::new(&["".to_string()]).unwrap();
+        assert!(service.keys.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_valid_key() {
+        let service =
+            ApiKeyAuthenticationService::new(&[format!("ops:read:{TEST_KEY_HASH}")]).unwrap();
+
+        let principal = service
+            .authenticate(&credentials("Bearer test-admin-key"))
+            .await
+            .unwrap();
+
+        assert_eq!(principal.subject, "ops");
+        assert!(principal.require(Scope::Read).is_ok());
+        assert!(matches!(
+            principal.require(Scope::Write),
+            Err(AppError::Forbidden(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_rejects_invalid_credentials() {
+        let service =
+            ApiKeyAuthenticationService::new(&[format!("ops:read:{TEST_KEY_HASH}")]).unwrap();
+
+        for authorization in ["Bearer wrong-key", "Basic test-admin-key"] {
+            let result = service.authenticate(&credentials(authorization)).await;
+            assert!(matches!(result, Err(AppError::Unauthorised(_))));
+        }
+
+        let result = service.authenticate(&Credentials::default()).await;
+        assert!(matches!(result, Err(AppError::Unauthorised(_))));
+    }
+}
@@ -0,0 +1,167 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::domain::{
+    entity::Category,
+    enums::{ContactMessageCategory, ContactMessagePriority},
+    exceptions::RepositoryError,
+    repository::CategoryRepository as CategoryRepositoryInterface,
+};
+use async_trait::async_trait;
+use serde::Deserialize;
+use worker::{D1Database, D1PreparedStatement};
+
+#[derive(Deserialize)]
+struct CategoryRow {
+    slug: String,
+    display_name: String,
+    description: String,
+    active: f64,
+    sort_order: f64,
+    default_priority: String,
+    created_at: f64,
+    updated_at: f64,
+}
+
+impl TryFrom<CategoryRow> for Category {
+    type Error = RepositoryError;
+
+    fn try_from(row: CategoryRow) -> Result<Self, Self::Error> {
+        let slug = ContactMessageCategory::from_str(&row.slug).map_err(|_| {
+            RepositoryError::DatabaseError(format!("Invalid category slug: {}", row.slug))
+        })?;
+        let default_priority =
+            ContactMessagePriority::from_str(&row.default_priority).map_err(|_| {
+                RepositoryError::DatabaseError(format!(
+                    "Unknown priority {} for category {}",
+                    row.default_priority, row.slug
+                ))
+            })?;
+
+        Ok(Category {
+            slug,
+            display_name: row.display_name,
+            description: row.description,
+            active: row.active != 0.0,
+            sort_order: row.sort_order as i64,
+            default_priority,
+            created_at: row.created_at as i64,
+            updated_at: row.updated_at as i64,
+        })
+    }
+}
+
+pub struct CategoryRepository {
+    db: D1Database,
+}
+
+impl CategoryRepository {
+    pub fn create(db: D1Database) -> Arc<dyn CategoryRepositoryInterface> {
+        Arc::new(Self { db })
+    }
+
+    /// Runs a write and returns whether it changed any row.
+    async fn run(statement: D1PreparedStatement) -> Result<bool, RepositoryError> {
+        let result = statement
+            .run()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to execute query: {e}")))?;
+
+        let changes = result
+            .meta()
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to read result: {e}")))?
+            .and_then(|meta| meta.changes)
+            .unwrap_or(0);
+
+        Ok(changes > 0)
+    }
+}
+
+#[async_trait(?Send)]
+impl CategoryRepositoryInterface for CategoryRepository {
+    async fn list(&self) -> Result<Vec<Category>, RepositoryError> {
+        let result = self
+            .db
+            .prepare(
+                "SELECT slug, display_name, description, active, sort_order, default_priority,
+                 created_at, updated_at
+                 FROM categories ORDER BY sort_order, slug",
+            )
+            .all()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to execute query: {e}")))?;
+
+        let rows: Vec<CategoryRow> = result
+            .results()
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to read rows: {e}")))?;
+
+        rows.into_iter().map(Category::try_from).collect()
+    }
+
+    async fn insert(&self, category: &Category) -> Result<bool, RepositoryError> {
+        let statement = self
+            .db
+            .prepare(
+                "INSERT INTO categories
+                 (slug, display_name, description, active, sort_order, default_priority,
+                  created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                 ON CONFLICT(slug) DO NOTHING",
+            )
+            .bind(&[
+                category.slug.as_str().into(),
+                category.display_name.clone().into(),
+                category.description.clone().into(),
+                (category.active as u8).into(),
+                (category.sort_order as f64).into(),
+                category.default_priority.to_string().into(),
+                (category.created_at as f64).into(),
+                (category.updated_at as f64).into(),
+            ])
+            .map_err(|e| {
+                RepositoryError::DatabaseError(format!("Failed to bind parameters: {e}"))
+            })?;
+
+        Self::run(statement).await
+    }
+
+    async fn update(&self, category: &Category) -> Result<bool, RepositoryError> {
+        let statement = self
+            .db
+            .prepare(
+                "UPDATE categories
+                 SET display_name = ?1, description = ?2, active = ?3, sort_order = ?4,
+                     default_priority = ?5, updated_at = ?6
+                 WHERE slug = ?7",
+            )
+            .bind(&[
+                category.display_name.clone().into(),
+                category.description.clone().into(),
+                (category.active as u8).into(),
+                (category.sort_order as f64).into(),
+                category.default_priority.to_string().into(),
+                (category.updated_at as f64).into(),
+                category.slug.as_str().into(),
+            ])
+            .map_err(|e| {
+                RepositoryError::DatabaseError(format!("Failed to bind parameters: {e}"))
+            })?;
+
+        Self::run(statement).await
+    }
+
+    async fn delete(&self, slug: &ContactMessageCategory) -> Result<bool, RepositoryError> {
+        let statement = self
+            .db
+            .prepare(
+                "DELETE FROM categories WHERE slug = ?1
+                 AND NOT EXISTS (SELECT 1 FROM contact_messages WHERE category = ?1)",
+            )
+            .bind(&[slug.as_str().into()])
+            .map_err(|e| {
+                RepositoryError::DatabaseError(format!("Failed to bind parameters: {e}"))
+            })?;
+
+        Self::run(statement).await
+    }
+}
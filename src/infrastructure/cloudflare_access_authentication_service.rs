@@ -0,0 +1,553 @@
+use std::sync::{Arc, LazyLock, Mutex};
+
+use crate::{
+    application::{
+        authentication_service::{AuthenticationServiceTrait, Credentials, Principal, Scope},
+        exceptions::AppError,
+    },
+    infrastructure::http_client::{HttpClient, HttpRequest},
+    log_error, log_info,
+};
+use async_trait::async_trait;
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::Utc;
+use rsa::{BigUint, RsaPublicKey, pkcs1v15, signature::Verifier};
+use serde::{Deserialize, de::DeserializeOwned};
+use sha2::Sha256;
+
+const JWKS_TTL_SECONDS: i64 = 60 * 60;
+/// Tokens naming a key missing from the cache trigger a refetch at most this
+/// often, so forged `kid`s can't turn admin requests into calls to Access.
+const JWKS_MIN_REFRESH_SECONDS: i64 = 60;
+
+#[derive(Deserialize, Debug, Clone)]
+struct Jwk {
+    kid: String,
+    kty: String,
+    n: String,
+    e: String,
+}
+
+#[derive(Deserialize)]
+struct JwkSet {
+    keys: Vec<Jwk>,
+}
+
+struct CachedJwks {
+    keys: Vec<Jwk>,
+    last_refresh_at: i64,
+}
+
+/// Isolate-wide cache of the Access signing keys, shared between requests.
+#[derive(Default)]
+pub struct JwksCache {
+    inner: Mutex<Option<CachedJwks>>,
+}
+
+impl JwksCache {
+    pub fn global() -> Arc<JwksCache> {
+        static CACHE: LazyLock<Arc<JwksCache>> = LazyLock::new(Default::default);
+        CACHE.clone()
+    }
+
+    fn get(&self, now: i64) -> Option<Vec<Jwk>> {
+        let inner = self.inner.lock().ok()?;
+        inner
+            .as_ref()
+            .filter(|cached| now - cached.last_refresh_at < JWKS_TTL_SECONDS)
+            .map(|cached| cached.keys.clone())
+    }
+
+    /// Whether the keys were fetched too recently to fetch them again.
+    fn recently_refreshed(&self, now: i64) -> bool {
+        self.inner.lock().ok().is_some_and(|inner| {
+            inner
+                .as_ref()
+                .is_some_and(|cached| now - cached.last_refresh_at < JWKS_MIN_REFRESH_SECONDS)
+        })
+    }
+
+    fn set(&self, keys: Vec<Jwk>, now: i64) {
+        if let Ok(mut inner) = self.inner.lock() {
+            *inner = Some(CachedJwks {
+                keys,
+                last_refresh_at: now,
+            });
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct JwtHeader {
+    alg: String,
+    kid: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Audience {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Audience {
+    fn contains(&self, audience: &str) -> bool {
+        match self {
+            Audience::One(aud) => aud == audience,
+            Audience::Many(auds) => auds.iter().any(|aud| aud == audience),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct AccessClaims {
+    aud: Audience,
+    exp: i64,
+    nbf: Option<i64>,
+    iss: Option<String>,
+    email: Option<String>,
+    common_name: Option<String>,
+    sub: Option<String>,
+}
+
+/// Verifies the `Cf-Access-Jwt-Assertion` header issued by Cloudflare Access.
+///
+/// Identities that pass the Access policy are granted read and write scopes.
+pub struct CloudflareAccessAuthenticationService {
+    http_client: Arc<dyn HttpClient>,
+    certs_url: String,
+    issuer: String,
+    audience: String,
+    cache: Arc<JwksCache>,
+}
+
+impl CloudflareAccessAuthenticationService {
+    pub fn create(
+        http_client: Arc<dyn HttpClient>,
+        team_domain: String,
+        audience: String,
+    ) -> Arc<dyn AuthenticationServiceTrait> {
+        Arc::new(Self::new(
+            http_client,
+            team_domain,
+            audience,
+            JwksCache::global(),
+        ))
+    }
+
+    pub fn new(
+        http_client: Arc<dyn HttpClient>,
+        team_domain: String,
+        audience: String,
+        cache: Arc<JwksCache>,
+    ) -> Self {
+        let issuer = if team_domain.starts_with("http://") || team_domain.starts_with("https://") {
+            team_domain.trim_end_matches('/').to_string()
+        } else {
+            format!("https://{}", team_domain.trim_end_matches('/'))
+        };
+
+        Self {
+            http_client,
+            certs_url: format!("{issuer}/cdn-cgi/access/certs"),
+            issuer,
+            audience,
+            cache,
+        }
+    }
+
+    async fn fetch_keys(&self, now: i64) -> Result<Vec<Jwk>, AppError> {
+        log_info!("Fetching Access signing keys from {}", self.certs_url);
+
+        let response = self
+            .http_client
+            .send(HttpRequest::get(&self.certs_url))
+            .await
+            .map_err(|e| {
+                log_error!("Failed to fetch Access signing keys: {}", e);
+                AppError::InternalError
+            })?;
+
+        if !response.is_success() {
+            log_error!("Access certs endpoint returned {}", response.status);
+            return Err(AppError::InternalError);
+        }
+
+        let jwk_set: JwkSet = response.json().map_err(|e| {
+            log_error!("Failed to parse Access signing keys: {}", e);
+            AppError::InternalError
+        })?;
+
+        self.cache.set(jwk_set.keys.clone(), now);
+        Ok(jwk_set.keys)
+    }
+
+    async fn find_key(&self, kid: &str, now: i64) -> Result<Jwk, AppError> {
+        if let Some(key) = self
+            .cache
+            .get(now)
+            .and_then(|keys| keys.into_iter().find(|k| k.kid == kid))
+        {
+            return Ok(key);
+        }
+
+        if self.cache.recently_refreshed(now) {
+            return Err(AppError::Unauthorised(format!(
+                "Unknown signing key '{kid}'"
+            )));
+        }
+
+        // The key may have been rotated since the cache was filled.
+        self.fetch_keys(now)
+            .await?
+            .into_iter()
+            .find(|k| k.kid == kid)
+            .ok_or_else(|| AppError::Unauthorised(format!("Unknown signing key '{kid}'")))
+    }
+
+    fn verify_signature(jwk: &Jwk, signing_input: &str, signature: &[u8]) -> Result<(), AppError> {
+        if jwk.kty != "RSA" {
+            return Err(AppError::Unauthorised("Signing key is not RSA".to_string()));
+        }
+
+        let n = decode_segment(&jwk.n)?;
+        let e = decode_segment(&jwk.e)?;
+        let public_key = RsaPublicKey::new(BigUint::from_bytes_be(&n), BigUint::from_bytes_be(&e))
+            .map_err(|e| {
+                log_error!("Invalid Access signing key: {}", e);
+                AppError::InternalError
+            })?;
+
+        let signature = pkcs1v15::Signature::try_from(signature)
+            .map_err(|_| AppError::Unauthorised("Malformed signature".to_string()))?;
+
+        pkcs1v15::VerifyingKey::<Sha256>::new(public_key)
+            .verify(signing_input.as_bytes(), &signature)
+            .map_err(|_| AppError::Unauthorised("Invalid signature".to_string()))
+    }
+
+    fn validate_claims(&self, claims: &AccessClaims, now: i64) -> Result<(), AppError> {
+        if !claims.aud.contains(&self.audience) {
+            return Err(AppError::Unauthorised("Invalid audience".to_string()));
+        }
+
+        if claims.exp <= now {
+            return Err(AppError::Unauthorised("Token has expired".to_string()));
+        }
+
+        if claims.nbf.is_some_and(|nbf| nbf > now) {
+            return Err(AppError::Unauthorised("Token is not yet valid".to_string()));
+        }
+
+        if claims.iss.as_ref().is_some_and(|iss| iss != &self.issuer) {
+            return Err(AppError::Unauthorised("Invalid issuer".to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+fn decode_segment(segment: &str) -> Result<Vec<u8>, AppError> {
+    URL_SAFE_NO_PAD
+        .decode(segment)
+        .map_err(|_| AppError::Unauthorised("Malformed token".to_string()))
+}
+
+fn decode_json_segment<T: DeserializeOwned>(segment: &str) -> Result<T, AppError> {
+    serde_json::from_slice(&decode_segment(segment)?)
+        .map_err(|_| AppError::Unauthorised("Malformed token".to_string()))
+}
+
+#[async_trait(?Send)]
+impl AuthenticationServiceTrait for CloudflareAccessAuthenticationService {
+    async fn authenticate(&self, credentials: &Credentials) -> Result<Principal, AppError> {
+        let token = credentials
+            .access_jwt
+            .as_deref()
+            .ok_or_else(|| AppError::Unauthorised("Missing Access token".to_string()))?;
+
+        let [header, payload, signature] = token.split('.').collect::<Vec<_>>()[..] else {
+            return Err(AppError::Unauthorised("Malformed token".to_string()));
+        };
+
+        let jwt_header: JwtHeader = decode_json_segment(header)?;
+        if jwt_header.alg != "RS256" {
+            return Err(AppError::Unauthorised(format!(
+                "Unsupported algorithm '{}'",
+                jwt_header.alg
+            )));
+        }
+        let kid = jwt_header
+            .kid
+            .ok_or_else(|| AppError::Unauthorised("Token has no key id".to_string()))?;
+
+        let now = Utc::now().timestamp();
+        let jwk = self.find_key(&kid, now).await?;
+        Self::verify_signature(
+            &jwk,
+            &format!("{header}.{payload}"),
+            &decode_segment(signature)?,
+        )?;
+
+        let claims: AccessClaims = decode_json_segment(payload)?;
+        self.validate_claims(&claims, now)?;
+
+        let subject = claims
+            .email
+            .clone()
+            .or(claims.common_name)
+            .or(claims.sub)
+            .ok_or_else(|| AppError::Unauthorised("Token has no subject".to_string()))?;
+
+        Ok(Principal {
+            subject,
+            email: claims.email,
+            scopes: vec![Scope::Read, Scope::Write],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::http_client::test_support::ReqwestHttpClient;
+    use rsa::{
+        RsaPrivateKey, pkcs1::DecodeRsaPrivateKey, signature::SignatureEncoding, signature::Signer,
+        traits::PublicKeyParts,
+    };
+    use serde_json::json;
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{method, path},
+    };
+
+    const AUDIENCE: &str = "test-audience";
+    const KID: &str = "test-kid";
+
+    // Throwaway key generated locally with `openssl genrsa 2048`, used only by these tests.
+    const TEST_PRIVATE_KEY: &str = include_str!("../../test/fixtures/access_test_key.pem");
+
+    fn private_key() -> RsaPrivateKey {
+        RsaPrivateKey::from_pkcs1_pem(TEST_PRIVATE_KEY).unwrap()
+    }
+
+    fn jwks() -> serde_json::Value {
+        let public_key = private_key().to_public_key();
+        json!({
+            "keys": [{
+                "kid": KID,
+                "kty": "RSA",
+                "alg": "RS256",
+                "use": "sig",
+                "n": URL_SAFE_NO_PAD.encode(public_key.n().to_bytes_be()),
+                "e": URL_SAFE_NO_PAD.encode(public_key.e().to_bytes_be()),
+            }]
+        })
+    }
+
+    fn sign(header: serde_json::Value, claims: serde_json::Value) -> String {
+        let signing_input = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(header.to_string()),
+            URL_SAFE_NO_PAD.encode(claims.to_string())
+        );
+        let signature = pkcs1v15::SigningKey::<Sha256>::new(private_key())
+            .sign(signing_input.as_bytes())
+            .to_bytes();
+        format!("{signing_input}.{}", URL_SAFE_NO_PAD.encode(signature))
+    }
+
+    fn claims(issuer: &str) -> serde_json::Value {
+        let now = Utc::now().timestamp();
+        json!({
+            "aud": [AUDIENCE],
+            "email": "admin@example.com",
+            "exp": now + 300,
+            "iat": now,
+            "nbf": now,
+            "iss": issuer,
+            "sub": "user-id",
+            "type": "app",
+        })
+    }
+
+    fn header() -> serde_json::Value {
+        json!({ "alg": "RS256", "kid": KID, "typ": "JWT" })
+    }
+
+    fn credentials(token: String) -> Credentials {
+        Credentials {
+            access_jwt: Some(token),
+            ..Default::default()
+        }
+    }
+
+    async fn setup() -> (MockServer, CloudflareAccessAuthenticationService) {
+        let server = MockServer::start().await;
+        let service = CloudflareAccessAuthenticationService::new(
+            ReqwestHttpClient::create(),
+            server.uri(),
+            AUDIENCE.to_string(),
+            Arc::new(JwksCache::default()),
+        );
+        (server, service)
+    }
+
+    async fn mount_jwks(server: &MockServer, expected_calls: u64) {
+        Mock::given(method("GET"))
+            .and(path("/cdn-cgi/access/certs"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(jwks()))
+            .expect(expected_calls)
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_valid_token() {
+        let (server, service) = setup().await;
+        mount_jwks(&server, 1).await;
+
+        let token = sign(header(), claims(&server.uri()));
+        let principal = service.authenticate(&credentials(token)).await.unwrap();
+
+        assert_eq!(principal.subject, "admin@example.com");
+        assert_eq!(principal.email.as_deref(), Some("admin@example.com"));
+        assert!(principal.require(Scope::Write).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_signing_keys_are_cached() {
+        let (server, service) = setup().await;
+        mount_jwks(&server, 1).await;
+
+        for _ in 0..3 {
+            let token = sign(header(), claims(&server.uri()));
+            assert!(service.authenticate(&credentials(token)).await.is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rejects_wrong_audience() {
+        let (server, service) = setup().await;
+        mount_jwks(&server, 1).await;
+
+        let mut claims = claims(&server.uri());
+        claims["aud"] = json!(["another-application"]);
+
+        let result = service
+            .authenticate(&credentials(sign(header(), claims)))
+            .await;
+        assert!(matches!(result, Err(AppError::Unauthorised(_))));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_expired_token() {
+        let (server, service) = setup().await;
+        mount_jwks(&server, 1).await;
+
+        let mut claims = claims(&server.uri());
+        claims["exp"] = json!(Utc::now().timestamp() - 10);
+
+        let result = service
+            .authenticate(&credentials(sign(header(), claims)))
+            .await;
+        assert!(matches!(result, Err(AppError::Unauthorised(_))));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_tampered_payload() {
+        let (server, service) = setup().await;
+        mount_jwks(&server, 1).await;
+
+        let token = sign(header(), claims(&server.uri()));
+        let parts: Vec<&str> = token.split('.').collect();
+        let mut forged_claims = claims(&server.uri());
+        forged_claims["email"] = json!("attacker@example.com");
+        let forged = format!(
+            "{}.{}.{}",
+            parts[0],
+            URL_SAFE_NO_PAD.encode(forged_claims.to_string()),
+            parts[2]
+        );
+
+        let result = service.authenticate(&credentials(forged)).await;
+        assert!(matches!(result, Err(AppError::Unauthorised(_))));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_unsupported_algorithm() {
+        let (server, service) = setup().await;
+        mount_jwks(&server, 0).await;
+
+        let header = json!({ "alg": "HS256", "kid": KID });
+        let result = service
+            .authenticate(&credentials(sign(header, claims(&server.uri()))))
+            .await;
+        assert!(matches!(result, Err(AppError::Unauthorised(_))));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_unknown_key_id() {
+        let (server, service) = setup().await;
+        mount_jwks(&server, 1).await;
+
+        let header = json!({ "alg": "RS256", "kid": "rotated-away" });
+        let result = service
+            .authenticate(&credentials(sign(header, claims(&server.uri()))))
+            .await;
+        assert!(matches!(result, Err(AppError::Unauthorised(_))));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_key_ids_do_not_refetch_within_interval() {
+        let (server, service) = setup().await;
+        mount_jwks(&server, 1).await;
+
+        for kid in ["forged-1", "forged-2"] {
+            let header = json!({ "alg": "RS256", "kid": kid });
+            let result = service
+                .authenticate(&credentials(sign(header, claims(&server.uri()))))
+                .await;
+            assert!(matches!(result, Err(AppError::Unauthorised(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unknown_key_id_refetches_after_interval() {
+        let (server, service) = setup().await;
+        mount_jwks(&server, 1).await;
+        let now = Utc::now().timestamp();
+        service.cache.set(vec![], now - JWKS_MIN_REFRESH_SECONDS);
+
+        let key = service.find_key(KID, now).await.unwrap();
+
+        assert_eq!(key.kid, KID);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_missing_or_malformed_token() {
+        let (_server, service) = setup().await;
+
+        let result = service.authenticate(&Credentials::default()).await;
+        assert!(matches!(result, Err(AppError::Unauthorised(_))));
+
+        let result = service
+            .authenticate(&credentials("not-a-jwt".to_string()))
+            .await;
+        assert!(matches!(result, Err(AppError::Unauthorised(_))));
+    }
+
+    #[tokio::test]
+    async fn test_jwks_unavailable_is_internal_error() {
+        let (server, service) = setup().await;
+        Mock::given(method("GET"))
+            .and(path("/cdn-cgi/access/certs"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+
+        let token = sign(header(), claims(&server.uri()));
+        let result = service.authenticate(&credentials(token)).await;
+        assert!(matches!(result, Err(AppError::InternalError)));
+    }
+}
@@ -1,14 +1,86 @@
-use std::collections::HashMap;
+use std::str::FromStr;
 use std::sync::Arc;
 
 use crate::domain::{
-    entity::ContactMessage, exceptions::RepositoryError,
+    entity::{ContactMessage, IdempotencyRecord, OutboxEvent},
+    enums::{ContactMessageCategory, ContactMessagePriority, ContactMessageStatus},
+    exceptions::RepositoryError,
+    form_schema::FormData,
     repository::ContactMessageRepository as ContactMessageRepositoryInterface,
 };
+use crate::infrastructure::{
+    idempotency_repository::IdempotencyRepository, outbox_repository::OutboxRepository,
+};
 use async_trait::async_trait;
-use chrono::Utc;
+use serde::Deserialize;
 use worker::D1Database;
 
+#[derive(Deserialize)]
+struct ContactMessageRow {
+    id: String,
+    category: String,
+    email: String,
+    name: String,
+    message: String,
+    data: Option<String>,
+    status: String,
+    priority: String,
+    created_at: f64,
+    updated_at: Option<f64>,
+    resolved_at: Option<f64>,
+    spam_score: Option<f64>,
+    previous_messages: Option<f64>,
+    auto_replied_at: Option<f64>,
+    fingerprint: Option<String>,
+    site_id: Option<String>,
+    form_id: Option<String>,
+}
+
+impl TryFrom<ContactMessageRow> for ContactMessage {
+    type Error = RepositoryError;
+
+    fn try_from(row: ContactMessageRow) -> Result<Self, Self::Error> {
+        let category = ContactMessageCategory::from_str(&row.category).map_err(|_| {
+            RepositoryError::DatabaseError(format!("Unknown category: {}", row.category))
+        })?;
+        let status = ContactMessageStatus::from_str(&row.status).map_err(|_| {
+            RepositoryError::DatabaseError(format!("Unknown status: {}", row.status))
+        })?;
+        let priority = ContactMessagePriority::from_str(&row.priority).map_err(|_| {
+            RepositoryError::DatabaseError(format!("Unknown priority: {}", row.priority))
+        })?;
+
+        // Messages stored before fingerprints were introduced get theirs computed on read.
+        let fingerprint = row.fingerprint.unwrap_or_else(|| {
+            ContactMessage::content_fingerprint(&row.email, &category, &row.message)
+        });
+
+        Ok(ContactMessage {
+            id: row.id,
+            category,
+            email: row.email,
+            name: row.name,
+            message: row.message,
+            data: ContactMessageRepository::deserialize_data(row.data)?,
+            status,
+            priority,
+            created_at: row.created_at as i64,
+            updated_at: row.updated_at.unwrap_or(row.created_at) as i64,
+            resolved_at: row.resolved_at.map(|t| t as i64),
+            spam_score: row.spam_score.map(|s| s as u32),
+            previous_messages: row.previous_messages.map(|n| n as u32),
+            auto_replied_at: row.auto_replied_at.map(|t| t as i64),
+            fingerprint,
+            site_id: row.site_id,
+            form_id: row.form_id,
+        })
+    }
+}
+
+const SELECT_COLUMNS: &str = "id, category, email, name, message, data, status, priority, \
+     created_at, updated_at, resolved_at, spam_score, previous_messages, auto_replied_at, \
+     fingerprint, site_id, form_id";
+
 pub struct ContactMessageRepository {
     db: D1Database,
 }
@@ -18,7 +90,7 @@ impl ContactMessageRepository {
         Arc::new(Self { db })
     }
 
-    fn serialize_data(data: &Option<HashMap<String, String>>) -> Result<String, RepositoryError> {
+    fn serialize_data(data: &Option<FormData>) -> Result<String, RepositoryError> {
         match data {
             Some(m) => serde_json::to_string(m).map_err(|e| {
                 RepositoryError::DatabaseError(format!("JSON serialization failed: {e}"))
@@ -26,20 +98,37 @@ impl ContactMessageRepository {
             None => Ok("null".to_string()),
         }
     }
+
+    fn deserialize_data(data: Option<String>) -> Result<Option<FormData>, RepositoryError> {
+        match data {
+            Some(s) => serde_json::from_str(&s).map_err(|e| {
+                RepositoryError::DatabaseError(format!("JSON deserialization failed: {e}"))
+            }),
+            None => Ok(None),
+        }
+    }
 }
 
 #[async_trait(?Send)]
 impl ContactMessageRepositoryInterface for ContactMessageRepository {
-    async fn save(&self, contact: &ContactMessage) -> Result<bool, RepositoryError> {
+    async fn save(
+        &self,
+        contact: &ContactMessage,
+        event: &OutboxEvent,
+        idempotency: Option<&IdempotencyRecord>,
+    ) -> Result<bool, RepositoryError> {
         let data_json = Self::serialize_data(&contact.data)?;
-        let created_at = Utc::now().timestamp() as f64;
+        let created_at = contact.created_at as f64;
+        let updated_at = contact.updated_at as f64;
 
         let statement = self.db.prepare(
-            "INSERT INTO contact_messages (id, category, email, name, message, data, created_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            "INSERT INTO contact_messages
+             (id, category, email, name, message, data, status, created_at, updated_at, fingerprint,
+              site_id, form_id, priority)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
         );
 
-        let result = statement
+        let statement = statement
             .bind(&[
                 contact.id.clone().into(),
                 contact.category.to_string().into(),
@@ -47,13 +136,250 @@ impl ContactMessageRepositoryInterface for ContactMessageRepository {
                 contact.name.clone().into(),
                 contact.message.clone().into(),
                 data_json.into(),
+                contact.status.to_string().into(),
                 created_at.into(),
+                updated_at.into(),
+                contact.fingerprint.clone().into(),
+                match &contact.site_id {
+                    Some(site_id) => site_id.clone().into(),
+                    None => worker::wasm_bindgen::JsValue::NULL,
+                },
+                match &contact.form_id {
+                    Some(form_id) => form_id.clone().into(),
+                    None => worker::wasm_bindgen::JsValue::NULL,
+                },
+                contact.priority.to_string().into(),
+            ])
+            .map_err(|e| {
+                RepositoryError::DatabaseError(format!("Failed to bind parameters: {e}"))
+            })?;
+
+        let mut statements = vec![
+            statement,
+            OutboxRepository::insert_statement(&self.db, event)?,
+        ];
+        if let Some(record) = idempotency {
+            statements.extend(IdempotencyRepository::insert_statements(&self.db, record)?);
+        }
+
+        // D1 runs a batch as a single transaction, so the event and idempotency
+        // record are stored if and only if the message is.
+        let results =
+            self.db.batch(statements).await.map_err(|e| {
+                RepositoryError::DatabaseError(format!("Failed to execute batch: {e}"))
+            })?;
+
+        Ok(results.iter().all(|r| r.success()))
+    }
+
+    async fn find_by_id(&self, id: &str) -> Result<ContactMessage, RepositoryError> {
+        let statement = self.db.prepare(format!(
+            "SELECT {SELECT_COLUMNS} FROM contact_messages WHERE id = ?1"
+        ));
+
+        let row: Option<ContactMessageRow> = statement
+            .bind(&[id.into()])
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to bind parameters: {e}")))?
+            .first(None)
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to execute query: {e}")))?;
+
+        match row {
+            Some(row) => row.try_into(),
+            None => Err(RepositoryError::NotFound(format!(
+                "Contact message {id} not found"
+            ))),
+        }
+    }
+
+    async fn find_recent_by_fingerprint(
+        &self,
+        fingerprint: &str,
+        site_id: Option<&str>,
+        since: i64,
+    ) -> Result<Option<ContactMessage>, RepositoryError> {
+        let statement = self.db.prepare(format!(
+            "SELECT {SELECT_COLUMNS} FROM contact_messages
+             WHERE fingerprint = ?1 AND site_id IS ?2 AND created_at >= ?3
+             ORDER BY created_at DESC LIMIT 1"
+        ));
+
+        let row: Option<ContactMessageRow> = statement
+            .bind(&[
+                fingerprint.into(),
+                match site_id {
+                    Some(site_id) => site_id.into(),
+                    None => worker::wasm_bindgen::JsValue::NULL,
+                },
+                (since as f64).into(),
+            ])
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to bind parameters: {e}")))?
+            .first(None)
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to execute query: {e}")))?;
+
+        row.map(ContactMessage::try_from).transpose()
+    }
+
+    async fn list(&self, limit: u32, offset: u32) -> Result<Vec<ContactMessage>, RepositoryError> {
+        let statement = self.db.prepare(format!(
+            "SELECT {SELECT_COLUMNS} FROM contact_messages
+             ORDER BY created_at DESC, id DESC LIMIT ?1 OFFSET ?2"
+        ));
+
+        let result = statement
+            .bind(&[limit.into(), offset.into()])
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to bind parameters: {e}")))?
+            .all()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to execute query: {e}")))?;
+
+        let rows: Vec<ContactMessageRow> = result
+            .results()
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to read rows: {e}")))?;
+
+        rows.into_iter().map(ContactMessage::try_from).collect()
+    }
+
+    async fn update_status(
+        &self,
+        contact: &ContactMessage,
+        previous_status: ContactMessageStatus,
+    ) -> Result<bool, RepositoryError> {
+        let statement = self.db.prepare(
+            "UPDATE contact_messages SET status = ?1, updated_at = ?2, resolved_at = ?3
+             WHERE id = ?4 AND status = ?5",
+        );
+
+        let resolved_at = match contact.resolved_at {
+            Some(t) => (t as f64).into(),
+            None => worker::wasm_bindgen::JsValue::NULL,
+        };
+
+        let result = statement
+            .bind(&[
+                contact.status.to_string().into(),
+                (contact.updated_at as f64).into(),
+                resolved_at,
+                contact.id.clone().into(),
+                previous_status.to_string().into(),
             ])
             .map_err(|e| RepositoryError::DatabaseError(format!("Failed to bind parameters: {e}")))?
             .run()
             .await
             .map_err(|e| RepositoryError::DatabaseError(format!("Failed to execute query: {e}")))?;
 
+        let changes = result
+            .meta()
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to read result: {e}")))?
+            .and_then(|meta| meta.changes)
+            .unwrap_or(0);
+
+        Ok(changes > 0)
+    }
+
+    async fn update_spam_score(
+        &self,
+        contact: &ContactMessage,
+        previous_status: ContactMessageStatus,
+    ) -> Result<bool, RepositoryError> {
+        let statement = self.db.prepare(
+            "UPDATE contact_messages SET spam_score = ?1, status = ?2, updated_at = ?3
+             WHERE id = ?4 AND status = ?5",
+        );
+
+        let spam_score = match contact.spam_score {
+            Some(score) => score.into(),
+            None => worker::wasm_bindgen::JsValue::NULL,
+        };
+
+        let result = statement
+            .bind(&[
+                spam_score,
+                contact.status.to_string().into(),
+                (contact.updated_at as f64).into(),
+                contact.id.clone().into(),
+                previous_status.to_string().into(),
+            ])
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to bind parameters: {e}")))?
+            .run()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to execute query: {e}")))?;
+
+        let changes = result
+            .meta()
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to read result: {e}")))?
+            .and_then(|meta| meta.changes)
+            .unwrap_or(0);
+
+        Ok(changes > 0)
+    }
+
+    async fn count_previous(&self, contact: &ContactMessage) -> Result<u32, RepositoryError> {
+        #[derive(Deserialize)]
+        struct CountRow {
+            count: f64,
+        }
+
+        // UUIDv7 ids sort by creation time, which breaks ties within a second.
+        let statement = self.db.prepare(
+            "SELECT COUNT(*) AS count FROM contact_messages
+             WHERE lower(email) = lower(?1) AND created_at <= ?2 AND id < ?3",
+        );
+
+        let row: Option<CountRow> = statement
+            .bind(&[
+                contact.email.clone().into(),
+                (contact.created_at as f64).into(),
+                contact.id.clone().into(),
+            ])
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to bind parameters: {e}")))?
+            .first(None)
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to execute query: {e}")))?;
+
+        Ok(row.map(|row| row.count as u32).unwrap_or(0))
+    }
+
+    async fn update_enrichment(&self, contact: &ContactMessage) -> Result<bool, RepositoryError> {
+        let statement = self
+            .db
+            .prepare("UPDATE contact_messages SET previous_messages = ?1 WHERE id = ?2");
+
+        let previous_messages = match contact.previous_messages {
+            Some(count) => count.into(),
+            None => worker::wasm_bindgen::JsValue::NULL,
+        };
+
+        let result = statement
+            .bind(&[previous_messages, contact.id.clone().into()])
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to bind parameters: {e}")))?
+            .run()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to execute query: {e}")))?;
+
         Ok(result.success())
     }
+
+    async fn mark_auto_replied(&self, id: &str, now: i64) -> Result<bool, RepositoryError> {
+        let result = self
+            .db
+            .prepare(
+                "UPDATE contact_messages SET auto_replied_at = ?1
+                 WHERE id = ?2 AND auto_replied_at IS NULL",
+            )
+            .bind(&[(now as f64).into(), id.into()])
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to bind parameters: {e}")))?
+            .run()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to execute query: {e}")))?;
+
+        let changes = result
+            .meta()
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to read result: {e}")))?
+            .and_then(|meta| meta.changes)
+            .unwrap_or(0);
+
+        Ok(changes > 0)
+    }
 }
@@ -0,0 +1,116 @@
+use std::sync::Arc;
+
+use crate::application::{
+    exceptions::AppError,
+    rate_limiter::{RateLimit, RateLimitDecision, RateLimiterTrait, TokenBucket},
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use worker::{
+    Env, Method, ObjectNamespace, Request, RequestInit, Response, State, console_error,
+    durable_object,
+};
+
+const BUCKET_KEY: &str = "bucket";
+
+#[derive(Deserialize, Serialize)]
+struct CheckRequest {
+    limit: RateLimit,
+}
+
+/// Holds the token bucket for a single rate-limit key. Durable Objects process
+/// one request at a time, so the read-modify-write of the bucket is atomic.
+#[durable_object(alarm)]
+pub struct RateLimiterObject {
+    state: State,
+}
+
+impl DurableObject for RateLimiterObject {
+    fn new(state: State, _env: Env) -> Self {
+        Self { state }
+    }
+
+    async fn fetch(&self, mut req: Request) -> worker::Result<Response> {
+        let CheckRequest { limit } = req.json().await?;
+        let now_ms = Utc::now().timestamp_millis();
+        let storage = self.state.storage();
+
+        let mut bucket = match self.load_bucket().await? {
+            Some(bucket) => bucket,
+            None => TokenBucket::full(&limit, now_ms),
+        };
+        let decision = bucket.try_acquire(&limit, now_ms);
+
+        storage.put(BUCKET_KEY, &bucket).await?;
+        // A bucket left alone for a full window is full again, so it can be dropped.
+        storage
+            .set_alarm(now_ms + limit.window_seconds as i64 * 1000)
+            .await?;
+
+        Response::from_json(&decision)
+    }
+
+    async fn alarm(&self) -> worker::Result<Response> {
+        self.state.storage().delete_all().await?;
+        Response::empty()
+    }
+}
+
+impl RateLimiterObject {
+    /// `Storage::get` reports a missing key as an error, so the bucket is read
+    /// through `get_multiple` to keep failed reads apart from a fresh key.
+    async fn load_bucket(&self) -> worker::Result<Option<TokenBucket>> {
+        let stored = self.state.storage().get_multiple(vec![BUCKET_KEY]).await?;
+        let value = stored.get(&BUCKET_KEY.into());
+        if value.is_undefined() {
+            return Ok(None);
+        }
+
+        serde_wasm_bindgen::from_value(value)
+            .map(Some)
+            .map_err(|e| worker::Error::RustError(format!("Invalid stored bucket: {e}")))
+    }
+}
+
+/// Checks rate limits against one `RateLimiterObject` per key.
+pub struct DurableObjectRateLimiter {
+    namespace: ObjectNamespace,
+}
+
+impl DurableObjectRateLimiter {
+    pub fn create(namespace: ObjectNamespace) -> Arc<dyn RateLimiterTrait> {
+        Arc::new(Self { namespace })
+    }
+}
+
+#[async_trait(?Send)]
+impl RateLimiterTrait for DurableObjectRateLimiter {
+    async fn check(&self, key: &str, limit: &RateLimit) -> Result<RateLimitDecision, AppError> {
+        let stub = self.namespace.get_by_name(key).map_err(|e| {
+            console_error!("Failed to get rate limiter for {}: {:?}", key, e);
+            AppError::InternalError
+        })?;
+
+        let body = serde_json::to_string(&CheckRequest { limit: *limit })
+            .map_err(|_| AppError::InternalError)?;
+        let mut init = RequestInit::new();
+        init.with_method(Method::Post);
+        init.with_body(Some(body.into()));
+
+        let request = Request::new_with_init("https://rate-limiter/check", &init).map_err(|e| {
+            console_error!("Failed to build rate limiter request: {:?}", e);
+            AppError::InternalError
+        })?;
+
+        let mut response = stub.fetch_with_request(request).await.map_err(|e| {
+            console_error!("Rate limiter request failed for {}: {:?}", key, e);
+            AppError::InternalError
+        })?;
+
+        response.json().await.map_err(|e| {
+            console_error!("Invalid rate limiter response for {}: {:?}", key, e);
+            AppError::InternalError
+        })
+    }
+}
@@ -0,0 +1,252 @@
+use std::sync::Arc;
+
+use crate::{
+    application::{
+        email_provider::{EmailProviderTrait, OutgoingEmail},
+        exceptions::AppError,
+        notification_service::{NotificationServiceTrait, RecipientRouting},
+    },
+    domain::{entity::ContactMessage, repository::SiteRepository},
+    log_error, log_info,
+};
+use async_trait::async_trait;
+
+/// Emails new messages to the recipients routed for their category, with
+/// the submitter as the reply-to address.
+pub struct EmailNotificationService {
+    email_provider: Arc<dyn EmailProviderTrait>,
+    routing: RecipientRouting,
+    sites: Arc<dyn SiteRepository>,
+}
+
+impl EmailNotificationService {
+    pub fn create(
+        email_provider: Arc<dyn EmailProviderTrait>,
+        routing: RecipientRouting,
+        sites: Arc<dyn SiteRepository>,
+    ) -> Arc<dyn NotificationServiceTrait> {
+        Arc::new(Self {
+            email_provider,
+            routing,
+            sites,
+        })
+    }
+
+    /// The routing of the message's site when it has its own recipients.
+    async fn site_routing(
+        &self,
+        message: &ContactMessage,
+    ) -> Result<Option<RecipientRouting>, AppError> {
+        let Some(site_id) = &message.site_id else {
+            return Ok(None);
+        };
+        let site = self.sites.find_by_id(site_id).await.map_err(|e| {
+            log_error!("Failed to load site {}: {}", site_id, e);
+            AppError::DatabaseError(e.to_string())
+        })?;
+
+        match site {
+            Some(site) if !site.notification_recipients.trim().is_empty() => {
+                RecipientRouting::parse(&site.notification_recipients)
+                    .map(Some)
+                    .map_err(|e| {
+                        log_error!(
+                            "Invalid notification recipients for site {}: {}",
+                            site_id,
+                            e
+                        );
+                        AppError::InternalError
+                    })
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn subject(message: &ContactMessage) -> String {
+        format!("[Contact Us] {} from {}", message.category, message.name)
+    }
+
+    fn body(message: &ContactMessage) -> String {
+        let mut body = format!(
+            "A new {} message was submitted.\n\nName: {}\nEmail: {}\nReference: {}\n\n{}\n",
+            message.category, message.name, message.email, message.id, message.message
+        );
+
+        if let Some(data) = &message.data {
+            let mut entries: Vec<_> = data.iter().collect();
+            entries.sort_by_key(|(key, _)| *key);
+
+            body.push_str("\nAdditional data:\n");
+            for (key, value) in entries {
+                match value.as_str() {
+                    Some(text) => body.push_str(&format!("  {key}: {text}\n")),
+                    None => body.push_str(&format!("  {key}: {value}\n")),
+                }
+            }
+        }
+
+        body
+    }
+}
+
+#[async_trait(?Send)]
+impl NotificationServiceTrait for EmailNotificationService {
+    async fn notify(&self, message: &ContactMessage) -> Result<(), AppError> {
+        let site_routing = self.site_routing(message).await?;
+        let recipients = site_routing
+            .as_ref()
+            .unwrap_or(&self.routing)
+            .recipients_for(&message.category.to_string());
+        if recipients.is_empty() {
+            log_info!("No email recipients configured for {}", message.category);
+            return Ok(());
+        }
+
+        self.email_provider
+            .send(&OutgoingEmail {
+                to: recipients.to_vec(),
+                reply_to: Some(message.email.clone()),
+                subject: Self::subject(message),
+                text: Self::body(message),
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::test_support::{MockSiteRepository, test_site};
+    use crate::domain::form_schema::{FormData, FormSchema};
+    use crate::domain::{entity::Site, enums::ContactMessageCategory};
+    use crate::infrastructure::{
+        http_client::test_support::ReqwestHttpClient, http_email_provider::HttpEmailProvider,
+    };
+    use serde_json::json;
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{body_partial_json, header, method, path},
+    };
+
+    fn message(category: ContactMessageCategory) -> ContactMessage {
+        ContactMessage::create(
+            category,
+            "user@example.com".to_string(),
+            "Jane Smith".to_string(),
+            "Something is broken".to_string(),
+            Some(FormData::from_iter([(
+                "page".to_string(),
+                "/settings".into(),
+            )])),
+            &FormSchema::unstructured(),
+        )
+        .unwrap()
+    }
+
+    fn service(server: &MockServer) -> Arc<dyn NotificationServiceTrait> {
+        EmailNotificationService::create(
+            HttpEmailProvider::create(
+                ReqwestHttpClient::create(),
+                format!("{}/emails", server.uri()),
+                "test-api-key".to_string(),
+                "contact@example.com".to_string(),
+            ),
+            RecipientRouting::parse("ERROR=oncall@example.com;IDEA=").unwrap(),
+            Arc::new(MockSiteRepository::with_sites(vec![Site {
+                notification_recipients: "*=shop-team@example.com".to_string(),
+                ..test_site("shop", vec![])
+            }])),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_notify_routes_to_category_recipients() {
+        let server = MockServer::start().await;
+        let message = message(ContactMessageCategory::ERROR);
+
+        Mock::given(method("POST"))
+            .and(path("/emails"))
+            .and(header("Authorization", "Bearer test-api-key"))
+            .and(body_partial_json(json!({
+                "from": "contact@example.com",
+                "to": ["oncall@example.com"],
+                "reply_to": "user@example.com",
+                "subject": "[Contact Us] ERROR from Jane Smith",
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": "email-id" })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let result = service(&server).notify(&message).await;
+
+        assert!(result.is_ok());
+        let requests = server.received_requests().await.unwrap();
+        let body: serde_json::Value = requests[0].body_json().unwrap();
+        let text = body["text"].as_str().unwrap();
+        assert!(text.contains("Something is broken"));
+        assert!(text.contains(&message.id));
+        assert!(text.contains("page: /settings"));
+    }
+
+    #[tokio::test]
+    async fn test_notify_skips_categories_without_recipients() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let service = service(&server);
+        assert!(
+            service
+                .notify(&message(ContactMessageCategory::IDEA))
+                .await
+                .is_ok()
+        );
+        assert!(
+            service
+                .notify(&message(ContactMessageCategory::OTHER))
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_notify_routes_to_site_recipients() {
+        let server = MockServer::start().await;
+        let mut message = message(ContactMessageCategory::ERROR);
+        message.site_id = Some("shop".to_string());
+
+        Mock::given(method("POST"))
+            .and(path("/emails"))
+            .and(body_partial_json(
+                json!({ "to": ["shop-team@example.com"] }),
+            ))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        assert!(service(&server).notify(&message).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_notify_provider_error() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/emails"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+
+        let result = service(&server)
+            .notify(&message(ContactMessageCategory::ERROR))
+            .await;
+
+        assert!(matches!(result, Err(AppError::InternalError)));
+    }
+}
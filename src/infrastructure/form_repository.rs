@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use crate::domain::{
+    entity::Form, exceptions::RepositoryError, form_schema::FormSchema,
+    repository::FormRepository as FormRepositoryInterface,
+};
+use async_trait::async_trait;
+use serde::Deserialize;
+use worker::D1Database;
+
+#[derive(Deserialize)]
+struct FormRow {
+    id: String,
+    site_id: Option<String>,
+    name: String,
+    schema: String,
+    created_at: f64,
+    updated_at: f64,
+}
+
+impl TryFrom<FormRow> for Form {
+    type Error = RepositoryError;
+
+    fn try_from(row: FormRow) -> Result<Self, Self::Error> {
+        let schema: FormSchema = serde_json::from_str(&row.schema).map_err(|e| {
+            RepositoryError::DatabaseError(format!("Invalid schema for form {}: {e}", row.id))
+        })?;
+        schema.check().map_err(|e| {
+            RepositoryError::DatabaseError(format!("Invalid schema for form {}: {e}", row.id))
+        })?;
+
+        Ok(Form {
+            id: row.id,
+            site_id: row.site_id,
+            name: row.name,
+            schema,
+            created_at: row.created_at as i64,
+            updated_at: row.updated_at as i64,
+        })
+    }
+}
+
+pub struct FormRepository {
+    db: D1Database,
+}
+
+impl FormRepository {
+    pub fn create(db: D1Database) -> Arc<dyn FormRepositoryInterface> {
+        Arc::new(Self { db })
+    }
+}
+
+#[async_trait(?Send)]
+impl FormRepositoryInterface for FormRepository {
+    async fn find_by_id(&self, id: &str) -> Result<Option<Form>, RepositoryError> {
+        let row: Option<FormRow> = self
+            .db
+            .prepare(
+                "SELECT id, site_id, name, schema, created_at, updated_at FROM forms WHERE id = ?1",
+            )
+            .bind(&[id.into()])
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to bind parameters: {e}")))?
+            .first(None)
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to execute query: {e}")))?;
+
+        row.map(Form::try_from).transpose()
+    }
+}
@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use crate::domain::{
+    exceptions::RepositoryError, repository::FormTokenRepository as FormTokenRepositoryInterface,
+};
+use async_trait::async_trait;
+use worker::D1Database;
+
+pub struct FormTokenRepository {
+    db: D1Database,
+}
+
+impl FormTokenRepository {
+    pub fn create(db: D1Database) -> Arc<dyn FormTokenRepositoryInterface> {
+        Arc::new(Self { db })
+    }
+}
+
+#[async_trait(?Send)]
+impl FormTokenRepositoryInterface for FormTokenRepository {
+    async fn redeem(
+        &self,
+        nonce: &str,
+        expires_at: i64,
+        now: i64,
+    ) -> Result<bool, RepositoryError> {
+        let delete_expired = self
+            .db
+            .prepare("DELETE FROM redeemed_form_tokens WHERE expires_at <= ?1")
+            .bind(&[(now as f64).into()])
+            .map_err(|e| {
+                RepositoryError::DatabaseError(format!("Failed to bind parameters: {e}"))
+            })?;
+
+        let insert = self
+            .db
+            .prepare(
+                "INSERT INTO redeemed_form_tokens (nonce, expires_at) VALUES (?1, ?2)
+                 ON CONFLICT (nonce) DO NOTHING",
+            )
+            .bind(&[nonce.into(), (expires_at as f64).into()])
+            .map_err(|e| {
+                RepositoryError::DatabaseError(format!("Failed to bind parameters: {e}"))
+            })?;
+
+        let results = self
+            .db
+            .batch(vec![delete_expired, insert])
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to execute batch: {e}")))?;
+
+        let changes = results
+            .last()
+            .map(|result| result.meta())
+            .transpose()
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to read result: {e}")))?
+            .flatten()
+            .and_then(|meta| meta.changes)
+            .unwrap_or(0);
+
+        Ok(changes > 0)
+    }
+}
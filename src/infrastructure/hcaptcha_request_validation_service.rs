@@ -0,0 +1,249 @@
+use std::sync::Arc;
+
+use crate::{
+    application::request_validation_service::{
+        RequestValidationServiceTrait, VerificationError, VerificationRequest,
+    },
+    infrastructure::http_client::{HttpClient, HttpRequest},
+    log_error, log_info,
+};
+use async_trait::async_trait;
+use serde::Deserialize;
+
+pub const HCAPTCHA_SITEVERIFY_URL: &str = "https://api.hcaptcha.com/siteverify";
+
+#[derive(Deserialize, Debug)]
+struct HCaptchaResponse {
+    success: bool,
+    hostname: Option<String>,
+    #[serde(rename = "error-codes")]
+    error_codes: Option<Vec<String>>,
+}
+
+/// Verifies hCaptcha response tokens with the siteverify API.
+pub struct HCaptchaRequestValidationService {
+    http_client: Arc<dyn HttpClient>,
+    siteverify_url: String,
+    secret_key: String,
+    site_key: Option<String>,
+}
+
+impl HCaptchaRequestValidationService {
+    /// When `site_key` is set, hCaptcha also checks the token was issued for it.
+    pub fn create(
+        http_client: Arc<dyn HttpClient>,
+        siteverify_url: String,
+        secret_key: String,
+        site_key: Option<String>,
+    ) -> Arc<dyn RequestValidationServiceTrait> {
+        Arc::new(Self {
+            http_client,
+            siteverify_url,
+            secret_key,
+            site_key,
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl RequestValidationServiceTrait for HCaptchaRequestValidationService {
+    async fn verify(&self, request: &VerificationRequest) -> Result<(), VerificationError> {
+        let Some(token) = &request.token else {
+            return Err(VerificationError::TokenInvalid(
+                "Missing hCaptcha token".to_string(),
+            ));
+        };
+
+        let mut fields = vec![
+            ("secret", self.secret_key.as_str()),
+            ("response", token.as_str()),
+            ("remoteip", request.remote_ip.as_str()),
+        ];
+        if let Some(site_key) = &self.site_key {
+            fields.push(("sitekey", site_key.as_str()));
+        }
+
+        let response = self
+            .http_client
+            .send(HttpRequest::post_form(&self.siteverify_url, &fields))
+            .await
+            .map_err(|e| {
+                log_error!("hCaptcha request failed: {}", e);
+                VerificationError::ProviderUnreachable(e.to_string())
+            })?;
+        if !response.is_success() {
+            log_error!("hCaptcha siteverify returned status {}", response.status);
+            return Err(VerificationError::ProviderUnreachable(format!(
+                "Siteverify returned status {}",
+                response.status
+            )));
+        }
+
+        let hcaptcha_response: HCaptchaResponse = response.json().map_err(|e| {
+            log_error!("Failed to parse hCaptcha response: {}", e);
+            VerificationError::ProviderMisconfigured(format!("Unexpected siteverify response: {e}"))
+        })?;
+
+        if hcaptcha_response.success {
+            return Ok(());
+        }
+
+        let error_codes = hcaptcha_response.error_codes.unwrap_or_default();
+        log_info!(
+            "hCaptcha validation failed for host {:?}: {:?}",
+            hcaptcha_response.hostname,
+            error_codes
+        );
+        if error_codes.iter().any(|code| {
+            matches!(
+                code.as_str(),
+                "missing-input-secret" | "invalid-input-secret" | "sitekey-secret-mismatch"
+            )
+        }) {
+            log_error!("hCaptcha is misconfigured: {:?}", error_codes);
+            return Err(VerificationError::ProviderMisconfigured(format!(
+                "hCaptcha rejected the configuration: {}",
+                error_codes.join(", ")
+            )));
+        }
+        if error_codes.iter().any(|code| {
+            matches!(
+                code.as_str(),
+                "expired-input-response" | "already-seen-response"
+            )
+        }) {
+            return Err(VerificationError::TokenExpired(
+                "hCaptcha token has expired or was already redeemed".to_string(),
+            ));
+        }
+
+        Err(VerificationError::TokenInvalid(
+            "hCaptcha validation failed".to_string(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::http_client::test_support::ReqwestHttpClient;
+    use serde_json::json;
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{body_string_contains, header, method, path},
+    };
+
+    fn service(
+        server: &MockServer,
+        site_key: Option<&str>,
+    ) -> Arc<dyn RequestValidationServiceTrait> {
+        HCaptchaRequestValidationService::create(
+            ReqwestHttpClient::create(),
+            format!("{}/siteverify", server.uri()),
+            "test-secret".to_string(),
+            site_key.map(str::to_string),
+        )
+    }
+
+    fn request(token: Option<&str>) -> VerificationRequest {
+        VerificationRequest {
+            token: token.map(str::to_string),
+            remote_ip: "1.2.3.4".to_string(),
+            ..Default::default()
+        }
+    }
+
+    async fn respond_with(server: &MockServer, body: serde_json::Value) {
+        Mock::given(method("POST"))
+            .and(path("/siteverify"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_valid_token_is_accepted() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/siteverify"))
+            .and(header("Content-Type", "application/x-www-form-urlencoded"))
+            .and(body_string_contains("secret=test-secret"))
+            .and(body_string_contains("response=token-1"))
+            .and(body_string_contains("remoteip=1.2.3.4"))
+            .and(body_string_contains("sitekey=site-1"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({ "success": true, "hostname": "example.com" })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let result = service(&server, Some("site-1"))
+            .verify(&request(Some("token-1")))
+            .await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_token_is_unauthorised() {
+        let server = MockServer::start().await;
+        respond_with(
+            &server,
+            json!({ "success": false, "error-codes": ["invalid-or-already-seen-response"] }),
+        )
+        .await;
+
+        let result = service(&server, None).verify(&request(Some("used"))).await;
+
+        assert!(matches!(result, Err(VerificationError::TokenInvalid(_))));
+    }
+
+    #[tokio::test]
+    async fn test_misconfigured_secret_is_misconfiguration() {
+        let server = MockServer::start().await;
+        respond_with(
+            &server,
+            json!({ "success": false, "error-codes": ["sitekey-secret-mismatch"] }),
+        )
+        .await;
+
+        let result = service(&server, Some("site-1"))
+            .verify(&request(Some("token-1")))
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(VerificationError::ProviderMisconfigured(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_missing_token_is_not_sent() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let result = service(&server, None).verify(&request(None)).await;
+
+        assert!(matches!(result, Err(VerificationError::TokenInvalid(_))));
+    }
+
+    #[tokio::test]
+    async fn test_redeemed_token_is_expired() {
+        let server = MockServer::start().await;
+        respond_with(
+            &server,
+            json!({ "success": false, "error-codes": ["already-seen-response"] }),
+        )
+        .await;
+
+        let result = service(&server, None).verify(&request(Some("used"))).await;
+
+        assert!(matches!(result, Err(VerificationError::TokenExpired(_))));
+    }
+}
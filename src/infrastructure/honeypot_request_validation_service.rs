@@ -0,0 +1,292 @@
+use std::sync::Arc;
+
+use crate::{
+    application::request_validation_service::{
+        RequestValidationServiceTrait, VerificationError, VerificationRequest,
+    },
+    domain::repository::FormTokenRepository as FormTokenRepositoryInterface,
+};
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use uuid::Uuid;
+
+/// A form token whose signature and age have been checked.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifiedFormToken {
+    pub nonce: String,
+    pub expires_at: i64,
+}
+
+/// Issues and checks form tokens of the form `{issued_at}.{nonce}.{signature}`,
+/// where the signature is `hex(HMAC-SHA256(secret, "form-token.{issued_at}.{nonce}"))`.
+///
+/// A token is accepted once it is at least `min_age_seconds` old, since people
+/// take a few seconds to fill in a form, and until it is `max_age_seconds` old.
+/// The random nonce lets each token be redeemed only once.
+#[derive(Clone)]
+pub struct FormTokenSigner {
+    secret: String,
+    pub min_age_seconds: i64,
+    pub max_age_seconds: i64,
+}
+
+impl FormTokenSigner {
+    pub fn new(secret: String, min_age_seconds: i64, max_age_seconds: i64) -> Self {
+        Self {
+            secret,
+            min_age_seconds,
+            max_age_seconds,
+        }
+    }
+
+    pub fn issue(&self, now: i64) -> String {
+        let nonce = Uuid::now_v7().simple().to_string();
+        let signature = hex::encode(self.mac(now, &nonce).finalize().into_bytes());
+        format!("{now}.{nonce}.{signature}")
+    }
+
+    pub fn verify(&self, token: &str, now: i64) -> Result<VerifiedFormToken, VerificationError> {
+        let malformed = || VerificationError::TokenInvalid("Malformed form token".to_string());
+        let mut parts = token.splitn(3, '.');
+        let (Some(issued_at), Some(nonce), Some(signature)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(malformed());
+        };
+        let issued_at: i64 = issued_at.parse().map_err(|_| malformed())?;
+        let signature = hex::decode(signature).map_err(|_| malformed())?;
+
+        self.mac(issued_at, nonce)
+            .verify_slice(&signature)
+            .map_err(|_| {
+                VerificationError::TokenInvalid("Invalid form token signature".to_string())
+            })?;
+
+        let age = now - issued_at;
+        if age < self.min_age_seconds {
+            return Err(VerificationError::TokenInvalid(
+                "Form was submitted too quickly".to_string(),
+            ));
+        }
+        if age > self.max_age_seconds {
+            return Err(VerificationError::TokenExpired(
+                "Form token has expired".to_string(),
+            ));
+        }
+
+        Ok(VerifiedFormToken {
+            nonce: nonce.to_string(),
+            expires_at: issued_at + self.max_age_seconds,
+        })
+    }
+
+    fn mac(&self, issued_at: i64, nonce: &str) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.secret.as_bytes())
+            .expect("HMAC accepts keys of any size");
+        mac.update(format!("form-token.{issued_at}.{nonce}").as_bytes());
+        mac
+    }
+}
+
+/// Validates submissions without a challenge widget: the honeypot field must
+/// be empty and the form token must be validly signed, neither too fresh nor
+/// too old, and not redeemed before.
+pub struct HoneypotRequestValidationService {
+    signer: FormTokenSigner,
+    repo: Arc<dyn FormTokenRepositoryInterface>,
+}
+
+impl HoneypotRequestValidationService {
+    pub fn create(
+        signer: FormTokenSigner,
+        repo: Arc<dyn FormTokenRepositoryInterface>,
+    ) -> Arc<dyn RequestValidationServiceTrait> {
+        Arc::new(Self { signer, repo })
+    }
+
+    fn check(
+        &self,
+        request: &VerificationRequest,
+        now: i64,
+    ) -> Result<VerifiedFormToken, VerificationError> {
+        if request
+            .honeypot
+            .as_ref()
+            .is_some_and(|value| !value.trim().is_empty())
+        {
+            return Err(VerificationError::TokenInvalid(
+                "Honeypot field was filled in".to_string(),
+            ));
+        }
+
+        let form_token = request
+            .form_token
+            .as_ref()
+            .ok_or_else(|| VerificationError::TokenInvalid("Missing form token".to_string()))?;
+        self.signer.verify(form_token, now)
+    }
+}
+
+#[async_trait(?Send)]
+impl RequestValidationServiceTrait for HoneypotRequestValidationService {
+    async fn verify(&self, request: &VerificationRequest) -> Result<(), VerificationError> {
+        let now = chrono::Utc::now().timestamp();
+        let token = self.check(request, now)?;
+
+        let redeemed = self
+            .repo
+            .redeem(&token.nonce, token.expires_at, now)
+            .await
+            .map_err(|e| VerificationError::Internal(e.to_string()))?;
+        if !redeemed {
+            return Err(VerificationError::TokenExpired(
+                "Form token was already used".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::{
+        request_validation_service::{FailurePolicy, FailurePolicyRequestValidationService},
+        test_support::MockFormTokenRepository,
+    };
+
+    const ISSUED_AT: i64 = 1_700_000_000;
+
+    fn signer() -> FormTokenSigner {
+        FormTokenSigner::new("secret".to_string(), 3, 3600)
+    }
+
+    fn service(repo: MockFormTokenRepository) -> HoneypotRequestValidationService {
+        HoneypotRequestValidationService {
+            signer: signer(),
+            repo: Arc::new(repo),
+        }
+    }
+
+    fn request(form_token: Option<String>, honeypot: Option<&str>) -> VerificationRequest {
+        VerificationRequest {
+            form_token,
+            honeypot: honeypot.map(str::to_string),
+            remote_ip: "1.1.1.1".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_token_is_accepted_within_its_window() {
+        let token = signer().issue(ISSUED_AT);
+
+        assert!(signer().verify(&token, ISSUED_AT + 3).is_ok());
+        assert!(signer().verify(&token, ISSUED_AT + 3600).is_ok());
+    }
+
+    #[test]
+    fn test_token_used_too_soon_is_rejected() {
+        let token = signer().issue(ISSUED_AT);
+
+        assert!(matches!(
+            signer().verify(&token, ISSUED_AT + 2),
+            Err(VerificationError::TokenInvalid(msg)) if msg.contains("too quickly")
+        ));
+    }
+
+    #[test]
+    fn test_expired_token_is_rejected() {
+        let token = signer().issue(ISSUED_AT);
+
+        assert!(matches!(
+            signer().verify(&token, ISSUED_AT + 3601),
+            Err(VerificationError::TokenExpired(_))
+        ));
+    }
+
+    #[test]
+    fn test_tampered_or_foreign_tokens_are_rejected() {
+        let token = signer().issue(ISSUED_AT);
+        let (_, rest) = token.split_once('.').unwrap();
+        let backdated = format!("{}.{rest}", ISSUED_AT - 60);
+        let (nonce, signature) = rest.split_once('.').unwrap();
+        let renonced = format!("{ISSUED_AT}.{nonce}0.{signature}");
+        let foreign = FormTokenSigner::new("other".to_string(), 3, 3600).issue(ISSUED_AT);
+
+        for token in [
+            backdated,
+            renonced,
+            foreign,
+            "garbage".to_string(),
+            "1.zz".to_string(),
+        ] {
+            assert!(
+                signer().verify(&token, ISSUED_AT + 10).is_err(),
+                "{token} was accepted"
+            );
+        }
+    }
+
+    #[test]
+    fn test_filled_honeypot_is_rejected() {
+        let service = service(MockFormTokenRepository::default());
+        let token = Some(signer().issue(ISSUED_AT));
+
+        assert!(
+            service
+                .check(&request(token.clone(), Some("")), ISSUED_AT + 10)
+                .is_ok()
+        );
+        assert!(
+            service
+                .check(&request(token, Some("http://spam.example")), ISSUED_AT + 10)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_missing_form_token_is_rejected() {
+        let service = service(MockFormTokenRepository::default());
+
+        assert!(matches!(
+            service.check(&request(None, None), ISSUED_AT),
+            Err(VerificationError::TokenInvalid(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_token_can_only_be_redeemed_once() {
+        let service = service(MockFormTokenRepository::default());
+        let now = chrono::Utc::now().timestamp();
+        let first = request(Some(signer().issue(now - 10)), None);
+        let second = request(Some(signer().issue(now - 10)), None);
+
+        assert!(service.verify(&first).await.is_ok());
+        assert!(matches!(
+            service.verify(&first).await,
+            Err(VerificationError::TokenExpired(msg)) if msg.contains("already used")
+        ));
+        assert!(service.verify(&second).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_unavailable_nonce_store_is_rejected_even_when_failing_open() {
+        let service = FailurePolicyRequestValidationService::create(
+            Arc::new(service(MockFormTokenRepository {
+                should_fail: true,
+                ..Default::default()
+            })),
+            FailurePolicy::Open,
+        );
+        let now = chrono::Utc::now().timestamp();
+
+        assert!(matches!(
+            service
+                .verify(&request(Some(signer().issue(now - 10)), None))
+                .await,
+            Err(VerificationError::Internal(_))
+        ));
+    }
+}
@@ -0,0 +1,219 @@
+use std::{pin::pin, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use futures_util::future::{Either, select};
+use serde::de::DeserializeOwned;
+use worker::{AbortController, Delay, Fetch, Headers, Method, Request, RequestInit};
+
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    pub method: Method,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+    /// Abandons the request if no complete response arrived in time.
+    pub timeout: Option<Duration>,
+}
+
+impl HttpRequest {
+    pub fn get(url: impl Into<String>) -> Self {
+        Self {
+            method: Method::Get,
+            url: url.into(),
+            headers: vec![],
+            body: None,
+            timeout: None,
+        }
+    }
+
+    pub fn post_json(url: impl Into<String>, body: &serde_json::Value) -> Self {
+        Self {
+            method: Method::Post,
+            url: url.into(),
+            headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+            body: Some(body.to_string()),
+            timeout: None,
+        }
+    }
+
+    pub fn post_form(url: impl Into<String>, fields: &[(&str, &str)]) -> Self {
+        Self {
+            method: Method::Post,
+            url: url.into(),
+            headers: vec![(
+                "Content-Type".to_string(),
+                "application/x-www-form-urlencoded".to_string(),
+            )],
+            body: Some(
+                serde_urlencoded::to_string(fields).expect("string pairs are form-encodable"),
+            ),
+            timeout: None,
+        }
+    }
+
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    pub fn is_server_error(&self) -> bool {
+        self.status >= 500
+    }
+
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, HttpError> {
+        serde_json::from_str(&self.body).map_err(|e| HttpError::InvalidResponse(e.to_string()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HttpError {
+    InvalidRequest(String),
+    Network(String),
+    Timeout(Duration),
+    InvalidResponse(String),
+}
+
+impl std::fmt::Display for HttpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HttpError::InvalidRequest(msg) => write!(f, "Invalid request: {msg}"),
+            HttpError::Network(msg) => write!(f, "Network error: {msg}"),
+            HttpError::Timeout(after) => write!(f, "Timed out after {}ms", after.as_millis()),
+            HttpError::InvalidResponse(msg) => write!(f, "Invalid response: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for HttpError {}
+
+/// Outbound HTTP used by infrastructure services, so they can be exercised
+/// against a mock server outside of the Workers runtime.
+#[async_trait(?Send)]
+pub trait HttpClient: Send + Sync {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, HttpError>;
+}
+
+pub struct WorkerHttpClient;
+
+impl WorkerHttpClient {
+    pub fn create() -> Arc<dyn HttpClient> {
+        Arc::new(Self)
+    }
+}
+
+#[async_trait(?Send)]
+impl HttpClient for WorkerHttpClient {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, HttpError> {
+        let headers = Headers::new();
+        for (name, value) in &request.headers {
+            headers
+                .set(name, value)
+                .map_err(|e| HttpError::InvalidRequest(e.to_string()))?;
+        }
+
+        let mut init = RequestInit::new();
+        init.with_method(request.method);
+        init.with_headers(headers);
+        init.with_body(request.body.map(Into::into));
+
+        let worker_request = Request::new_with_init(&request.url, &init)
+            .map_err(|e| HttpError::InvalidRequest(e.to_string()))?;
+
+        let controller = AbortController::default();
+        let signal = controller.signal();
+        let exchange = async {
+            let mut response = Fetch::Request(worker_request)
+                .send_with_signal(&signal)
+                .await
+                .map_err(|e| HttpError::Network(e.to_string()))?;
+
+            let body = response
+                .text()
+                .await
+                .map_err(|e| HttpError::InvalidResponse(e.to_string()))?;
+
+            Ok(HttpResponse {
+                status: response.status_code(),
+                body,
+            })
+        };
+
+        let Some(timeout) = request.timeout else {
+            return exchange.await;
+        };
+        match select(pin!(exchange), pin!(Delay::from(timeout))).await {
+            Either::Left((result, _)) => result,
+            Either::Right(_) => {
+                // Dropping the future alone would leave the fetch running.
+                controller.abort();
+                Err(HttpError::Timeout(timeout))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test_support {
+    use super::*;
+
+    /// A native `HttpClient` for talking to wiremock in unit tests.
+    pub struct ReqwestHttpClient {
+        client: reqwest::Client,
+    }
+
+    impl ReqwestHttpClient {
+        pub fn create() -> Arc<dyn HttpClient> {
+            Arc::new(Self {
+                client: reqwest::Client::new(),
+            })
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl HttpClient for ReqwestHttpClient {
+        async fn send(&self, request: HttpRequest) -> Result<HttpResponse, HttpError> {
+            let method = reqwest::Method::from_bytes(request.method.to_string().as_bytes())
+                .map_err(|e| HttpError::InvalidRequest(e.to_string()))?;
+
+            let mut builder = self.client.request(method, &request.url);
+            for (name, value) in request.headers {
+                builder = builder.header(name, value);
+            }
+            if let Some(body) = request.body {
+                builder = builder.body(body);
+            }
+            if let Some(timeout) = request.timeout {
+                builder = builder.timeout(timeout);
+            }
+
+            let response = builder.send().await.map_err(|e| match request.timeout {
+                Some(timeout) if e.is_timeout() => HttpError::Timeout(timeout),
+                _ => HttpError::Network(e.to_string()),
+            })?;
+            let status = response.status().as_u16();
+            let body = response.text().await.map_err(|e| match request.timeout {
+                Some(timeout) if e.is_timeout() => HttpError::Timeout(timeout),
+                _ => HttpError::InvalidResponse(e.to_string()),
+            })?;
+
+            Ok(HttpResponse { status, body })
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use crate::{
+    application::{
+        email_provider::{EmailProviderTrait, OutgoingEmail},
+        exceptions::AppError,
+    },
+    infrastructure::http_client::{HttpClient, HttpRequest},
+    log_error,
+};
+use async_trait::async_trait;
+
+/// Sends email through a Resend-style JSON API (`POST {api_url}` with a
+/// bearer key and `from`/`to`/`reply_to`/`subject`/`text`).
+pub struct HttpEmailProvider {
+    http_client: Arc<dyn HttpClient>,
+    api_url: String,
+    api_key: String,
+    from: String,
+}
+
+impl HttpEmailProvider {
+    pub fn create(
+        http_client: Arc<dyn HttpClient>,
+        api_url: String,
+        api_key: String,
+        from: String,
+    ) -> Arc<dyn EmailProviderTrait> {
+        Arc::new(Self {
+            http_client,
+            api_url,
+            api_key,
+            from,
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl EmailProviderTrait for HttpEmailProvider {
+    async fn send(&self, email: &OutgoingEmail) -> Result<(), AppError> {
+        let mut payload = serde_json::json!({
+            "from": &self.from,
+            "to": &email.to,
+            "subject": &email.subject,
+            "text": &email.text,
+        });
+        if let Some(reply_to) = &email.reply_to {
+            payload["reply_to"] = reply_to.as_str().into();
+        }
+
+        let request = HttpRequest::post_json(&self.api_url, &payload)
+            .with_header("Authorization", format!("Bearer {}", self.api_key));
+
+        let response = self.http_client.send(request).await.map_err(|e| {
+            log_error!("Email request failed: {}", e);
+            AppError::InternalError
+        })?;
+
+        if !response.is_success() {
+            log_error!(
+                "Email provider returned {}: {}",
+                response.status,
+                response.body
+            );
+            return Err(AppError::InternalError);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::http_client::test_support::ReqwestHttpClient;
+    use serde_json::json;
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{body_json, header, method, path},
+    };
+
+    fn provider(server: &MockServer) -> Arc<dyn EmailProviderTrait> {
+        HttpEmailProvider::create(
+            ReqwestHttpClient::create(),
+            format!("{}/emails", server.uri()),
+            "test-api-key".to_string(),
+            "contact@example.com".to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_send_omits_missing_reply_to() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/emails"))
+            .and(header("Authorization", "Bearer test-api-key"))
+            .and(body_json(json!({
+                "from": "contact@example.com",
+                "to": ["user@example.com"],
+                "subject": "Thanks",
+                "text": "We got your message",
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let result = provider(&server)
+            .send(&OutgoingEmail {
+                to: vec!["user@example.com".to_string()],
+                reply_to: None,
+                subject: "Thanks".to_string(),
+                text: "We got your message".to_string(),
+            })
+            .await;
+
+        assert!(result.is_ok());
+    }
+}
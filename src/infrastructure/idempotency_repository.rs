@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use crate::domain::{
+    entity::IdempotencyRecord, exceptions::RepositoryError,
+    repository::IdempotencyRepository as IdempotencyRepositoryInterface,
+};
+use async_trait::async_trait;
+use serde::Deserialize;
+use worker::{D1Database, D1PreparedStatement};
+
+#[derive(Deserialize)]
+struct IdempotencyRecordRow {
+    key: String,
+    request_hash: String,
+    message_id: String,
+    created_at: f64,
+    expires_at: f64,
+}
+
+impl From<IdempotencyRecordRow> for IdempotencyRecord {
+    fn from(row: IdempotencyRecordRow) -> Self {
+        IdempotencyRecord {
+            key: row.key,
+            request_hash: row.request_hash,
+            message_id: row.message_id,
+            created_at: row.created_at as i64,
+            expires_at: row.expires_at as i64,
+        }
+    }
+}
+
+pub struct IdempotencyRepository {
+    db: D1Database,
+}
+
+impl IdempotencyRepository {
+    pub fn create(db: D1Database) -> Arc<dyn IdempotencyRepositoryInterface> {
+        Arc::new(Self { db })
+    }
+
+    /// Builds the statements that claim `record.key` so they can be batched
+    /// with the message insert. An expired record for the key is replaced;
+    /// a live one makes the insert, and so the whole batch, fail.
+    pub fn insert_statements(
+        db: &D1Database,
+        record: &IdempotencyRecord,
+    ) -> Result<Vec<D1PreparedStatement>, RepositoryError> {
+        let delete_expired = db
+            .prepare("DELETE FROM idempotency_keys WHERE key = ?1 AND expires_at <= ?2")
+            .bind(&[record.key.clone().into(), (record.created_at as f64).into()])
+            .map_err(|e| {
+                RepositoryError::DatabaseError(format!("Failed to bind parameters: {e}"))
+            })?;
+
+        let insert = db
+            .prepare(
+                "INSERT INTO idempotency_keys (key, request_hash, message_id, created_at, expires_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+            )
+            .bind(&[
+                record.key.clone().into(),
+                record.request_hash.clone().into(),
+                record.message_id.clone().into(),
+                (record.created_at as f64).into(),
+                (record.expires_at as f64).into(),
+            ])
+            .map_err(|e| {
+                RepositoryError::DatabaseError(format!("Failed to bind parameters: {e}"))
+            })?;
+
+        Ok(vec![delete_expired, insert])
+    }
+}
+
+#[async_trait(?Send)]
+impl IdempotencyRepositoryInterface for IdempotencyRepository {
+    async fn find(
+        &self,
+        key: &str,
+        now: i64,
+    ) -> Result<Option<IdempotencyRecord>, RepositoryError> {
+        let statement = self.db.prepare(
+            "SELECT key, request_hash, message_id, created_at, expires_at
+             FROM idempotency_keys WHERE key = ?1 AND expires_at > ?2",
+        );
+
+        let row: Option<IdempotencyRecordRow> = statement
+            .bind(&[key.into(), (now as f64).into()])
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to bind parameters: {e}")))?
+            .first(None)
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to execute query: {e}")))?;
+
+        Ok(row.map(IdempotencyRecord::from))
+    }
+
+    async fn insert(&self, record: &IdempotencyRecord) -> Result<bool, RepositoryError> {
+        let results = self
+            .db
+            .batch(Self::insert_statements(&self.db, record)?)
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to execute batch: {e}")))?;
+
+        Ok(results.iter().all(|r| r.success()))
+    }
+
+    async fn delete_expired(&self, now: i64) -> Result<u64, RepositoryError> {
+        let result = self
+            .db
+            .prepare("DELETE FROM idempotency_keys WHERE expires_at <= ?1")
+            .bind(&[(now as f64).into()])
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to bind parameters: {e}")))?
+            .run()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to execute query: {e}")))?;
+
+        let changes = result
+            .meta()
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to read result: {e}")))?
+            .and_then(|meta| meta.changes)
+            .unwrap_or(0);
+
+        Ok(changes as u64)
+    }
+}
@@ -1,2 +1,20 @@
+pub mod api_key_authentication_service;
+pub mod category_repository;
+pub mod cloudflare_access_authentication_service;
 pub mod contact_message_repository;
+pub mod durable_object_rate_limiter;
+pub mod email_notification_service;
+pub mod form_repository;
+pub mod form_token_repository;
+pub mod hcaptcha_request_validation_service;
+pub mod honeypot_request_validation_service;
+pub mod http_client;
+pub mod http_email_provider;
+pub mod idempotency_repository;
+pub mod outbox_repository;
+pub mod queue_event_publisher;
+pub mod recaptcha_v3_request_validation_service;
 pub mod request_validation_service;
+pub mod retrying_http_client;
+pub mod site_repository;
+pub mod webhook_notification_service;
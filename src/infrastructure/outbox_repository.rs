@@ -0,0 +1,172 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::domain::{
+    entity::OutboxEvent, enums::OutboxStatus, exceptions::RepositoryError,
+    repository::OutboxRepository as OutboxRepositoryInterface,
+};
+use async_trait::async_trait;
+use serde::Deserialize;
+use worker::{D1Database, D1PreparedStatement, wasm_bindgen::JsValue};
+
+#[derive(Deserialize)]
+struct OutboxEventRow {
+    id: String,
+    event_type: String,
+    aggregate_id: String,
+    payload: String,
+    status: String,
+    attempts: f64,
+    next_attempt_at: f64,
+    last_error: Option<String>,
+    created_at: f64,
+    updated_at: f64,
+}
+
+impl TryFrom<OutboxEventRow> for OutboxEvent {
+    type Error = RepositoryError;
+
+    fn try_from(row: OutboxEventRow) -> Result<Self, Self::Error> {
+        let status = OutboxStatus::from_str(&row.status).map_err(|_| {
+            RepositoryError::DatabaseError(format!("Unknown outbox status: {}", row.status))
+        })?;
+
+        Ok(OutboxEvent {
+            id: row.id,
+            event_type: row.event_type,
+            aggregate_id: row.aggregate_id,
+            payload: row.payload,
+            status,
+            attempts: row.attempts as u32,
+            next_attempt_at: row.next_attempt_at as i64,
+            last_error: row.last_error,
+            created_at: row.created_at as i64,
+            updated_at: row.updated_at as i64,
+        })
+    }
+}
+
+pub struct OutboxRepository {
+    db: D1Database,
+}
+
+impl OutboxRepository {
+    pub fn create(db: D1Database) -> Arc<dyn OutboxRepositoryInterface> {
+        Arc::new(Self { db })
+    }
+
+    /// Builds the insert for `event` so it can be batched with the write that produced it.
+    pub fn insert_statement(
+        db: &D1Database,
+        event: &OutboxEvent,
+    ) -> Result<D1PreparedStatement, RepositoryError> {
+        db.prepare(
+            "INSERT INTO outbox_events
+             (id, event_type, aggregate_id, payload, status, attempts, next_attempt_at,
+              last_error, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        )
+        .bind(&[
+            event.id.clone().into(),
+            event.event_type.clone().into(),
+            event.aggregate_id.clone().into(),
+            event.payload.clone().into(),
+            event.status.to_string().into(),
+            event.attempts.into(),
+            (event.next_attempt_at as f64).into(),
+            Self::optional_string(&event.last_error),
+            (event.created_at as f64).into(),
+            (event.updated_at as f64).into(),
+        ])
+        .map_err(|e| RepositoryError::DatabaseError(format!("Failed to bind parameters: {e}")))
+    }
+
+    fn optional_string(value: &Option<String>) -> JsValue {
+        match value {
+            Some(v) => v.clone().into(),
+            None => JsValue::NULL,
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl OutboxRepositoryInterface for OutboxRepository {
+    async fn find_due(&self, now: i64, limit: u32) -> Result<Vec<OutboxEvent>, RepositoryError> {
+        let statement = self.db.prepare(
+            "SELECT id, event_type, aggregate_id, payload, status, attempts, next_attempt_at,
+                    last_error, created_at, updated_at
+             FROM outbox_events
+             WHERE status = ?1 AND next_attempt_at <= ?2
+             ORDER BY next_attempt_at ASC, id ASC LIMIT ?3",
+        );
+
+        let result = statement
+            .bind(&[
+                OutboxStatus::PENDING.to_string().into(),
+                (now as f64).into(),
+                limit.into(),
+            ])
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to bind parameters: {e}")))?
+            .all()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to execute query: {e}")))?;
+
+        let rows: Vec<OutboxEventRow> = result
+            .results()
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to read rows: {e}")))?;
+
+        rows.into_iter().map(OutboxEvent::try_from).collect()
+    }
+
+    async fn claim(&self, id: &str, now: i64, lease_until: i64) -> Result<bool, RepositoryError> {
+        let statement = self.db.prepare(
+            "UPDATE outbox_events
+             SET next_attempt_at = ?1
+             WHERE id = ?2 AND status = ?3 AND next_attempt_at <= ?4",
+        );
+
+        let result = statement
+            .bind(&[
+                (lease_until as f64).into(),
+                id.into(),
+                OutboxStatus::PENDING.to_string().into(),
+                (now as f64).into(),
+            ])
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to bind parameters: {e}")))?
+            .run()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to execute query: {e}")))?;
+
+        let changes = result
+            .meta()
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to read result: {e}")))?
+            .and_then(|meta| meta.changes)
+            .unwrap_or(0);
+
+        Ok(changes > 0)
+    }
+
+    async fn update(&self, event: &OutboxEvent) -> Result<bool, RepositoryError> {
+        let statement = self.db.prepare(
+            "UPDATE outbox_events
+             SET status = ?1, attempts = ?2, next_attempt_at = ?3, last_error = ?4, updated_at = ?5
+             WHERE id = ?6",
+        );
+
+        let result = statement
+            .bind(&[
+                event.status.to_string().into(),
+                event.attempts.into(),
+                (event.next_attempt_at as f64).into(),
+                Self::optional_string(&event.last_error),
+                (event.updated_at as f64).into(),
+                event.id.clone().into(),
+            ])
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to bind parameters: {e}")))?
+            .run()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to execute query: {e}")))?;
+
+        Ok(result.success())
+    }
+}
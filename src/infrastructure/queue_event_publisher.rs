@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use crate::{
+    application::{event_publisher::EventPublisherTrait, exceptions::AppError},
+    domain::events::DomainEvent,
+};
+use async_trait::async_trait;
+use worker::{Queue, console_error};
+
+/// Publishes domain events as JSON messages on a Cloudflare Queue.
+pub struct QueueEventPublisher {
+    queue: Queue,
+}
+
+impl QueueEventPublisher {
+    pub fn create(queue: Queue) -> Arc<dyn EventPublisherTrait> {
+        Arc::new(Self { queue })
+    }
+}
+
+#[async_trait(?Send)]
+impl EventPublisherTrait for QueueEventPublisher {
+    async fn publish(&self, event: &DomainEvent) -> Result<(), AppError> {
+        self.queue.send(event.clone()).await.map_err(|e| {
+            console_error!("Failed to enqueue event {}: {:?}", event.event_id(), e);
+            AppError::InternalError
+        })
+    }
+}
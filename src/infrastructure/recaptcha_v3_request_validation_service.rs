@@ -0,0 +1,297 @@
+use std::sync::Arc;
+
+use crate::{
+    application::request_validation_service::{
+        RequestValidationServiceTrait, VerificationError, VerificationRequest,
+    },
+    infrastructure::http_client::{HttpClient, HttpRequest},
+    log_error, log_info,
+};
+use async_trait::async_trait;
+use serde::Deserialize;
+
+pub const RECAPTCHA_SITEVERIFY_URL: &str = "https://www.google.com/recaptcha/api/siteverify";
+
+/// Google's suggested starting point for separating people from bots.
+pub const DEFAULT_RECAPTCHA_MIN_SCORE: f64 = 0.5;
+
+#[derive(Deserialize, Debug)]
+struct RecaptchaResponse {
+    success: bool,
+    score: Option<f64>,
+    action: Option<String>,
+    hostname: Option<String>,
+    #[serde(rename = "error-codes")]
+    error_codes: Option<Vec<String>>,
+}
+
+/// Verifies reCAPTCHA v3 tokens, which carry a score from 0.0 (bot) to 1.0
+/// (person) and the action name passed to `grecaptcha.execute`.
+pub struct RecaptchaV3RequestValidationService {
+    http_client: Arc<dyn HttpClient>,
+    siteverify_url: String,
+    secret_key: String,
+    min_score: f64,
+    expected_action: Option<String>,
+}
+
+impl RecaptchaV3RequestValidationService {
+    /// Tokens scoring below `min_score` are rejected, as are tokens for
+    /// another action when `expected_action` is set.
+    pub fn create(
+        http_client: Arc<dyn HttpClient>,
+        siteverify_url: String,
+        secret_key: String,
+        min_score: f64,
+        expected_action: Option<String>,
+    ) -> Arc<dyn RequestValidationServiceTrait> {
+        Arc::new(Self {
+            http_client,
+            siteverify_url,
+            secret_key,
+            min_score,
+            expected_action,
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl RequestValidationServiceTrait for RecaptchaV3RequestValidationService {
+    async fn verify(&self, request: &VerificationRequest) -> Result<(), VerificationError> {
+        let Some(token) = &request.token else {
+            return Err(VerificationError::TokenInvalid(
+                "Missing reCAPTCHA token".to_string(),
+            ));
+        };
+
+        let fields = [
+            ("secret", self.secret_key.as_str()),
+            ("response", token.as_str()),
+            ("remoteip", request.remote_ip.as_str()),
+        ];
+        let response = self
+            .http_client
+            .send(HttpRequest::post_form(&self.siteverify_url, &fields))
+            .await
+            .map_err(|e| {
+                log_error!("reCAPTCHA request failed: {}", e);
+                VerificationError::ProviderUnreachable(e.to_string())
+            })?;
+        if !response.is_success() {
+            log_error!("reCAPTCHA siteverify returned status {}", response.status);
+            return Err(VerificationError::ProviderUnreachable(format!(
+                "Siteverify returned status {}",
+                response.status
+            )));
+        }
+
+        let recaptcha_response: RecaptchaResponse = response.json().map_err(|e| {
+            log_error!("Failed to parse reCAPTCHA response: {}", e);
+            VerificationError::ProviderMisconfigured(format!("Unexpected siteverify response: {e}"))
+        })?;
+
+        if !recaptcha_response.success {
+            let error_codes = recaptcha_response.error_codes.unwrap_or_default();
+            log_info!("reCAPTCHA validation failed: {:?}", error_codes);
+            if error_codes.iter().any(|code| {
+                matches!(
+                    code.as_str(),
+                    "missing-input-secret" | "invalid-input-secret"
+                )
+            }) {
+                log_error!("reCAPTCHA is misconfigured: {:?}", error_codes);
+                return Err(VerificationError::ProviderMisconfigured(
+                    "reCAPTCHA rejected the secret key".to_string(),
+                ));
+            }
+            if error_codes
+                .iter()
+                .any(|code| code == "timeout-or-duplicate")
+            {
+                return Err(VerificationError::TokenExpired(
+                    "reCAPTCHA token has expired or was already redeemed".to_string(),
+                ));
+            }
+            return Err(VerificationError::TokenInvalid(
+                "reCAPTCHA validation failed".to_string(),
+            ));
+        }
+
+        if let Some(expected_action) = &self.expected_action
+            && recaptcha_response.action.as_ref() != Some(expected_action)
+        {
+            log_info!(
+                "reCAPTCHA action {:?} does not match {}",
+                recaptcha_response.action,
+                expected_action
+            );
+            return Err(VerificationError::TokenInvalid(
+                "reCAPTCHA action mismatch".to_string(),
+            ));
+        }
+
+        let score = recaptcha_response.score.unwrap_or(0.0);
+        if score < self.min_score {
+            log_info!(
+                "reCAPTCHA score {} from {:?} is below {}",
+                score,
+                recaptcha_response.hostname,
+                self.min_score
+            );
+            return Err(VerificationError::TokenInvalid(
+                "reCAPTCHA score too low".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::http_client::test_support::ReqwestHttpClient;
+    use serde_json::json;
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{body_string_contains, method, path},
+    };
+
+    fn service(server: &MockServer) -> Arc<dyn RequestValidationServiceTrait> {
+        RecaptchaV3RequestValidationService::create(
+            ReqwestHttpClient::create(),
+            format!("{}/recaptcha/api/siteverify", server.uri()),
+            "test-secret".to_string(),
+            0.7,
+            Some("contact".to_string()),
+        )
+    }
+
+    fn request() -> VerificationRequest {
+        VerificationRequest {
+            token: Some("token-1".to_string()),
+            remote_ip: "1.2.3.4".to_string(),
+            ..Default::default()
+        }
+    }
+
+    async fn respond_with(server: &MockServer, body: serde_json::Value) {
+        Mock::given(method("POST"))
+            .and(path("/recaptcha/api/siteverify"))
+            .and(body_string_contains("secret=test-secret"))
+            .and(body_string_contains("response=token-1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .expect(1)
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_high_score_for_expected_action_is_accepted() {
+        let server = MockServer::start().await;
+        respond_with(
+            &server,
+            json!({ "success": true, "score": 0.9, "action": "contact", "hostname": "example.com" }),
+        )
+        .await;
+
+        assert!(service(&server).verify(&request()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_score_below_threshold_is_rejected() {
+        let server = MockServer::start().await;
+        respond_with(
+            &server,
+            json!({ "success": true, "score": 0.3, "action": "contact" }),
+        )
+        .await;
+
+        let result = service(&server).verify(&request()).await;
+
+        assert!(
+            matches!(result, Err(VerificationError::TokenInvalid(msg)) if msg.contains("score"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unexpected_action_is_rejected() {
+        let server = MockServer::start().await;
+        respond_with(
+            &server,
+            json!({ "success": true, "score": 0.9, "action": "login" }),
+        )
+        .await;
+
+        let result = service(&server).verify(&request()).await;
+
+        assert!(
+            matches!(result, Err(VerificationError::TokenInvalid(msg)) if msg.contains("action"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_any_action_is_accepted_when_not_configured() {
+        let server = MockServer::start().await;
+        respond_with(
+            &server,
+            json!({ "success": true, "score": 0.5, "action": "login" }),
+        )
+        .await;
+
+        let service = RecaptchaV3RequestValidationService::create(
+            ReqwestHttpClient::create(),
+            format!("{}/recaptcha/api/siteverify", server.uri()),
+            "test-secret".to_string(),
+            DEFAULT_RECAPTCHA_MIN_SCORE,
+            None,
+        );
+
+        assert!(service.verify(&request()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_failed_verification() {
+        let server = MockServer::start().await;
+        respond_with(
+            &server,
+            json!({ "success": false, "error-codes": ["invalid-input-response"] }),
+        )
+        .await;
+
+        let result = service(&server).verify(&request()).await;
+
+        assert!(matches!(result, Err(VerificationError::TokenInvalid(_))));
+    }
+
+    #[tokio::test]
+    async fn test_redeemed_token_is_expired() {
+        let server = MockServer::start().await;
+        respond_with(
+            &server,
+            json!({ "success": false, "error-codes": ["timeout-or-duplicate"] }),
+        )
+        .await;
+
+        let result = service(&server).verify(&request()).await;
+
+        assert!(matches!(result, Err(VerificationError::TokenExpired(_))));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_secret_is_misconfiguration() {
+        let server = MockServer::start().await;
+        respond_with(
+            &server,
+            json!({ "success": false, "error-codes": ["invalid-input-secret"] }),
+        )
+        .await;
+
+        let result = service(&server).verify(&request()).await;
+
+        assert!(matches!(
+            result,
+            Err(VerificationError::ProviderMisconfigured(_))
+        ));
+    }
+}
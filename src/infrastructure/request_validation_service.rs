@@ -1,100 +1,506 @@
 use std::sync::Arc;
 
-use crate::application::{
-    exceptions::AppError, request_validation_service::RequestValidationServiceTrait,
+use crate::{
+    application::request_validation_service::{
+        RequestValidationServiceTrait, VerificationError, VerificationRequest,
+    },
+    infrastructure::http_client::{HttpClient, HttpRequest},
+    log_error, log_info,
 };
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
-use worker::{Fetch, Headers, Method, Request, RequestInit, console_error, console_log};
+use uuid::Uuid;
+
+/// Turnstile tokens are only redeemable for five minutes.
+pub const DEFAULT_TURNSTILE_MAX_TOKEN_AGE_SECONDS: i64 = 300;
 
 #[derive(Deserialize, Debug)]
 struct TurnstileResponse {
     success: bool,
+    challenge_ts: Option<String>,
+    hostname: Option<String>,
+    action: Option<String>,
+    cdata: Option<String>,
     #[serde(rename = "error-codes")]
     error_codes: Option<Vec<String>>,
 }
 
-#[derive(Clone)]
+/// What a successful siteverify response must also satisfy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TurnstilePolicy {
+    /// Hostnames the widget may be served from; empty allows any.
+    pub allowed_hostnames: Vec<String>,
+    /// The `data-action` the widget must have been rendered with, if any.
+    pub expected_action: Option<String>,
+    /// The `data-cdata` the widget must have been rendered with, if any.
+    pub expected_cdata: Option<String>,
+    /// Tokens whose challenge was solved longer ago than this are rejected.
+    pub max_token_age_seconds: i64,
+}
+
+impl Default for TurnstilePolicy {
+    fn default() -> Self {
+        Self {
+            allowed_hostnames: vec![],
+            expected_action: None,
+            expected_cdata: None,
+            max_token_age_seconds: DEFAULT_TURNSTILE_MAX_TOKEN_AGE_SECONDS,
+        }
+    }
+}
+
+impl TurnstilePolicy {
+    fn check(
+        &self,
+        response: &TurnstileResponse,
+        now: DateTime<Utc>,
+    ) -> Result<(), VerificationError> {
+        if !self.allowed_hostnames.is_empty() {
+            let hostname = response.hostname.as_deref().unwrap_or_default();
+            if !self
+                .allowed_hostnames
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(hostname))
+            {
+                log_info!("Turnstile token issued for unexpected host {:?}", hostname);
+                return Err(VerificationError::TokenInvalid(
+                    "Turnstile hostname mismatch".to_string(),
+                ));
+            }
+        }
+
+        if let Some(expected_action) = &self.expected_action
+            && response.action.as_ref() != Some(expected_action)
+        {
+            log_info!(
+                "Turnstile action {:?} does not match {}",
+                response.action,
+                expected_action
+            );
+            return Err(VerificationError::TokenInvalid(
+                "Turnstile action mismatch".to_string(),
+            ));
+        }
+
+        if let Some(expected_cdata) = &self.expected_cdata
+            && response.cdata.as_ref() != Some(expected_cdata)
+        {
+            log_info!(
+                "Turnstile cdata {:?} does not match {}",
+                response.cdata,
+                expected_cdata
+            );
+            return Err(VerificationError::TokenInvalid(
+                "Turnstile cdata mismatch".to_string(),
+            ));
+        }
+
+        let Some(challenge_ts) = response
+            .challenge_ts
+            .as_deref()
+            .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+        else {
+            log_error!(
+                "Turnstile response has no valid challenge_ts: {:?}",
+                response.challenge_ts
+            );
+            return Err(VerificationError::TokenInvalid(
+                "Turnstile challenge time missing".to_string(),
+            ));
+        };
+        let age = (now - challenge_ts.with_timezone(&Utc)).num_seconds();
+        if age > self.max_token_age_seconds {
+            log_info!("Turnstile token is {}s old", age);
+            return Err(VerificationError::TokenExpired(
+                "Turnstile token has expired".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
 pub struct CloudflareRequestValidationService {
+    http_client: Arc<dyn HttpClient>,
     siteverify_url: String,
     secret_key: String,
+    policy: TurnstilePolicy,
 }
 
 impl CloudflareRequestValidationService {
     pub fn create(
+        http_client: Arc<dyn HttpClient>,
         siteverify_url: String,
         secret_key: String,
+        policy: TurnstilePolicy,
     ) -> Arc<dyn RequestValidationServiceTrait> {
         Arc::new(Self {
+            http_client,
             siteverify_url,
             secret_key,
+            policy,
         })
     }
 }
 
 #[async_trait(?Send)]
 impl RequestValidationServiceTrait for CloudflareRequestValidationService {
-    async fn verify(&self, token: String, ip: String) -> Result<(), AppError> {
+    async fn verify(&self, request: &VerificationRequest) -> Result<(), VerificationError> {
+        let Some(token) = &request.token else {
+            return Err(VerificationError::TokenInvalid(
+                "Missing Turnstile token".to_string(),
+            ));
+        };
+
+        // Siteverify answers a repeated request with the same idempotency key
+        // like the first one, so resending this exact body after a network
+        // failure doesn't report the token as already redeemed.
         let body = serde_json::json!({
-            "secret": &self.secret_key,
+            "secret": request.site_secret_key.as_deref().unwrap_or(&self.secret_key),
             "response": token,
-            "remoteip": ip,
+            "remoteip": &request.remote_ip,
+            "idempotency_key": Uuid::now_v7().to_string(),
         });
-        let body_string = serde_json::to_string(&body).map_err(|_| AppError::InternalError)?;
 
-        let mut init = RequestInit::new();
-        init.with_method(Method::Post);
-        init.with_body(Some(body_string.into()));
+        let response = self
+            .http_client
+            .send(HttpRequest::post_json(&self.siteverify_url, &body))
+            .await
+            .map_err(|e| {
+                log_error!("Cloudflare request failed: {}", e);
+                VerificationError::ProviderUnreachable(e.to_string())
+            })?;
+        if !response.is_success() {
+            log_error!("Turnstile siteverify returned status {}", response.status);
+            return Err(VerificationError::ProviderUnreachable(format!(
+                "Siteverify returned status {}",
+                response.status
+            )));
+        }
 
-        let headers = Headers::new();
-        headers
-            .set("Content-Type", "application/json")
-            .map_err(|_| AppError::InternalError)?;
-        init.with_headers(headers);
+        let turnstile_response: TurnstileResponse = response.json().map_err(|e| {
+            log_error!("Failed to parse Turnstile response: {}", e);
+            VerificationError::ProviderMisconfigured(format!("Unexpected siteverify response: {e}"))
+        })?;
 
-        let request = Request::new_with_init(&self.siteverify_url, &init)
-            .map_err(|_| AppError::InternalError)?;
+        log_info!(
+            "Turnstile response: success={} hostname={:?} action={:?}",
+            turnstile_response.success,
+            turnstile_response.hostname,
+            turnstile_response.action
+        );
 
-        let mut response = Fetch::Request(request).send().await.map_err(|e| {
-            console_error!("Cloudflare request failed: {:?}", e);
-            AppError::InternalError
-        })?;
+        if turnstile_response.success {
+            return self.policy.check(&turnstile_response, Utc::now());
+        }
 
-        let turnstile_response: TurnstileResponse = response.json().await.map_err(|e| {
-            console_error!("Failed to parse Turnstile response: {:?}", e);
-            AppError::InternalError
-        })?;
+        let error_codes = turnstile_response.error_codes.unwrap_or_default();
+        log_info!("Turnstile validation failed with errors: {:?}", error_codes);
+        Err(Self::rejection(&error_codes))
+    }
+}
 
-        console_log!("Turnstile response: {:?}", turnstile_response);
+impl CloudflareRequestValidationService {
+    fn rejection(error_codes: &[String]) -> VerificationError {
+        if error_codes.iter().any(|code| {
+            matches!(
+                code.as_str(),
+                "missing-input-secret" | "invalid-input-secret"
+            )
+        }) {
+            log_error!("Invalid secret key configured");
+            return VerificationError::ProviderMisconfigured(
+                "Turnstile rejected the secret key".to_string(),
+            );
+        }
+        if error_codes
+            .iter()
+            .any(|code| code == "timeout-or-duplicate")
+        {
+            return VerificationError::TokenExpired(
+                "Turnstile token has expired or was already redeemed".to_string(),
+            );
+        }
+        VerificationError::TokenInvalid("Turnstile validation failed".to_string())
+    }
+}
 
-        if turnstile_response.success {
-            Ok(())
-        } else {
-            if let Some(error_codes) = &turnstile_response.error_codes {
-                console_log!("Turnstile validation failed with errors: {:?}", error_codes);
-
-                for error_code in error_codes {
-                    match error_code.as_str() {
-                        "invalid-input-secret" => {
-                            console_error!("Invalid secret key configured");
-                            return Err(AppError::InternalError);
-                        }
-                        "invalid-input-response" => {
-                            console_log!("Invalid or expired token");
-                        }
-                        "timeout-or-duplicate" => {
-                            console_log!("Token timeout or duplicate submission");
-                        }
-                        _ => {
-                            console_log!("Unknown error code: {}", error_code);
-                        }
-                    }
-                }
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::http_client::test_support::ReqwestHttpClient;
+    use chrono::Duration;
+    use serde_json::json;
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{body_partial_json, method, path},
+    };
 
-            Err(AppError::Unauthorised(
-                "Turnstile validation failed".to_string(),
-            ))
+    fn policy() -> TurnstilePolicy {
+        TurnstilePolicy {
+            allowed_hostnames: vec!["example.com".to_string(), "www.example.com".to_string()],
+            expected_action: Some("contact".to_string()),
+            expected_cdata: None,
+            max_token_age_seconds: 300,
         }
     }
+
+    fn service(
+        server: &MockServer,
+        policy: TurnstilePolicy,
+    ) -> Arc<dyn RequestValidationServiceTrait> {
+        CloudflareRequestValidationService::create(
+            ReqwestHttpClient::create(),
+            format!("{}/turnstile/v0/siteverify", server.uri()),
+            "test-secret".to_string(),
+            policy,
+        )
+    }
+
+    fn request() -> VerificationRequest {
+        VerificationRequest {
+            token: Some("token-1".to_string()),
+            remote_ip: "1.2.3.4".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn success(hostname: &str, action: &str, age_seconds: i64) -> serde_json::Value {
+        json!({
+            "success": true,
+            "challenge_ts": (Utc::now() - Duration::seconds(age_seconds)).to_rfc3339(),
+            "hostname": hostname,
+            "action": action,
+            "cdata": "session-1",
+            "error-codes": [],
+        })
+    }
+
+    async fn respond_with(server: &MockServer, body: serde_json::Value) {
+        Mock::given(method("POST"))
+            .and(path("/turnstile/v0/siteverify"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_valid_token_is_accepted() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/turnstile/v0/siteverify"))
+            .and(body_partial_json(json!({
+                "secret": "test-secret",
+                "response": "token-1",
+                "remoteip": "1.2.3.4",
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(success(
+                "WWW.example.com",
+                "contact",
+                5,
+            )))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        assert!(service(&server, policy()).verify(&request()).await.is_ok());
+
+        let requests = server.received_requests().await.unwrap();
+        let body: serde_json::Value = requests[0].body_json().unwrap();
+        assert!(Uuid::parse_str(body["idempotency_key"].as_str().unwrap()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_site_secret_overrides_configured_secret() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/turnstile/v0/siteverify"))
+            .and(body_partial_json(json!({ "secret": "site-secret" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(success(
+                "example.com",
+                "contact",
+                5,
+            )))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let request = VerificationRequest {
+            site_secret_key: Some("site-secret".to_string()),
+            ..request()
+        };
+
+        assert!(service(&server, policy()).verify(&request).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_unexpected_hostname_is_rejected() {
+        let server = MockServer::start().await;
+        respond_with(&server, success("evil.com", "contact", 5)).await;
+
+        let result = service(&server, policy()).verify(&request()).await;
+
+        assert!(
+            matches!(result, Err(VerificationError::TokenInvalid(msg)) if msg.contains("hostname"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unexpected_action_is_rejected() {
+        let server = MockServer::start().await;
+        respond_with(&server, success("example.com", "login", 5)).await;
+
+        let result = service(&server, policy()).verify(&request()).await;
+
+        assert!(
+            matches!(result, Err(VerificationError::TokenInvalid(msg)) if msg.contains("action"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unexpected_cdata_is_rejected() {
+        let server = MockServer::start().await;
+        respond_with(&server, success("example.com", "contact", 5)).await;
+        let policy = TurnstilePolicy {
+            expected_cdata: Some("contact-form".to_string()),
+            ..policy()
+        };
+
+        let result = service(&server, policy).verify(&request()).await;
+
+        assert!(
+            matches!(result, Err(VerificationError::TokenInvalid(msg)) if msg.contains("cdata"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_old_token_is_rejected() {
+        let server = MockServer::start().await;
+        respond_with(&server, success("example.com", "contact", 301)).await;
+
+        let result = service(&server, policy()).verify(&request()).await;
+
+        assert!(matches!(result, Err(VerificationError::TokenExpired(_))));
+    }
+
+    #[tokio::test]
+    async fn test_default_policy_accepts_any_host_and_action() {
+        let server = MockServer::start().await;
+        respond_with(&server, success("anything.test", "", 60)).await;
+
+        let result = service(&server, TurnstilePolicy::default())
+            .verify(&request())
+            .await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_missing_challenge_ts_is_rejected() {
+        let server = MockServer::start().await;
+        respond_with(&server, json!({ "success": true })).await;
+
+        let result = service(&server, TurnstilePolicy::default())
+            .verify(&request())
+            .await;
+
+        assert!(matches!(result, Err(VerificationError::TokenInvalid(_))));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_token_is_rejected() {
+        let server = MockServer::start().await;
+        respond_with(
+            &server,
+            json!({ "success": false, "error-codes": ["invalid-input-response"] }),
+        )
+        .await;
+
+        let result = service(&server, policy()).verify(&request()).await;
+
+        assert!(matches!(result, Err(VerificationError::TokenInvalid(_))));
+    }
+
+    #[tokio::test]
+    async fn test_redeemed_token_is_expired() {
+        let server = MockServer::start().await;
+        respond_with(
+            &server,
+            json!({ "success": false, "error-codes": ["timeout-or-duplicate"] }),
+        )
+        .await;
+
+        let result = service(&server, policy()).verify(&request()).await;
+
+        assert!(matches!(result, Err(VerificationError::TokenExpired(_))));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_secret_is_misconfiguration() {
+        let server = MockServer::start().await;
+        respond_with(
+            &server,
+            json!({ "success": false, "error-codes": ["invalid-input-secret"] }),
+        )
+        .await;
+
+        let result = service(&server, policy()).verify(&request()).await;
+
+        assert!(matches!(
+            result,
+            Err(VerificationError::ProviderMisconfigured(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_server_error_is_unreachable() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/turnstile/v0/siteverify"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+
+        let result = service(&server, policy()).verify(&request()).await;
+
+        assert!(matches!(
+            result,
+            Err(VerificationError::ProviderUnreachable(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_siteverify_is_unreachable() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/turnstile/v0/siteverify"))
+            .respond_with(ResponseTemplate::new(429).set_body_string("Too Many Requests"))
+            .mount(&server)
+            .await;
+
+        let result = service(&server, policy()).verify(&request()).await;
+
+        assert!(matches!(
+            result,
+            Err(VerificationError::ProviderUnreachable(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_unparseable_success_is_misconfiguration() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/turnstile/v0/siteverify"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<html></html>"))
+            .mount(&server)
+            .await;
+
+        let result = service(&server, policy()).verify(&request()).await;
+
+        assert!(matches!(
+            result,
+            Err(VerificationError::ProviderMisconfigured(_))
+        ));
+    }
 }
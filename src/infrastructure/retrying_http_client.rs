@@ -0,0 +1,309 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use uuid::Uuid;
+use worker::Delay;
+
+use crate::{
+    infrastructure::http_client::{HttpClient, HttpError, HttpRequest, HttpResponse},
+    log_info,
+};
+
+/// Timeout and retry settings for outbound calls.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Applied to each attempt that doesn't set its own timeout.
+    pub timeout: Duration,
+    /// Attempts made after the first one fails.
+    pub max_retries: u32,
+    pub base_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(5),
+            max_retries: 2,
+            base_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before the given retry (1-based): exponential backoff of which
+    /// half is fixed and half scaled by `jitter` in `0.0..=1.0`, so that
+    /// requests which failed together don't retry together.
+    pub fn backoff(&self, retry: u32, jitter: f64) -> Duration {
+        let exponent = retry.saturating_sub(1).min(16);
+        let delay = self
+            .base_backoff
+            .saturating_mul(1 << exponent)
+            .min(self.max_backoff);
+        delay / 2 + delay.mul_f64(jitter.clamp(0.0, 1.0) / 2.0)
+    }
+
+    fn is_retryable(result: &Result<HttpResponse, HttpError>) -> bool {
+        match result {
+            Ok(response) => response.is_server_error(),
+            Err(HttpError::Network(_)) | Err(HttpError::Timeout(_)) => true,
+            Err(_) => false,
+        }
+    }
+}
+
+/// A uniformly distributed value in `0.0..1.0`. Version 7 UUIDs end in 62
+/// random bits, which saves a separate source of randomness.
+fn jitter() -> f64 {
+    let random_bits = Uuid::now_v7().as_u128() as u64 & ((1 << 53) - 1);
+    random_bits as f64 / (1u64 << 53) as f64
+}
+
+#[async_trait(?Send)]
+pub trait Sleeper: Send + Sync {
+    async fn sleep(&self, duration: Duration);
+}
+
+pub struct WorkerSleeper;
+
+impl WorkerSleeper {
+    pub fn create() -> Arc<dyn Sleeper> {
+        Arc::new(Self)
+    }
+}
+
+#[async_trait(?Send)]
+impl Sleeper for WorkerSleeper {
+    async fn sleep(&self, duration: Duration) {
+        Delay::from(duration).await;
+    }
+}
+
+/// Wraps an `HttpClient` with a per-attempt timeout and retries network
+/// errors, timeouts and 5xx responses. Only use it for requests that are safe
+/// to repeat; the last attempt's response or error is returned unchanged.
+pub struct RetryingHttpClient {
+    inner: Arc<dyn HttpClient>,
+    policy: RetryPolicy,
+    sleeper: Arc<dyn Sleeper>,
+}
+
+impl RetryingHttpClient {
+    pub fn create(
+        inner: Arc<dyn HttpClient>,
+        policy: RetryPolicy,
+        sleeper: Arc<dyn Sleeper>,
+    ) -> Arc<dyn HttpClient> {
+        Arc::new(Self {
+            inner,
+            policy,
+            sleeper,
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl HttpClient for RetryingHttpClient {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, HttpError> {
+        let request = match request.timeout {
+            Some(_) => request,
+            None => request.with_timeout(self.policy.timeout),
+        };
+
+        let mut retry = 0;
+        loop {
+            let result = self.inner.send(request.clone()).await;
+            if retry >= self.policy.max_retries || !RetryPolicy::is_retryable(&result) {
+                return result;
+            }
+
+            retry += 1;
+            let delay = self.policy.backoff(retry, jitter());
+            log_info!(
+                "Retrying {} ({} of {}) in {}ms after {}",
+                request.url,
+                retry,
+                self.policy.max_retries,
+                delay.as_millis(),
+                match &result {
+                    Ok(response) => format!("status {}", response.status),
+                    Err(e) => e.to_string(),
+                }
+            );
+            self.sleeper.sleep(delay).await;
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test_support {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Records requested delays without waiting.
+    #[derive(Default)]
+    pub struct RecordingSleeper {
+        pub slept: Mutex<Vec<Duration>>,
+    }
+
+    #[async_trait(?Send)]
+    impl Sleeper for RecordingSleeper {
+        async fn sleep(&self, duration: Duration) {
+            self.slept.lock().unwrap().push(duration);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_support::RecordingSleeper;
+    use super::*;
+    use crate::infrastructure::http_client::test_support::ReqwestHttpClient;
+    use wiremock::{Mock, MockServer, ResponseTemplate, matchers::method};
+
+    const POLICY: RetryPolicy = RetryPolicy {
+        timeout: Duration::from_millis(200),
+        max_retries: 2,
+        base_backoff: Duration::from_millis(100),
+        max_backoff: Duration::from_millis(150),
+    };
+
+    fn client(sleeper: &Arc<RecordingSleeper>) -> Arc<dyn HttpClient> {
+        RetryingHttpClient::create(ReqwestHttpClient::create(), POLICY, sleeper.clone())
+    }
+
+    #[test]
+    fn test_backoff_grows_with_jitter_and_is_capped() {
+        assert_eq!(POLICY.backoff(1, 0.0), Duration::from_millis(50));
+        assert_eq!(POLICY.backoff(1, 1.0), Duration::from_millis(100));
+        assert_eq!(POLICY.backoff(2, 0.5), Duration::from_micros(112_500));
+        assert_eq!(POLICY.backoff(30, 1.0), Duration::from_millis(150));
+    }
+
+    #[test]
+    fn test_jitter_is_in_range() {
+        for _ in 0..100 {
+            assert!((0.0..1.0).contains(&jitter()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_server_errors_are_retried_until_success() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let sleeper = Arc::new(RecordingSleeper::default());
+
+        let response = client(&sleeper)
+            .send(HttpRequest::get(server.uri()))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status, 200);
+        let slept = sleeper.slept.lock().unwrap();
+        assert_eq!(slept.len(), 2);
+        assert!(slept[0] >= Duration::from_millis(50) && slept[0] <= Duration::from_millis(100));
+        assert!(slept[1] >= Duration::from_millis(75) && slept[1] <= Duration::from_millis(150));
+    }
+
+    #[tokio::test]
+    async fn test_retries_are_bounded() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(502))
+            .expect(3)
+            .mount(&server)
+            .await;
+        let sleeper = Arc::new(RecordingSleeper::default());
+
+        let response = client(&sleeper)
+            .send(HttpRequest::get(server.uri()))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status, 502);
+        assert_eq!(sleeper.slept.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_slow_upstream_times_out_and_is_retried() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(2)))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+            .mount(&server)
+            .await;
+        let sleeper = Arc::new(RecordingSleeper::default());
+
+        let response = client(&sleeper)
+            .send(HttpRequest::get(server.uri()))
+            .await
+            .unwrap();
+
+        assert_eq!(response.body, "ok");
+        assert_eq!(sleeper.slept.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_persistently_slow_upstream_returns_timeout() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(2)))
+            .mount(&server)
+            .await;
+        let sleeper = Arc::new(RecordingSleeper::default());
+
+        let result = client(&sleeper).send(HttpRequest::get(server.uri())).await;
+
+        assert_eq!(
+            result.unwrap_err(),
+            HttpError::Timeout(Duration::from_millis(200))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_are_not_retried() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(400))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let sleeper = Arc::new(RecordingSleeper::default());
+
+        let response = client(&sleeper)
+            .send(HttpRequest::get(server.uri()))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status, 400);
+        assert!(sleeper.slept.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_upstream_is_retried() {
+        let sleeper = Arc::new(RecordingSleeper::default());
+
+        // Nothing listens on port 9 (discard) in the test environment.
+        let result = client(&sleeper)
+            .send(HttpRequest::get("http://127.0.0.1:9/"))
+            .await;
+
+        assert!(matches!(result, Err(HttpError::Network(_))));
+        assert_eq!(sleeper.slept.lock().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,112 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::domain::{
+    entity::Site, enums::ContactMessageCategory, exceptions::RepositoryError,
+    repository::SiteRepository as SiteRepositoryInterface,
+};
+use async_trait::async_trait;
+use serde::Deserialize;
+use worker::D1Database;
+
+#[derive(Deserialize)]
+struct SiteRow {
+    id: String,
+    name: String,
+    allowed_origins: String,
+    turnstile_secret_key: Option<String>,
+    turnstile_site_key: Option<String>,
+    categories: String,
+    notification_recipients: String,
+    created_at: f64,
+    updated_at: f64,
+}
+
+impl TryFrom<SiteRow> for Site {
+    type Error = RepositoryError;
+
+    fn try_from(row: SiteRow) -> Result<Self, Self::Error> {
+        let allowed_origins: Vec<String> =
+            serde_json::from_str(&row.allowed_origins).map_err(|e| {
+                RepositoryError::DatabaseError(format!(
+                    "Invalid allowed_origins for site {}: {e}",
+                    row.id
+                ))
+            })?;
+        let categories: Vec<String> = serde_json::from_str(&row.categories).map_err(|e| {
+            RepositoryError::DatabaseError(format!("Invalid categories for site {}: {e}", row.id))
+        })?;
+        let categories = categories
+            .iter()
+            .map(|category| {
+                ContactMessageCategory::from_str(category).map_err(|_| {
+                    RepositoryError::DatabaseError(format!(
+                        "Unknown category {category} for site {}",
+                        row.id
+                    ))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Site {
+            id: row.id,
+            name: row.name,
+            allowed_origins,
+            turnstile_secret_key: row.turnstile_secret_key.filter(|s| !s.is_empty()),
+            turnstile_site_key: row.turnstile_site_key.filter(|s| !s.is_empty()),
+            categories,
+            notification_recipients: row.notification_recipients,
+            created_at: row.created_at as i64,
+            updated_at: row.updated_at as i64,
+        })
+    }
+}
+
+const SELECT_COLUMNS: &str = "sites.id, sites.name, sites.allowed_origins, \
+     sites.turnstile_secret_key, sites.turnstile_site_key, sites.categories, sites.notification_recipients, \
+     sites.created_at, sites.updated_at";
+
+pub struct SiteRepository {
+    db: D1Database,
+}
+
+impl SiteRepository {
+    pub fn create(db: D1Database) -> Arc<dyn SiteRepositoryInterface> {
+        Arc::new(Self { db })
+    }
+
+    async fn find_one(&self, query: &str, value: &str) -> Result<Option<Site>, RepositoryError> {
+        let row: Option<SiteRow> = self
+            .db
+            .prepare(query)
+            .bind(&[value.into()])
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to bind parameters: {e}")))?
+            .first(None)
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(format!("Failed to execute query: {e}")))?;
+
+        row.map(Site::try_from).transpose()
+    }
+}
+
+#[async_trait(?Send)]
+impl SiteRepositoryInterface for SiteRepository {
+    async fn find_by_id(&self, id: &str) -> Result<Option<Site>, RepositoryError> {
+        self.find_one(
+            &format!("SELECT {SELECT_COLUMNS} FROM sites WHERE sites.id = ?1"),
+            id,
+        )
+        .await
+    }
+
+    async fn find_by_origin(&self, origin: &str) -> Result<Option<Site>, RepositoryError> {
+        self.find_one(
+            &format!(
+                "SELECT {SELECT_COLUMNS} FROM sites, json_each(sites.allowed_origins) AS origin
+                 WHERE origin.value = ?1 ORDER BY sites.id LIMIT 1"
+            ),
+            origin,
+        )
+        .await
+    }
+}
@@ -0,0 +1,435 @@
+use std::sync::Arc;
+
+use crate::{
+    application::{exceptions::AppError, notification_service::NotificationServiceTrait},
+    domain::entity::ContactMessage,
+    infrastructure::http_client::{HttpClient, HttpRequest},
+    log_error,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use sha2::Sha256;
+
+const SLACK_TEXT_LIMIT: usize = 2900;
+const DISCORD_DESCRIPTION_LIMIT: usize = 4000;
+const DISCORD_EMBED_COLOR: u32 = 0x5865F2;
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookKind {
+    Slack,
+    Discord,
+    Generic,
+}
+
+/// A single webhook destination from the `WEBHOOKS` secret, e.g.
+/// `[{"kind": "slack", "url": "https://hooks.slack.com/...", "categories": ["ERROR"]}]`.
+///
+/// Webhooks without `categories` receive every message. Generic webhooks
+/// are signed when a `secret` is set.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct WebhookConfig {
+    pub kind: WebhookKind,
+    pub url: String,
+    pub categories: Option<Vec<String>>,
+    pub secret: Option<String>,
+}
+
+impl WebhookConfig {
+    pub fn parse_list(value: &str) -> Result<Vec<Self>, String> {
+        if value.trim().is_empty() {
+            return Ok(vec![]);
+        }
+        serde_json::from_str(value).map_err(|e| format!("Invalid webhook configuration: {e}"))
+    }
+
+    fn accepts(&self, category: &str) -> bool {
+        self.categories
+            .as_ref()
+            .is_none_or(|categories| categories.iter().any(|c| c.eq_ignore_ascii_case(category)))
+    }
+}
+
+/// Computes the `X-Webhook-Signature` value for a generic webhook body:
+/// `sha256=hex(HMAC-SHA256(secret, "{timestamp}.{body}"))`.
+pub fn sign_payload(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(format!("{timestamp}.{body}").as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+fn truncate(value: &str, limit: usize) -> String {
+    match value.char_indices().nth(limit) {
+        Some((index, _)) => format!("{}…", &value[..index]),
+        None => value.to_string(),
+    }
+}
+
+fn escape_slack(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn slack_payload(message: &ContactMessage) -> Value {
+    json!({
+        "text": format!("New {} message from {}", message.category, message.name),
+        "blocks": [
+            {
+                "type": "header",
+                "text": { "type": "plain_text", "text": format!("New {} message", message.category) }
+            },
+            {
+                "type": "section",
+                "fields": [
+                    { "type": "mrkdwn", "text": format!("*Name:*\n{}", escape_slack(&message.name)) },
+                    { "type": "mrkdwn", "text": format!("*Email:*\n{}", escape_slack(&message.email)) }
+                ]
+            },
+            {
+                "type": "section",
+                "text": { "type": "mrkdwn", "text": escape_slack(&truncate(&message.message, SLACK_TEXT_LIMIT)) }
+            },
+            {
+                "type": "context",
+                "elements": [{ "type": "mrkdwn", "text": format!("Reference: {}", message.id) }]
+            }
+        ]
+    })
+}
+
+fn discord_payload(message: &ContactMessage) -> Value {
+    let timestamp = DateTime::from_timestamp(message.created_at, 0)
+        .unwrap_or_default()
+        .to_rfc3339();
+
+    json!({
+        "embeds": [{
+            "title": format!("New {} message", message.category),
+            "description": truncate(&message.message, DISCORD_DESCRIPTION_LIMIT),
+            "color": DISCORD_EMBED_COLOR,
+            "fields": [
+                { "name": "Name", "value": &message.name, "inline": true },
+                { "name": "Email", "value": &message.email, "inline": true }
+            ],
+            "footer": { "text": format!("Reference: {}", message.id) },
+            "timestamp": timestamp
+        }]
+    })
+}
+
+fn generic_payload(message: &ContactMessage) -> Value {
+    json!({
+        "event": "contact_message.created",
+        "message": message,
+    })
+}
+
+pub struct WebhookNotificationService {
+    http_client: Arc<dyn HttpClient>,
+    webhooks: Vec<WebhookConfig>,
+}
+
+impl WebhookNotificationService {
+    pub fn create(
+        http_client: Arc<dyn HttpClient>,
+        webhooks: Vec<WebhookConfig>,
+    ) -> Arc<dyn NotificationServiceTrait> {
+        Arc::new(Self {
+            http_client,
+            webhooks,
+        })
+    }
+
+    fn build_request(webhook: &WebhookConfig, message: &ContactMessage, now: i64) -> HttpRequest {
+        match webhook.kind {
+            WebhookKind::Slack => HttpRequest::post_json(&webhook.url, &slack_payload(message)),
+            WebhookKind::Discord => HttpRequest::post_json(&webhook.url, &discord_payload(message)),
+            WebhookKind::Generic => {
+                let payload = generic_payload(message);
+                let mut request = HttpRequest::post_json(&webhook.url, &payload)
+                    .with_header("X-Webhook-Timestamp", now.to_string());
+                if let Some(secret) = &webhook.secret {
+                    let signature = sign_payload(secret, now, &payload.to_string());
+                    request = request.with_header("X-Webhook-Signature", signature);
+                }
+                request
+            }
+        }
+    }
+
+    async fn deliver(&self, webhook: &WebhookConfig, message: &ContactMessage) -> bool {
+        let request = Self::build_request(webhook, message, Utc::now().timestamp());
+
+        match self.http_client.send(request).await {
+            Ok(response) if response.is_success() => true,
+            Ok(response) => {
+                log_error!(
+                    "{:?} webhook returned {} for message {}",
+                    webhook.kind,
+                    response.status,
+                    message.id
+                );
+                false
+            }
+            Err(e) => {
+                log_error!(
+                    "{:?} webhook request failed for message {}: {}",
+                    webhook.kind,
+                    message.id,
+                    e
+                );
+                false
+            }
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl NotificationServiceTrait for WebhookNotificationService {
+    /// Attempts every matching webhook, failing if any delivery failed.
+    async fn notify(&self, message: &ContactMessage) -> Result<(), AppError> {
+        let category = message.category.to_string();
+        let mut all_delivered = true;
+
+        for webhook in self.webhooks.iter().filter(|w| w.accepts(&category)) {
+            all_delivered &= self.deliver(webhook, message).await;
+        }
+
+        if all_delivered {
+            Ok(())
+        } else {
+            Err(AppError::InternalError)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::enums::ContactMessageCategory;
+    use crate::domain::form_schema::FormSchema;
+    use crate::infrastructure::http_client::test_support::ReqwestHttpClient;
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{body_partial_json, header_exists, method, path},
+    };
+
+    fn message(category: ContactMessageCategory) -> ContactMessage {
+        ContactMessage::create(
+            category,
+            "user@example.com".to_string(),
+            "Jane <Smith>".to_string(),
+            "Something is broken".to_string(),
+            None,
+            &FormSchema::unstructured(),
+        )
+        .unwrap()
+    }
+
+    fn webhook(kind: WebhookKind, url: String, categories: Option<Vec<&str>>) -> WebhookConfig {
+        WebhookConfig {
+            kind,
+            url,
+            categories: categories.map(|c| c.into_iter().map(String::from).collect()),
+            secret: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_webhook_config() {
+        let webhooks = WebhookConfig::parse_list(
+            r#"[
+                {"kind": "slack", "url": "https://hooks.slack.com/x", "categories": ["ERROR"]},
+                {"kind": "generic", "url": "https://example.com/hook", "secret": "s3cret"}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(webhooks.len(), 2);
+        assert_eq!(webhooks[0].kind, WebhookKind::Slack);
+        assert!(webhooks[0].accepts("error"));
+        assert!(!webhooks[0].accepts("IDEA"));
+        assert!(webhooks[1].accepts("IDEA"));
+        assert_eq!(webhooks[1].secret.as_deref(), Some("s3cret"));
+
+        assert!(WebhookConfig::parse_list("").unwrap().is_empty());
+        assert!(WebhookConfig::parse_list(r#"[{"kind": "teams", "url": "x"}]"#).is_err());
+    }
+
+    #[test]
+    fn test_sign_payload() {
+        let signature = sign_payload("secret", 1700000000, r#"{"a":1}"#);
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"secret").unwrap();
+        mac.update(br#"1700000000.{"a":1}"#);
+        let expected = format!("sha256={}", hex::encode(mac.finalize().into_bytes()));
+
+        assert_eq!(signature, expected);
+        assert_ne!(signature, sign_payload("secret", 1700000001, r#"{"a":1}"#));
+        assert_ne!(signature, sign_payload("other", 1700000000, r#"{"a":1}"#));
+    }
+
+    #[test]
+    fn test_slack_payload_escapes_and_truncates() {
+        let mut message = message(ContactMessageCategory::ERROR);
+        message.message = "a".repeat(SLACK_TEXT_LIMIT + 10);
+
+        let payload = slack_payload(&message);
+
+        assert_eq!(
+            payload["blocks"][1]["fields"][0]["text"],
+            "*Name:*\nJane &lt;Smith&gt;"
+        );
+        let text = payload["blocks"][2]["text"]["text"].as_str().unwrap();
+        assert_eq!(text.chars().count(), SLACK_TEXT_LIMIT + 1);
+        assert!(text.ends_with('…'));
+    }
+
+    #[test]
+    fn test_discord_payload() {
+        let message = message(ContactMessageCategory::IDEA);
+
+        let payload = discord_payload(&message);
+        let embed = &payload["embeds"][0];
+
+        assert_eq!(embed["title"], "New IDEA message");
+        assert_eq!(embed["description"], "Something is broken");
+        assert_eq!(embed["fields"][1]["value"], "user@example.com");
+        assert_eq!(
+            embed["footer"]["text"],
+            format!("Reference: {}", message.id)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_notify_fans_out_to_matching_webhooks() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/slack"))
+            .and(body_partial_json(
+                json!({ "text": "New ERROR message from Jane <Smith>" }),
+            ))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/discord"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/ideas-only"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let service = WebhookNotificationService::create(
+            ReqwestHttpClient::create(),
+            vec![
+                webhook(WebhookKind::Slack, format!("{}/slack", server.uri()), None),
+                webhook(
+                    WebhookKind::Discord,
+                    format!("{}/discord", server.uri()),
+                    Some(vec!["ERROR"]),
+                ),
+                webhook(
+                    WebhookKind::Slack,
+                    format!("{}/ideas-only", server.uri()),
+                    Some(vec!["IDEA"]),
+                ),
+            ],
+        );
+
+        let result = service
+            .notify(&message(ContactMessageCategory::ERROR))
+            .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_generic_webhook_is_signed() {
+        let server = MockServer::start().await;
+        let message = message(ContactMessageCategory::OTHER);
+
+        Mock::given(method("POST"))
+            .and(path("/generic"))
+            .and(header_exists("X-Webhook-Timestamp"))
+            .and(header_exists("X-Webhook-Signature"))
+            .and(body_partial_json(json!({
+                "event": "contact_message.created",
+                "message": { "id": &message.id, "category": "OTHER" }
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut generic = webhook(
+            WebhookKind::Generic,
+            format!("{}/generic", server.uri()),
+            None,
+        );
+        generic.secret = Some("s3cret".to_string());
+        let service =
+            WebhookNotificationService::create(ReqwestHttpClient::create(), vec![generic]);
+
+        assert!(service.notify(&message).await.is_ok());
+
+        let request = &server.received_requests().await.unwrap()[0];
+        let timestamp: i64 = request.headers["X-Webhook-Timestamp"]
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        let body = String::from_utf8(request.body.clone()).unwrap();
+        assert_eq!(
+            request.headers["X-Webhook-Signature"].to_str().unwrap(),
+            sign_payload("s3cret", timestamp, &body)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_delivery_does_not_stop_other_webhooks() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/broken"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/working"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let service = WebhookNotificationService::create(
+            ReqwestHttpClient::create(),
+            vec![
+                webhook(WebhookKind::Slack, format!("{}/broken", server.uri()), None),
+                webhook(
+                    WebhookKind::Generic,
+                    format!("{}/working", server.uri()),
+                    None,
+                ),
+            ],
+        );
+
+        let result = service
+            .notify(&message(ContactMessageCategory::ERROR))
+            .await;
+        assert!(matches!(result, Err(AppError::InternalError)));
+    }
+}
@@ -2,10 +2,20 @@ pub mod api;
 pub mod application;
 pub mod domain;
 pub mod infrastructure;
+pub mod logging;
 pub mod setup;
 
+use chrono::Utc;
 use worker::*;
 
+use crate::{
+    application::outbox_processor::DEFAULT_BATCH_SIZE, domain::events::DomainEvent,
+    setup::app_state::AppState,
+};
+
+/// Delay before the queue redelivers a message whose processing failed.
+const QUEUE_RETRY_DELAY_SECONDS: u32 = 30;
+
 #[event(fetch)]
 async fn fetch(req: Request, env: Env, _ctx: Context) -> Result<Response> {
     console_log!("Worker handling request: {} {}", req.method(), req.path());
@@ -13,3 +23,74 @@ async fn fetch(req: Request, env: Env, _ctx: Context) -> Result<Response> {
     let router = api::router::create_router();
     router.run(req, env).await
 }
+
+#[event(scheduled)]
+async fn scheduled(_event: ScheduledEvent, env: Env, _ctx: ScheduleContext) {
+    let app_state = match AppState::from_env(&env) {
+        Ok(state) => state,
+        Err(e) => {
+            console_error!("Failed to initialise app state for outbox drain: {}", e);
+            return;
+        }
+    };
+
+    match app_state
+        .outbox_processor
+        .drain(Utc::now().timestamp(), DEFAULT_BATCH_SIZE)
+        .await
+    {
+        Ok(summary) => console_log!(
+            "Outbox drain: {} delivered, {} retrying, {} dead",
+            summary.delivered,
+            summary.retrying,
+            summary.dead
+        ),
+        Err(e) => console_error!("Outbox drain failed: {}", e),
+    }
+
+    if let Err(e) = app_state
+        .contact_message_service
+        .purge_expired_idempotency_keys(Utc::now().timestamp())
+        .await
+    {
+        console_error!("Failed to purge expired idempotency keys: {}", e);
+    }
+}
+
+#[event(queue)]
+async fn queue(batch: MessageBatch<serde_json::Value>, env: Env, _ctx: Context) -> Result<()> {
+    let app_state = AppState::from_env(&env).map_err(Error::RustError)?;
+    let retry_options = QueueRetryOptionsBuilder::new()
+        .with_delay_seconds(QUEUE_RETRY_DELAY_SECONDS)
+        .build();
+
+    for message in batch.iter() {
+        let message = match message {
+            Ok(message) => message,
+            Err(e) => {
+                console_error!("Failed to read queue message: {}", e);
+                continue;
+            }
+        };
+
+        // Malformed events can never succeed, so they are acknowledged rather than retried.
+        let event: DomainEvent = match serde_json::from_value(message.body().clone()) {
+            Ok(event) => event,
+            Err(e) => {
+                console_error!("Dropping invalid event {}: {}", message.id(), e);
+                message.ack();
+                continue;
+            }
+        };
+
+        match app_state.event_consumer.handle(&event).await {
+            Ok(()) => message.ack(),
+            Err(e) => {
+                console_error!("Retrying event {}: {}", event.event_id(), e);
+                message.retry_with_options(&retry_options);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,29 @@
+//! `console_log!`/`console_error!` wrappers that fall back to stdout/stderr
+//! outside of the Workers runtime, so services logging through them can be
+//! exercised by native unit tests.
+//!
+//! Application services and the infrastructure they are tested with log
+//! through these macros. Code that only runs inside the Workers runtime
+//! (route handlers, `AppState`, the event handlers in `lib.rs` and the
+//! Durable Object and queue bindings) uses `console_*` directly, and a module
+//! never mixes the two.
+
+#[macro_export]
+macro_rules! log_info {
+    ($($t:tt)*) => {{
+        #[cfg(target_arch = "wasm32")]
+        worker::console_log!($($t)*);
+        #[cfg(not(target_arch = "wasm32"))]
+        println!($($t)*);
+    }};
+}
+
+#[macro_export]
+macro_rules! log_error {
+    ($($t:tt)*) => {{
+        #[cfg(target_arch = "wasm32")]
+        worker::console_error!($($t)*);
+        #[cfg(not(target_arch = "wasm32"))]
+        eprintln!($($t)*);
+    }};
+}
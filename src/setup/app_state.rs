@@ -1,42 +1,270 @@
 use std::sync::Arc;
 
-use worker::{Env, console_error};
+use worker::{D1Database, Env, console_error};
 
 use crate::{
     application::{
+        authentication_service::{AuthenticationChain, AuthenticationServiceTrait},
+        auto_reply::{AutoReplyStep, AutoReplyTemplates},
+        category_service::CategoryService,
         contact_message_service::ContactMessageService,
-        request_validation_service::RequestValidationServiceTrait,
+        enrichment::EnrichmentStep,
+        event_consumer::{EventConsumer, NotificationStep, PostProcessingStepTrait},
+        notification_service::{NotificationServiceTrait, RecipientRouting},
+        outbox_processor::OutboxProcessor,
+        rate_limiter::RateLimitService,
+        request_validation_service::{
+            FailurePolicyRequestValidationService, RequestValidationServiceTrait,
+        },
+        site_service::SiteService,
+        spam_scoring::{SpamScorer, SpamScoringStep},
     },
     infrastructure::{
+        api_key_authentication_service::ApiKeyAuthenticationService,
+        category_repository::CategoryRepository,
+        cloudflare_access_authentication_service::CloudflareAccessAuthenticationService,
         contact_message_repository::ContactMessageRepository,
-        request_validation_service::CloudflareRequestValidationService,
+        durable_object_rate_limiter::DurableObjectRateLimiter,
+        email_notification_service::EmailNotificationService,
+        form_repository::FormRepository,
+        form_token_repository::FormTokenRepository,
+        hcaptcha_request_validation_service::HCaptchaRequestValidationService,
+        honeypot_request_validation_service::{FormTokenSigner, HoneypotRequestValidationService},
+        http_client::WorkerHttpClient,
+        http_email_provider::HttpEmailProvider,
+        idempotency_repository::IdempotencyRepository,
+        outbox_repository::OutboxRepository,
+        queue_event_publisher::QueueEventPublisher,
+        recaptcha_v3_request_validation_service::RecaptchaV3RequestValidationService,
+        request_validation_service::{CloudflareRequestValidationService, TurnstilePolicy},
+        retrying_http_client::{RetryingHttpClient, WorkerSleeper},
+        site_repository::SiteRepository,
+        webhook_notification_service::{WebhookConfig, WebhookNotificationService},
     },
-    setup::config::Config,
+    setup::config::{CaptchaProvider, Config},
 };
 
 pub struct AppState {
     pub contact_message_service: ContactMessageService,
+    pub outbox_processor: Arc<OutboxProcessor>,
+    pub event_consumer: EventConsumer,
     pub request_validation_service: Arc<dyn RequestValidationServiceTrait>,
+    pub authentication_service: Arc<dyn AuthenticationServiceTrait>,
+    pub rate_limit_service: RateLimitService,
+    pub site_service: SiteService,
+    pub category_service: Arc<CategoryService>,
+    /// Issues form tokens; `None` when no `FORM_TOKEN_SECRET` is configured.
+    pub form_token_signer: Option<FormTokenSigner>,
 }
 
 impl AppState {
     pub fn from_env(env: &Env) -> Result<Self, String> {
         let config = Config::from_env(env)?;
 
-        let db = env.d1("DB").map_err(|e| {
-            console_error!("Failed to get D1 binding: {:?}", e);
-            "Database unavailable".to_string()
-        })?;
+        let db = Self::database(env)?;
+
+        let http_client = WorkerHttpClient::create();
+        // Siteverify calls are safe to repeat, unlike notification deliveries,
+        // which the queue retries instead.
+        let captcha_http_client = RetryingHttpClient::create(
+            http_client.clone(),
+            config.captcha_retry_policy,
+            WorkerSleeper::create(),
+        );
+
+        let form_token_signer = config.form_token_secret.map(|secret| {
+            FormTokenSigner::new(
+                secret,
+                config.form_token_min_age_seconds,
+                config.form_token_max_age_seconds,
+            )
+        });
 
-        let request_validation_service =
-            CloudflareRequestValidationService::create(config.siteverify_url, config.secret_key);
+        let captcha_service = match config.captcha_provider {
+            CaptchaProvider::Turnstile => {
+                let secret_key = config
+                    .secret_key
+                    .ok_or_else(|| "Missing secret key".to_string())?;
+                CloudflareRequestValidationService::create(
+                    captcha_http_client.clone(),
+                    config.siteverify_url,
+                    secret_key,
+                    TurnstilePolicy {
+                        allowed_hostnames: config.turnstile_allowed_hostnames,
+                        expected_action: config.turnstile_expected_action,
+                        expected_cdata: config.turnstile_expected_cdata,
+                        max_token_age_seconds: config.turnstile_max_token_age_seconds,
+                    },
+                )
+            }
+            CaptchaProvider::HCaptcha => {
+                let secret_key = config
+                    .hcaptcha_secret_key
+                    .ok_or_else(|| "Missing hCaptcha secret key".to_string())?;
+                HCaptchaRequestValidationService::create(
+                    captcha_http_client.clone(),
+                    config.hcaptcha_siteverify_url,
+                    secret_key,
+                    config.hcaptcha_site_key,
+                )
+            }
+            CaptchaProvider::RecaptchaV3 => {
+                let secret_key = config
+                    .recaptcha_secret_key
+                    .ok_or_else(|| "Missing reCAPTCHA secret key".to_string())?;
+                RecaptchaV3RequestValidationService::create(
+                    captcha_http_client.clone(),
+                    config.recaptcha_siteverify_url,
+                    secret_key,
+                    config.recaptcha_min_score,
+                    config.recaptcha_expected_action,
+                )
+            }
+            CaptchaProvider::Honeypot => {
+                let signer = form_token_signer
+                    .clone()
+                    .ok_or_else(|| "Missing form token secret".to_string())?;
+                HoneypotRequestValidationService::create(
+                    signer,
+                    FormTokenRepository::create(Self::database(env)?),
+                )
+            }
+        };
+        let request_validation_service = FailurePolicyRequestValidationService::create(
+            captcha_service,
+            config.captcha_failure_policy,
+        );
+
+        // A bad key list disables API key authentication rather than every route.
+        let mut authentication_services = vec![];
+        match ApiKeyAuthenticationService::create(&config.admin_api_keys) {
+            Ok(service) => authentication_services.push(service),
+            Err(e) => console_error!("Invalid ADMIN_API_KEYS configuration: {}", e),
+        }
+        if let (Some(team_domain), Some(audience)) =
+            (config.access_team_domain, config.access_audience)
+        {
+            authentication_services.push(CloudflareAccessAuthenticationService::create(
+                http_client.clone(),
+                team_domain,
+                audience,
+            ));
+        }
+        let authentication_service = AuthenticationChain::create(authentication_services);
+
+        let rate_limiter_namespace = env.durable_object("RATE_LIMITER").map_err(|e| {
+            console_error!("Failed to get rate limiter binding: {:?}", e);
+            "Rate limiter unavailable".to_string()
+        })?;
+        let rate_limit_service = RateLimitService::create(
+            DurableObjectRateLimiter::create(rate_limiter_namespace),
+            config.rate_limit_per_ip,
+            config.rate_limit_per_email,
+        );
 
         let contact_message_repository = ContactMessageRepository::create(db);
-        let contact_message_service = ContactMessageService::create(contact_message_repository);
+        let outbox_repository = OutboxRepository::create(Self::database(env)?);
+        let site_repository = SiteRepository::create(Self::database(env)?);
+        let email_provider = match (
+            config.email_api_url,
+            config.email_api_key,
+            config.email_from,
+        ) {
+            (Some(api_url), Some(api_key), Some(from)) => Some(HttpEmailProvider::create(
+                http_client.clone(),
+                api_url,
+                api_key,
+                from,
+            )),
+            _ => None,
+        };
+
+        let mut notification_services: Vec<Arc<dyn NotificationServiceTrait>> = vec![];
+        // Bad notification settings disable that notifier, not the submit endpoint.
+        if let Some(email_provider) = &email_provider {
+            match RecipientRouting::parse(&config.notification_recipients) {
+                Ok(routing) => notification_services.push(EmailNotificationService::create(
+                    email_provider.clone(),
+                    routing,
+                    site_repository.clone(),
+                )),
+                Err(e) => console_error!("Invalid NOTIFICATION_RECIPIENTS configuration: {}", e),
+            }
+        }
+
+        match WebhookConfig::parse_list(&config.webhooks) {
+            Ok(webhooks) if !webhooks.is_empty() => notification_services.push(
+                WebhookNotificationService::create(http_client.clone(), webhooks),
+            ),
+            Ok(_) => {}
+            Err(e) => console_error!("Invalid WEBHOOKS configuration: {}", e),
+        }
+
+        let spam_scorer = SpamScorer::create(
+            SpamScorer::default_rules(&config.spam_expected_locale, &config.spam_keywords),
+            config.spam_threshold,
+        );
+        // Scoring runs first so that messages marked as spam are never notified.
+        let mut steps: Vec<Arc<dyn PostProcessingStepTrait>> = vec![
+            SpamScoringStep::create(contact_message_repository.clone(), spam_scorer),
+            EnrichmentStep::create(contact_message_repository.clone()),
+            NotificationStep::create(notification_services),
+        ];
+
+        // Like notifications, bad auto-reply settings only disable auto-replies.
+        match (
+            AutoReplyTemplates::parse(&config.auto_reply_templates),
+            email_provider,
+        ) {
+            (Ok(templates), _) if templates.is_empty() => {}
+            (Ok(templates), Some(email_provider)) => steps.push(AutoReplyStep::create(
+                contact_message_repository.clone(),
+                email_provider,
+                templates,
+            )),
+            (Ok(_), None) => console_error!("AUTO_REPLY_TEMPLATES requires an email provider"),
+            (Err(e), _) => console_error!("Invalid AUTO_REPLY_TEMPLATES configuration: {}", e),
+        }
+        let event_consumer = EventConsumer::create(contact_message_repository.clone(), steps);
+
+        let queue = env.queue("CONTACT_EVENTS").map_err(|e| {
+            console_error!("Failed to get queue binding: {:?}", e);
+            "Event queue unavailable".to_string()
+        })?;
+        let outbox_processor = Arc::new(OutboxProcessor::create(
+            outbox_repository,
+            QueueEventPublisher::create(queue),
+        ));
+        let category_service = Arc::new(CategoryService::create(CategoryRepository::create(
+            Self::database(env)?,
+        )));
+        let contact_message_service = ContactMessageService::create(
+            contact_message_repository,
+            outbox_processor.clone(),
+            IdempotencyRepository::create(Self::database(env)?),
+            FormRepository::create(Self::database(env)?),
+            category_service.clone(),
+            config.duplicate_detection,
+        );
 
         Ok(Self {
             contact_message_service,
+            outbox_processor,
+            event_consumer,
             request_validation_service,
+            authentication_service,
+            rate_limit_service,
+            site_service: SiteService::create(site_repository),
+            category_service,
+            form_token_signer,
+        })
+    }
+
+    /// `D1Database` is not `Clone`, so each repository takes its own handle to the binding.
+    fn database(env: &Env) -> Result<D1Database, String> {
+        env.d1("DB").map_err(|e| {
+            console_error!("Failed to get D1 binding: {:?}", e);
+            "Database unavailable".to_string()
         })
     }
 }
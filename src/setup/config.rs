@@ -1,22 +1,166 @@
-use worker::Env;
+use std::{str::FromStr, time::Duration};
+
+use worker::{Env, Url};
+
+use crate::{
+    application::{
+        contact_message_service::{DuplicateDetection, DuplicatePolicy},
+        rate_limiter::RateLimit,
+        request_validation_service::FailurePolicy,
+        spam_scoring::SpamScorer,
+    },
+    infrastructure::{
+        hcaptcha_request_validation_service::HCAPTCHA_SITEVERIFY_URL,
+        recaptcha_v3_request_validation_service::{
+            DEFAULT_RECAPTCHA_MIN_SCORE, RECAPTCHA_SITEVERIFY_URL,
+        },
+        request_validation_service::DEFAULT_TURNSTILE_MAX_TOKEN_AGE_SECONDS,
+        retrying_http_client::RetryPolicy,
+    },
+};
+
+/// Which anti-bot check `POST /api/v1/contact-us/` requires.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CaptchaProvider {
+    Turnstile,
+    HCaptcha,
+    RecaptchaV3,
+    /// Honeypot field plus a signed form-render timestamp.
+    Honeypot,
+}
+
+impl FromStr for CaptchaProvider {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "turnstile" => Ok(CaptchaProvider::Turnstile),
+            "hcaptcha" => Ok(CaptchaProvider::HCaptcha),
+            "recaptcha_v3" => Ok(CaptchaProvider::RecaptchaV3),
+            "honeypot" => Ok(CaptchaProvider::Honeypot),
+            _ => Err(format!("Unknown CAPTCHA_PROVIDER '{s}'")),
+        }
+    }
+}
 
 pub struct Config {
+    pub captcha_provider: CaptchaProvider,
     pub siteverify_url: String,
-    pub secret_key: String,
+    pub secret_key: Option<String>,
+    /// The public key clients render the Turnstile widget with.
+    pub turnstile_site_key: Option<String>,
+    pub turnstile_allowed_hostnames: Vec<String>,
+    pub turnstile_expected_action: Option<String>,
+    pub turnstile_expected_cdata: Option<String>,
+    pub turnstile_max_token_age_seconds: i64,
+    pub hcaptcha_siteverify_url: String,
+    pub hcaptcha_secret_key: Option<String>,
+    pub hcaptcha_site_key: Option<String>,
+    pub recaptcha_siteverify_url: String,
+    pub recaptcha_secret_key: Option<String>,
+    pub recaptcha_min_score: f64,
+    pub recaptcha_expected_action: Option<String>,
+    pub captcha_retry_policy: RetryPolicy,
+    pub captcha_failure_policy: FailurePolicy,
+    pub form_token_secret: Option<String>,
+    pub form_token_min_age_seconds: i64,
+    pub form_token_max_age_seconds: i64,
     pub allowed_origins: Vec<String>,
+    pub thank_you_url: Option<Url>,
+    pub admin_api_keys: Vec<String>,
+    pub access_team_domain: Option<String>,
+    pub access_audience: Option<String>,
+    pub email_api_url: Option<String>,
+    pub email_api_key: Option<String>,
+    pub email_from: Option<String>,
+    pub notification_recipients: String,
+    pub webhooks: String,
+    pub auto_reply_templates: String,
+    pub rate_limit_per_ip: Option<RateLimit>,
+    pub rate_limit_per_email: Option<RateLimit>,
+    pub spam_threshold: u32,
+    pub spam_expected_locale: String,
+    pub spam_keywords: Vec<String>,
+    pub duplicate_detection: Option<DuplicateDetection>,
 }
 
 impl Config {
     pub fn from_env(env: &Env) -> Result<Self, String> {
+        let captcha_provider = Self::optional(env, "CAPTCHA_PROVIDER")
+            .map(|v| v.parse())
+            .transpose()?
+            .unwrap_or(CaptchaProvider::Turnstile);
+
         let siteverify_url = env
             .var("TURNSTILE_SITEVERIFY_URL")
             .map_err(|_| "Missing siteverify URL".to_string())?
             .to_string();
 
-        let secret_key = env
-            .secret("TURNSTILE_SECRET_KEY")
-            .map_err(|_| "Missing secret key".to_string())?
-            .to_string();
+        let secret_key = Self::optional_secret(env, "TURNSTILE_SECRET_KEY");
+
+        let turnstile_site_key = Self::optional(env, "TURNSTILE_SITE_KEY");
+
+        let turnstile_allowed_hostnames = env
+            .var("TURNSTILE_ALLOWED_HOSTNAMES")
+            .map(|v| {
+                v.to_string()
+                    .split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            })
+            .unwrap_or_else(|_| vec![]);
+
+        let turnstile_expected_action = Self::optional(env, "TURNSTILE_EXPECTED_ACTION");
+        let turnstile_expected_cdata = Self::optional(env, "TURNSTILE_EXPECTED_CDATA");
+
+        let turnstile_max_token_age_seconds = Self::number(env, "TURNSTILE_MAX_TOKEN_AGE_SECONDS")?
+            .unwrap_or(DEFAULT_TURNSTILE_MAX_TOKEN_AGE_SECONDS);
+
+        let hcaptcha_siteverify_url = Self::optional(env, "HCAPTCHA_SITEVERIFY_URL")
+            .unwrap_or_else(|| HCAPTCHA_SITEVERIFY_URL.to_string());
+
+        let hcaptcha_secret_key = Self::optional_secret(env, "HCAPTCHA_SECRET_KEY");
+
+        let hcaptcha_site_key = Self::optional(env, "HCAPTCHA_SITE_KEY");
+
+        let recaptcha_siteverify_url = Self::optional(env, "RECAPTCHA_SITEVERIFY_URL")
+            .unwrap_or_else(|| RECAPTCHA_SITEVERIFY_URL.to_string());
+
+        let recaptcha_secret_key = Self::optional_secret(env, "RECAPTCHA_SECRET_KEY");
+
+        let recaptcha_min_score = Self::optional(env, "RECAPTCHA_MIN_SCORE")
+            .map(|v| match v.parse::<f64>() {
+                Ok(score) if (0.0..=1.0).contains(&score) => Ok(score),
+                _ => Err(format!(
+                    "Invalid RECAPTCHA_MIN_SCORE: {v}, expected a number from 0.0 to 1.0"
+                )),
+            })
+            .transpose()?
+            .unwrap_or(DEFAULT_RECAPTCHA_MIN_SCORE);
+
+        let recaptcha_expected_action = Self::optional(env, "RECAPTCHA_EXPECTED_ACTION");
+
+        let captcha_retry_policy = Self::captcha_retry_policy(env)?;
+
+        let captcha_failure_policy = Self::optional(env, "CAPTCHA_FAILURE_POLICY")
+            .map(|v| {
+                v.parse::<FailurePolicy>()
+                    .map_err(|e| format!("Invalid CAPTCHA_FAILURE_POLICY: {e}"))
+            })
+            .transpose()?
+            .unwrap_or(FailurePolicy::Closed);
+
+        let form_token_secret = Self::optional_secret(env, "FORM_TOKEN_SECRET");
+
+        let form_token_min_age_seconds =
+            Self::number(env, "FORM_TOKEN_MIN_AGE_SECONDS")?.unwrap_or(3);
+        let form_token_max_age_minutes =
+            Self::number(env, "FORM_TOKEN_MAX_AGE_MINUTES")?.unwrap_or(60);
+        let form_token_max_age_seconds =
+            form_token_max_age_minutes.checked_mul(60).ok_or_else(|| {
+                format!("Invalid FORM_TOKEN_MAX_AGE_MINUTES: {form_token_max_age_minutes}")
+            })?;
 
         let allowed_origins = env
             .var("ALLOWED_ORIGINS")
@@ -28,10 +172,181 @@ impl Config {
             })
             .unwrap_or_else(|_| vec![]);
 
+        let thank_you_url = Self::optional(env, "THANK_YOU_URL")
+            .map(|v| Url::parse(&v).map_err(|e| format!("Invalid THANK_YOU_URL: {e}")))
+            .transpose()?;
+
+        let admin_api_keys = env
+            .secret("ADMIN_API_KEYS")
+            .map(|v| {
+                v.to_string()
+                    .split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            })
+            .unwrap_or_else(|_| vec![]);
+
+        let access_team_domain = Self::optional(env, "CF_ACCESS_TEAM_DOMAIN");
+
+        let access_audience = Self::optional(env, "CF_ACCESS_AUD");
+
+        let email_api_url = Self::optional(env, "EMAIL_API_URL");
+
+        let email_api_key = Self::optional_secret(env, "EMAIL_API_KEY");
+
+        let email_from = Self::optional(env, "EMAIL_FROM");
+
+        let notification_recipients = env
+            .var("NOTIFICATION_RECIPIENTS")
+            .map(|v| v.to_string())
+            .unwrap_or_default();
+
+        let webhooks = env
+            .secret("WEBHOOKS")
+            .map(|v| v.to_string())
+            .unwrap_or_default();
+
+        let auto_reply_templates = Self::optional(env, "AUTO_REPLY_TEMPLATES").unwrap_or_default();
+
+        let rate_limit_per_ip = Self::rate_limit(env, "RATE_LIMIT_PER_IP")?;
+        let rate_limit_per_email = Self::rate_limit(env, "RATE_LIMIT_PER_EMAIL")?;
+
+        let spam_threshold = Self::optional(env, "SPAM_THRESHOLD")
+            .map(|v| {
+                v.parse()
+                    .map_err(|_| format!("Invalid SPAM_THRESHOLD: {v}"))
+            })
+            .transpose()?
+            .unwrap_or(SpamScorer::DEFAULT_THRESHOLD);
+
+        let spam_expected_locale =
+            Self::optional(env, "SPAM_EXPECTED_LOCALE").unwrap_or_else(|| "en".to_string());
+
+        let spam_keywords = env
+            .var("SPAM_KEYWORDS")
+            .map(|v| {
+                v.to_string()
+                    .split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            })
+            .unwrap_or_else(|_| vec![]);
+
+        let duplicate_detection = Self::duplicate_detection(env)?;
+
         Ok(Config {
+            captcha_provider,
             siteverify_url,
             secret_key,
+            turnstile_site_key,
+            turnstile_allowed_hostnames,
+            turnstile_expected_action,
+            turnstile_expected_cdata,
+            turnstile_max_token_age_seconds,
+            hcaptcha_siteverify_url,
+            hcaptcha_secret_key,
+            hcaptcha_site_key,
+            recaptcha_siteverify_url,
+            recaptcha_secret_key,
+            recaptcha_min_score,
+            recaptcha_expected_action,
+            captcha_retry_policy,
+            captcha_failure_policy,
+            form_token_secret,
+            form_token_min_age_seconds,
+            form_token_max_age_seconds,
             allowed_origins,
+            thank_you_url,
+            admin_api_keys,
+            access_team_domain,
+            access_audience,
+            email_api_url,
+            email_api_key,
+            email_from,
+            notification_recipients,
+            webhooks,
+            auto_reply_templates,
+            rate_limit_per_ip,
+            rate_limit_per_email,
+            spam_threshold,
+            spam_expected_locale,
+            spam_keywords,
+            duplicate_detection,
         })
     }
+
+    /// An unset variable and an empty one both read as `None`.
+    fn optional(env: &Env, name: &str) -> Option<String> {
+        env.var(name)
+            .map(|v| v.to_string())
+            .ok()
+            .filter(|v| !v.is_empty())
+    }
+
+    fn optional_secret(env: &Env, name: &str) -> Option<String> {
+        env.secret(name)
+            .map(|v| v.to_string())
+            .ok()
+            .filter(|v| !v.is_empty())
+    }
+
+    fn number(env: &Env, name: &str) -> Result<Option<i64>, String> {
+        Self::optional(env, name)
+            .map(|v| v.parse().map_err(|_| format!("Invalid {name}: {v}")))
+            .transpose()
+    }
+
+    /// Each siteverify attempt gets `CAPTCHA_TIMEOUT_MS`, and failed attempts
+    /// are retried up to `CAPTCHA_MAX_RETRIES` times.
+    fn captcha_retry_policy(env: &Env) -> Result<RetryPolicy, String> {
+        let defaults = RetryPolicy::default();
+
+        let timeout = match Self::number(env, "CAPTCHA_TIMEOUT_MS")? {
+            Some(ms) if ms > 0 => Duration::from_millis(ms as u64),
+            Some(ms) => return Err(format!("Invalid CAPTCHA_TIMEOUT_MS: {ms}")),
+            None => defaults.timeout,
+        };
+        let max_retries = match Self::number(env, "CAPTCHA_MAX_RETRIES")? {
+            Some(retries) => u32::try_from(retries)
+                .map_err(|_| format!("Invalid CAPTCHA_MAX_RETRIES: {retries}"))?,
+            None => defaults.max_retries,
+        };
+
+        Ok(RetryPolicy {
+            timeout,
+            max_retries,
+            ..defaults
+        })
+    }
+
+    /// Duplicate detection is off unless a window above `0` seconds is set.
+    fn duplicate_detection(env: &Env) -> Result<Option<DuplicateDetection>, String> {
+        let Some(window_seconds) =
+            Self::number(env, "DUPLICATE_WINDOW_SECONDS")?.filter(|seconds| *seconds > 0)
+        else {
+            return Ok(None);
+        };
+
+        let policy = Self::optional(env, "DUPLICATE_POLICY")
+            .map(|v| {
+                v.parse::<DuplicatePolicy>()
+                    .map_err(|e| format!("Invalid DUPLICATE_POLICY: {e}"))
+            })
+            .transpose()?
+            .unwrap_or(DuplicatePolicy::Collapse);
+
+        Ok(Some(DuplicateDetection {
+            window_seconds,
+            policy,
+        }))
+    }
+
+    /// An unset or empty variable disables the limit.
+    fn rate_limit(env: &Env, name: &str) -> Result<Option<RateLimit>, String> {
+        Self::optional(env, name)
+            .map(|v| RateLimit::parse(&v).map_err(|e| format!("Invalid {name}: {e}")))
+            .transpose()
+    }
 }
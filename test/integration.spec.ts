@@ -29,8 +29,10 @@ describe("Contact Us Worker", () => {
 
     expect(response.status).toBe(200);
     expect(response.headers.get("Access-Control-Allow-Origin")).toBe("http://localhost:5173");
-    expect(response.headers.get("Access-Control-Allow-Methods")).toBe("POST, OPTIONS");
-    expect(response.headers.get("Access-Control-Allow-Headers")).toBe("Content-Type");
+    expect(response.headers.get("Access-Control-Allow-Methods")).toBe("GET, POST, OPTIONS");
+    expect(response.headers.get("Access-Control-Allow-Headers")).toBe(
+      "Content-Type, Idempotency-Key",
+    );
   });
 
   it("rejects POST request without required fields", async () => {
@@ -43,14 +45,17 @@ describe("Contact Us Worker", () => {
       body: JSON.stringify({}),
     });
 
+    const problem: any = await response.json();
     expect(response.status).toBe(400);
+    expect(response.headers.get("Content-Type")).toBe("application/problem+json");
+    expect(problem.code).toBe("invalid_body");
   });
 
   it("accepts valid POST request with all required fields", async () => {
     fetchMock
       .get("https://test.com")
       .intercept({ method: "POST", path: "/turnstile/v0/siteverify" })
-      .reply(200, JSON.stringify({ success: true }));
+      .reply(200, JSON.stringify({ success: true, challenge_ts: new Date().toISOString() }));
 
     const response = await SELF.fetch("http://example.com/api/v1/contact-us/", {
       method: "POST",
@@ -118,6 +123,353 @@ describe("Contact Us Worker", () => {
       }),
     });
 
+    const data: any = await response.json();
     expect(response.status).toBe(401);
+    expect(data.code).toBe("token_invalid");
   });
-});
\ No newline at end of file
+
+  it("accepts urlencoded form posts and redirects to the thank-you page", async () => {
+    fetchMock
+      .get("https://test.com")
+      .intercept({ method: "POST", path: "/turnstile/v0/siteverify" })
+      .reply(200, JSON.stringify({ success: true, challenge_ts: new Date().toISOString() }));
+
+    const response = await SELF.fetch("http://example.com/api/v1/contact-us/", {
+      method: "POST",
+      headers: { Origin: "http://localhost:5173" },
+      body: new URLSearchParams({
+        category: "IDEA",
+        email: "form@example.com",
+        name: "Form User",
+        message: "Sent without JavaScript",
+        "data[source]": "footer",
+        "cf-turnstile-response": "test-token",
+      }),
+      redirect: "manual",
+    });
+
+    expect(response.status).toBe(303);
+    expect(response.headers.get("Location")).toBe("http://localhost:5173/thanks");
+
+    const stored = await env.DB.prepare("SELECT data FROM contact_messages WHERE email = ?1")
+      .bind("form@example.com")
+      .first<{ data: string }>();
+    expect(JSON.parse(stored!.data)).toEqual({ source: "footer" });
+  });
+
+  it("validates data against the form schema and stores it typed", async () => {
+    await env.DB.prepare(
+      `INSERT INTO forms (id, name, schema, created_at, updated_at)
+       VALUES ('order', 'Order enquiry', ?1, 0, 0)`,
+    )
+      .bind(
+        JSON.stringify({
+          fields: [
+            { name: "order_id", type: "text", required: true },
+            { name: "quantity", type: "number", min: 1 },
+            { name: "gift", type: "checkbox" },
+          ],
+        }),
+      )
+      .run();
+    const submit = (data: Record<string, string>) =>
+      SELF.fetch("http://example.com/api/v1/contact-us/", {
+        method: "POST",
+        headers: { Origin: "http://localhost:5173" },
+        body: new URLSearchParams({
+          form_id: "order",
+          category: "OTHER",
+          email: "orders@example.com",
+          name: "Order User",
+          message: "Where is my order?",
+          "cf-turnstile-response": "test-token",
+          ...data,
+        }),
+        redirect: "manual",
+      });
+
+    fetchMock
+      .get("https://test.com")
+      .intercept({ method: "POST", path: "/turnstile/v0/siteverify" })
+      .reply(200, JSON.stringify({ success: true, challenge_ts: new Date().toISOString() }))
+      .times(2);
+
+    const invalid = await submit({ "data[quantity]": "0" });
+    const problem: any = await invalid.json();
+    expect(invalid.status).toBe(400);
+    expect(problem.errors.map((e: any) => e.field)).toEqual(["data.order_id", "data.quantity"]);
+
+    const valid = await submit({ "data[order_id]": "A-1", "data[quantity]": "2" });
+    expect(valid.status).toBe(303);
+
+    const stored = await env.DB.prepare(
+      "SELECT data, form_id FROM contact_messages WHERE email = ?1",
+    )
+      .bind("orders@example.com")
+      .first<{ data: string; form_id: string }>();
+    expect(stored!.form_id).toBe("order");
+    expect(JSON.parse(stored!.data)).toEqual({ order_id: "A-1", quantity: 2, gift: false });
+  });
+
+  it("accepts submissions for a site from that site's origin", async () => {
+    await env.DB.prepare(
+      `INSERT INTO sites (id, name, allowed_origins, categories, created_at, updated_at)
+       VALUES ('shop', 'Shop', '["https://shop.example.com"]', '["ERROR"]', 0, 0)`,
+    ).run();
+    fetchMock
+      .get("https://test.com")
+      .intercept({ method: "POST", path: "/turnstile/v0/siteverify" })
+      .reply(200, JSON.stringify({ success: true, challenge_ts: new Date().toISOString() }));
+
+    const response = await SELF.fetch("http://example.com/api/v1/sites/shop/contact-us/", {
+      method: "POST",
+      headers: {
+        "Content-Type": "application/json",
+        Origin: "https://shop.example.com",
+      },
+      body: JSON.stringify({
+        category: "ERROR",
+        email: "shopper@example.com",
+        name: "Shopper",
+        message: "Checkout is broken",
+        token: "test-token",
+      }),
+    });
+
+    expect(response.status).toBe(200);
+    expect(response.headers.get("Access-Control-Allow-Origin")).toBe("https://shop.example.com");
+    const stored = await env.DB.prepare("SELECT site_id FROM contact_messages WHERE email = ?1")
+      .bind("shopper@example.com")
+      .first<{ site_id: string }>();
+    expect(stored!.site_id).toBe("shop");
+  });
+
+  it("rejects submissions for an unknown site", async () => {
+    const response = await SELF.fetch("http://example.com/api/v1/sites/missing/contact-us/", {
+      method: "POST",
+      headers: {
+        "Content-Type": "application/json",
+        Origin: "http://localhost:5173",
+      },
+      body: JSON.stringify({}),
+    });
+
+    const problem: any = await response.json();
+    expect(response.status).toBe(404);
+    expect(problem.code).toBe("site_not_found");
+    expect(response.headers.get("Access-Control-Allow-Origin")).toBe("http://localhost:5173");
+  });
+
+  it("replays repeated requests with the same Idempotency-Key", async () => {
+    fetchMock
+      .get("https://test.com")
+      .intercept({ method: "POST", path: "/turnstile/v0/siteverify" })
+      .reply(200, JSON.stringify({ success: true, challenge_ts: new Date().toISOString() }));
+
+    const submit = (message: string) =>
+      SELF.fetch("http://example.com/api/v1/contact-us/", {
+        method: "POST",
+        headers: {
+          "Content-Type": "application/json",
+          Origin: "http://localhost:5173",
+          "Idempotency-Key": "integration-test-key",
+        },
+        body: JSON.stringify({
+          category: "IDEA",
+          email: "idempotent@example.com",
+          name: "Test User",
+          message,
+          token: "test-token",
+        }),
+      });
+
+    const first = await submit("Submitted twice");
+    expect(first.status).toBe(200);
+
+    // No Turnstile interceptor is registered for the replay.
+    const replay = await submit("Submitted twice");
+    expect(replay.status).toBe(200);
+    expect(replay.headers.get("Idempotent-Replayed")).toBe("true");
+
+    const stored = await env.DB.prepare(
+      "SELECT COUNT(*) AS count FROM contact_messages WHERE email = ?1",
+    )
+      .bind("idempotent@example.com")
+      .first<{ count: number }>();
+    expect(stored?.count).toBe(1);
+
+    const reused = await submit("A different message");
+    expect(reused.status).toBe(422);
+  });
+
+  it("serves the form configuration with an ETag", async () => {
+    const response = await SELF.fetch("http://example.com/api/v1/contact-us/config/", {
+      headers: { Origin: "http://localhost:5173" },
+    });
+
+    const data: any = await response.json();
+    const etag = response.headers.get("ETag");
+    expect(response.status).toBe(200);
+    expect(response.headers.get("Cache-Control")).toBe("public, max-age=300");
+    expect(etag).toMatch(/^"[0-9a-f]{32}"$/);
+    expect(data.data.categories.map((c: any) => c.slug)).toContain("ERROR");
+    expect(data.data.fields.message.max_length).toBe(5000);
+    expect(data.data.turnstile_site_key).toBe("test-site-key");
+
+    const revalidated = await SELF.fetch("http://example.com/api/v1/contact-us/config/", {
+      headers: { Origin: "http://localhost:5173", "If-None-Match": etag! },
+    });
+    expect(revalidated.status).toBe(304);
+    expect(revalidated.headers.get("ETag")).toBe(etag);
+  });
+
+  it("rejects listing messages without an API key", async () => {
+    const response = await SELF.fetch("http://example.com/api/v1/contact-us/");
+
+    expect(response.status).toBe(401);
+    expect(response.headers.get("WWW-Authenticate")).toBe("Bearer");
+  });
+
+  it("issues signed form tokens", async () => {
+    const response = await SELF.fetch("http://example.com/api/v1/contact-us/form-token/", {
+      headers: { Origin: "http://localhost:5173" },
+    });
+
+    const data: any = await response.json();
+    expect(response.status).toBe(200);
+    expect(response.headers.get("Cache-Control")).toBe("no-store");
+    expect(data.data.token).toMatch(/^\d+\.[0-9a-f]{32}\.[0-9a-f]{64}$/);
+    expect(data.data.expires_at - data.data.valid_from).toBe(3600 - 3);
+  });
+
+  it("issues form tokens to a site's own origin", async () => {
+    await env.DB.prepare(
+      `INSERT OR IGNORE INTO sites (id, name, allowed_origins, categories, created_at, updated_at)
+       VALUES ('shop', 'Shop', '["https://shop.example.com"]', '["ERROR"]', 0, 0)`,
+    ).run();
+
+    const response = await SELF.fetch("http://example.com/api/v1/sites/shop/contact-us/form-token/", {
+      headers: { Origin: "https://shop.example.com" },
+    });
+
+    expect(response.status).toBe(200);
+    expect(response.headers.get("Access-Control-Allow-Origin")).toBe("https://shop.example.com");
+  });
+
+  it("rejects listing messages with an invalid API key", async () => {
+    const response = await SELF.fetch("http://example.com/api/v1/contact-us/", {
+      headers: { Authorization: "Bearer wrong-key" },
+    });
+
+    expect(response.status).toBe(401);
+  });
+
+  it("lists messages with a valid API key", async () => {
+    const response = await SELF.fetch("http://example.com/api/v1/contact-us/?limit=5", {
+      headers: { Authorization: "Bearer test-admin-key" },
+    });
+
+    const data: any = await response.json();
+    expect(response.status).toBe(200);
+    expect(data.data.limit).toBe(5);
+    expect(Array.isArray(data.data.messages)).toBe(true);
+  });
+
+  it("returns 404 for an unknown message id", async () => {
+    const response = await SELF.fetch("http://example.com/api/v1/contact-us/does-not-exist/", {
+      headers: { Authorization: "Bearer test-admin-key" },
+    });
+
+    expect(response.status).toBe(404);
+  });
+
+  it("moves a message through the status lifecycle", async () => {
+    const list = await SELF.fetch("http://example.com/api/v1/contact-us/?limit=1", {
+      headers: { Authorization: "Bearer test-write-key" },
+    });
+    const listData: any = await list.json();
+    const id = listData.data.messages[0].id;
+    expect(listData.data.messages[0].status).toBe("NEW");
+
+    const readOnly = await SELF.fetch(`http://example.com/api/v1/contact-us/${id}/status/`, {
+      method: "PATCH",
+      headers: { Authorization: "Bearer test-admin-key", "Content-Type": "application/json" },
+      body: JSON.stringify({ status: "RESOLVED" }),
+    });
+    expect(readOnly.status).toBe(403);
+
+    const resolved = await SELF.fetch(`http://example.com/api/v1/contact-us/${id}/status/`, {
+      method: "PATCH",
+      headers: { Authorization: "Bearer test-write-key", "Content-Type": "application/json" },
+      body: JSON.stringify({ status: "RESOLVED" }),
+    });
+    const resolvedData: any = await resolved.json();
+    expect(resolved.status).toBe(200);
+    expect(resolvedData.data.status).toBe("RESOLVED");
+    expect(resolvedData.data.resolved_at).not.toBeNull();
+
+    const illegal = await SELF.fetch(`http://example.com/api/v1/contact-us/${id}/status/`, {
+      method: "PATCH",
+      headers: { Authorization: "Bearer test-write-key", "Content-Type": "application/json" },
+      body: JSON.stringify({ status: "SPAM" }),
+    });
+    expect(illegal.status).toBe(409);
+  });
+
+  it("manages categories through the admin API", async () => {
+    const admin = (method: string, path: string, body?: unknown) =>
+      SELF.fetch(`http://example.com/api/v1/categories/${path}`, {
+        method,
+        headers: { Authorization: "Bearer test-write-key", "Content-Type": "application/json" },
+        body: body === undefined ? undefined : JSON.stringify(body),
+      });
+
+    const seeded: any = await (await admin("GET", "")).json();
+    expect(seeded.data.categories.map((c: any) => c.slug)).toEqual([
+      "ERROR",
+      "IDEA",
+      "TESTIMONIAL",
+      "OTHER",
+    ]);
+
+    const created = await admin("POST", "", {
+      slug: "billing",
+      display_name: "Billing",
+      default_priority: "high",
+    });
+    const createdData: any = await created.json();
+    expect(created.status).toBe(200);
+    expect(createdData.data.slug).toBe("BILLING");
+    expect(createdData.data.default_priority).toBe("HIGH");
+    expect((await admin("POST", "", { slug: "BILLING", display_name: "Again" })).status).toBe(409);
+
+    fetchMock
+      .get("https://test.com")
+      .intercept({ method: "POST", path: "/turnstile/v0/siteverify" })
+      .reply(200, JSON.stringify({ success: true, challenge_ts: new Date().toISOString() }));
+    const submitted = await SELF.fetch("http://example.com/api/v1/contact-us/", {
+      method: "POST",
+      headers: { "Content-Type": "application/json", Origin: "http://localhost:5173" },
+      body: JSON.stringify({
+        category: "Billing",
+        email: "billing@example.com",
+        name: "Test User",
+        message: "I was charged twice",
+        token: "test-token",
+      }),
+    });
+    expect(submitted.status).toBe(200);
+    const stored = await env.DB.prepare(
+      "SELECT category, priority FROM contact_messages WHERE email = ?1",
+    )
+      .bind("billing@example.com")
+      .first<{ category: string; priority: string }>();
+    expect(stored).toEqual({ category: "BILLING", priority: "HIGH" });
+
+    expect((await admin("DELETE", "billing/")).status).toBe(409);
+    const deactivated = await admin("PUT", "billing/", { display_name: "Billing", active: false });
+    const deactivatedData: any = await deactivated.json();
+    expect(deactivated.status).toBe(200);
+    expect(deactivatedData.data.active).toBe(false);
+  });
+});
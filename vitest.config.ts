@@ -13,7 +13,17 @@ export default defineWorkersConfig(async () => {
               ENVIRONMENT: "test",
               TURNSTILE_SITEVERIFY_URL: "https://test.com/turnstile/v0/siteverify",
               TURNSTILE_SECRET_KEY: "test-secret-key",
+              TURNSTILE_SITE_KEY: "test-site-key",
+              FORM_TOKEN_SECRET: "test-form-token-secret",
               ALLOWED_ORIGINS: "http://localhost:5173",
+              THANK_YOU_URL: "http://localhost:5173/thanks",
+              // sha256("test-admin-key") and sha256("test-write-key")
+              ADMIN_API_KEYS:
+                "reader:read:944650a7cd0f9e14d5c4fb15edbffb7fa45fb9ed36a4fa9be3d7e5476ae51bd9," +
+                "writer:read+write:04c0218b3c6929c6638fa052827cc353c3e1eb3a432020f686e083aae900d6c4",
+              // Generous limits so the suite's submissions are never throttled
+              RATE_LIMIT_PER_IP: "100/60",
+              RATE_LIMIT_PER_EMAIL: "100/60",
               TEST_MIGRATIONS: migrations,
             },
             d1Databases: {